@@ -9,9 +9,14 @@ import './web-components/ecdh-non-shareable-keygen'
 import './web-components/snack-bar'
 import './web-components/data-table'
 import './web-components/new-secret'
+import './web-components/edit-secret'
+import './web-components/rollback-secret'
 import './web-components/connect-account'
 import './web-components/downloadable-key'
 import './web-components/add-member'
+import './web-components/member-environments'
+import './web-components/rotate-key'
+import './web-components/rotate-account-key'
 import '@github/relative-time-element';
 
 // Front end enhancement
@@ -28,8 +33,6 @@ import '@github/relative-time-element';
 //import '../templates/members/delete_member'
 
 // Misc.
-import './remember-form'
-import './filter-trigger'
 import './select-div'
 
 import '@primer/view-components/app/components/primer/tab_container_component';
@@ -0,0 +1,43 @@
+import { SideDrawer } from './side-drawer'
+
+/**
+ * Change which environments a vault member has access to.
+ * 
+ * - The ticked environments are sent as a comma separated list of ids,
+ *   the same as when the member was added.
+ */
+class MemberEnvironments extends SideDrawer {
+
+    constructor() {
+        super()
+
+        const form = this.closest('form')
+
+        if(form) {
+            form.addEventListener('submit', () => {
+                this.parseEnvironments()
+            })
+        } else {
+            console.error('Could not find required elements')
+        }
+    }
+
+    private parseEnvironments() {
+        var ids = ''
+        this.querySelectorAll("input[type='checkbox']:checked").forEach((item) => {
+            if(item instanceof HTMLInputElement) {
+                ids += item.value + ','
+            }
+        })
+        const envHiddenField = this.querySelector("input[name='environments']")
+        if(envHiddenField instanceof HTMLInputElement) {
+            envHiddenField.value = ids
+        }
+    }
+}
+
+document.addEventListener('readystatechange', () => {
+    if (document.readyState == 'complete') {
+        customElements.define('member-environments', MemberEnvironments);
+    }
+})
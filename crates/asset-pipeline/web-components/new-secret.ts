@@ -151,7 +151,9 @@ class NewSecret extends SideDrawer {
                     secrets: [
                         secret
                     ],
-                    publicEcdhKey: etherealPublicKeyBase64
+                    publicEcdhKey: etherealPublicKeyBase64,
+                    // A service account rotating its key is listed once per key.
+                    serviceAccountEcdhPublicKey: serviceAccount.publicEcdhKey
                 }
     
                 createSecretsRequest.accountSecrets.push(serviceAccountSecrets)
@@ -0,0 +1,141 @@
+import { Vault, Cipher, ByteData, AESKey, ECDHKeyPair, ECDHPublicKey } from '../cryptography/vault'
+import { VaultClient } from '../api.client';
+import { GrpcWebFetchTransport } from "@protobuf-ts/grpcweb-transport";
+import type { RpcOptions } from "@protobuf-ts/runtime-rpc";
+import { ServiceAccount } from '../api';
+import { SideDrawer } from './side-drawer';
+
+/**
+ * Alice rolls a secret back to an old version.
+ * 
+ * - Alice decrypts the Vault key and uses it to decrypt the old version.
+ * - The old version is re-encrypted for every service account connected to the
+ *   secrets environment.
+ * - The server swaps in the old version and the service account copies in one go.
+ */
+class RollbackSecret extends SideDrawer {
+
+    constructor() {
+        super()
+
+        this.querySelectorAll('[data-version-id]').forEach(button => {
+            button.addEventListener('click', async event => {
+                event.preventDefault()
+                if (button instanceof HTMLElement) {
+                    await this.rollback(button)
+                }
+            })
+        })
+    }
+
+    async rollback(button: HTMLElement) {
+        const form = this.closest('form')
+        const details = this.querySelector('.secret-details')
+        const versionIdInput = this.querySelector('input[name="version_id"]')
+        const serviceAccountSecretsInput = this.querySelector('input[name="service_account_secrets"]')
+
+        if (form instanceof HTMLFormElement && details instanceof HTMLInputElement
+            && versionIdInput instanceof HTMLInputElement
+            && serviceAccountSecretsInput instanceof HTMLInputElement) {
+
+            try {
+                const vaultId = parseInt(details.dataset.vaultId || '0')
+                const environmentId = parseInt(details.dataset.environmentId || '0')
+
+                const vaultKey = await this.decryptSymmetricVaultKey(details)
+
+                const plaintextName = await vaultKey.decrypt(
+                    Cipher.fromString(button.dataset.cipherName || ''))
+                const plaintextValue = await vaultKey.decrypt(
+                    Cipher.fromString(button.dataset.cipherValue || ''))
+
+                // Call back to the server and get the vault details including the
+                // connected service accounts
+                const call = this.getVaultClient().getVault({
+                        vaultId: vaultId
+                    }, this.getRpcOptions()
+                )
+                const vault = await call.response
+
+                const copies = await this.deriveServiceAccountSecrets(vault.serviceAccounts,
+                    plaintextName, plaintextValue, environmentId)
+
+                versionIdInput.value = button.dataset.versionId || ''
+                serviceAccountSecretsInput.value = JSON.stringify(copies)
+                form.submit()
+            } catch (err) {
+                if (err instanceof Error) {
+                    console.log(err.message)
+                }
+            }
+        } else {
+            console.error('Could not find required elements')
+        }
+    }
+
+    async deriveServiceAccountSecrets(serviceAccounts: ServiceAccount[],
+        plaintextName: ByteData, plaintextValue: ByteData, environmentId: number) : Promise<object[]> {
+
+        const etherealKeyPair = await ECDHKeyPair.fromRandom()
+        const etherealPublicKeyData = await etherealKeyPair.publicKey.export()
+
+        const copies : object[] = []
+
+        for (const serviceAccount of serviceAccounts) {
+            if (environmentId == serviceAccount.environmentId) {
+                const serviceAccountECDHPublicKey: ECDHPublicKey = 
+                    await ECDHPublicKey.import(ByteData.fromB64(serviceAccount.publicEcdhKey))
+                const aesKeyAgreement: AESKey = 
+                    await etherealKeyPair.privateKey.deriveAESKey(serviceAccountECDHPublicKey)
+
+                // Associated Data
+                const associatedData = new ByteData(new Uint8Array(4))
+                const view = new DataView(associatedData.arr.buffer)
+                view.setUint32(0, serviceAccount.serviceAccountId, true /* littleEndian */);
+
+                const encryptedName = await aesKeyAgreement.aeadEncrypt(plaintextName, associatedData)
+                const encryptedValue = await aesKeyAgreement.aeadEncrypt(plaintextValue, associatedData)
+
+                copies.push({
+                    service_account_id: serviceAccount.serviceAccountId,
+                    service_account_ecdh_public_key: serviceAccount.publicEcdhKey,
+                    public_ecdh_key: etherealPublicKeyData.b64,
+                    encrypted_name: encryptedName.string,
+                    encrypted_secret_value: encryptedValue.string
+                })
+            }
+        }
+
+        return copies
+    }
+
+    private getVaultClient(): VaultClient {
+        let transport = new GrpcWebFetchTransport({
+            baseUrl: window.location.protocol + '//' + window.location.host
+        });
+        return new VaultClient(transport)
+    }
+
+    private getRpcOptions() : RpcOptions {
+        const meta = {}
+        meta['authentication-type'] = 'cookie';
+
+        let options: RpcOptions = {
+            meta: meta
+        }
+        return options
+    }
+
+    private async decryptSymmetricVaultKey(details: HTMLInputElement): Promise<AESKey> {
+        const vaultKeyCipher = Cipher.fromString(details.dataset.encryptedVaultKey || '')
+        const ecdhPublicKey = await ECDHPublicKey.import(
+            ByteData.fromB64(details.dataset.ecdhPublicKey || ''))
+        return await Vault.decryptVaultKey(vaultKeyCipher, ecdhPublicKey)
+    }
+}
+
+document.addEventListener('readystatechange', () => {
+    if (document.readyState == 'complete') {
+        customElements.define('rollback-secret', RollbackSecret);
+    }
+})
@@ -0,0 +1,145 @@
+import { Vault, Cipher, ByteData, AESKey, ECDHKeyPair, ECDHPublicKey } from '../cryptography/vault'
+import { VaultClient } from '../api.client';
+import { GrpcWebFetchTransport } from "@protobuf-ts/grpcweb-transport";
+import type { RpcOptions } from "@protobuf-ts/runtime-rpc";
+import { GetVaultResponse } from '../api';
+import { SideDrawer } from './side-drawer'
+
+/**
+ * Rotate the key of a service account whose private key may have leaked.
+ *
+ * - A new key pair is created and the private key wrapped with the users key,
+ *   the same as when the service account was created.
+ * - If the service account is connected to a vault the secrets of its
+ *   environment are decrypted and encrypted for the new public key.
+ * - The server swaps the keys, the old one keeps working until the grace
+ *   period is over.
+ */
+class RotateAccountKey extends SideDrawer {
+
+    constructor() {
+        super()
+
+        const serviceAccountIdInput = this.querySelector('.rotate-service-account-id')
+
+        if(serviceAccountIdInput instanceof HTMLInputElement) {
+            const serviceAccountId = parseInt(serviceAccountIdInput.value)
+            const rotateButton = this.querySelector('#rotate-account-key-button-' + serviceAccountId)
+
+            if(rotateButton) {
+                rotateButton.addEventListener('click', async event => {
+                    event.preventDefault()
+                    await this.rotateKey(serviceAccountId)
+                })
+            } else {
+                console.error('Could not find required elements')
+            }
+        } else {
+            console.error('Could not find required elements')
+        }
+    }
+
+    private async rotateKey(serviceAccountId: number) {
+        const form = document.getElementById('rotate-account-key-form-' + serviceAccountId)
+        const vaultIdInput = this.querySelector('.rotate-vault-id')
+        const environmentIdInput = this.querySelector('.rotate-environment-id')
+        const publicKeyInput = this.querySelector('.rotate-public-key')
+        const privateKeyInput = this.querySelector('.rotate-encrypted-private-key')
+        const publicEcdhKeyInput = this.querySelector('.rotate-public-ecdh-key')
+        const secretsInput = this.querySelector('.rotate-secrets')
+
+        if(form instanceof HTMLFormElement
+            && vaultIdInput instanceof HTMLInputElement
+            && environmentIdInput instanceof HTMLInputElement
+            && publicKeyInput instanceof HTMLInputElement
+            && privateKeyInput instanceof HTMLInputElement
+            && publicEcdhKeyInput instanceof HTMLInputElement
+            && secretsInput instanceof HTMLInputElement) {
+
+            try {
+                const ecdhKeyPair = await ECDHKeyPair.fromRandom()
+                const userAesKey = await AESKey.fromBarricade()
+                const exportedPrivateKey = await ecdhKeyPair.privateKey.export()
+                const wrappedPrivateKey = await userAesKey.encrypt(exportedPrivateKey)
+                const publicKey = await ecdhKeyPair.publicKey.export()
+
+                publicKeyInput.value = publicKey.b64
+                privateKeyInput.value = wrappedPrivateKey.string
+
+                // Not connected to a vault, so there are no secrets to copy.
+                if(vaultIdInput.value != '' && environmentIdInput.value != '') {
+                    const call = this.getVaultClient().getVault({
+                            vaultId: parseInt(vaultIdInput.value)
+                        }, this.getRpcOptions()
+                    )
+                    const vault: GetVaultResponse = await call.response
+
+                    const etherealKeyPair = await ECDHKeyPair.fromRandom()
+                    const agreementKey = await etherealKeyPair.privateKey.deriveAESKey(ecdhKeyPair.publicKey)
+                    const etherealPublicKey = await etherealKeyPair.publicKey.export()
+
+                    publicEcdhKeyInput.value = etherealPublicKey.b64
+                    secretsInput.value = JSON.stringify(await this.reencryptSecrets(vault,
+                        agreementKey, serviceAccountId, parseInt(environmentIdInput.value)))
+                }
+
+                form.submit()
+            } catch (err) {
+                if (err instanceof Error) {
+                    console.log(err.message)
+                }
+            }
+        }
+    }
+
+    private async reencryptSecrets(vault: GetVaultResponse, agreementKey: AESKey,
+        serviceAccountId: number, environmentId: number) {
+
+        const wrappedVaultKey = Cipher.fromString(vault.userVaultEncryptedVaultKey)
+        const ecdhUserPublicKey = await ECDHPublicKey.import(ByteData.fromB64(vault.userVaultPublicEcdhKey))
+        const vaultKey = await Vault.decryptVaultKey(wrappedVaultKey, ecdhUserPublicKey)
+
+        // Associated Data
+        const associatedData = new ByteData(new Uint8Array(4))
+        const view = new DataView(associatedData.arr.buffer)
+        view.setUint32(0, serviceAccountId, true /* littleEndian */);
+
+        const secrets = []
+        for (const secret of vault.secrets) {
+            if(secret.environmentId == environmentId) {
+                const name = await vaultKey.decrypt(Cipher.fromString(secret.encryptedName))
+                const value = await vaultKey.decrypt(Cipher.fromString(secret.encryptedSecretValue))
+
+                secrets.push({
+                    name_blind_index: secret.nameBlindIndex,
+                    encrypted_name: (await agreementKey.aeadEncrypt(name, associatedData)).string,
+                    encrypted_secret_value: (await agreementKey.aeadEncrypt(value, associatedData)).string
+                })
+            }
+        }
+        return secrets
+    }
+
+    private getVaultClient(): VaultClient {
+        let transport = new GrpcWebFetchTransport({
+            baseUrl: window.location.protocol + '//' + window.location.host
+        });
+        return new VaultClient(transport)
+    }
+
+    private getRpcOptions(): RpcOptions {
+        const meta = {}
+        meta['authentication-type'] = 'cookie';
+
+        let options: RpcOptions = {
+            meta: meta
+        }
+        return options
+    }
+}
+
+document.addEventListener('readystatechange', () => {
+    if (document.readyState == 'complete') {
+        customElements.define('rotate-account-key', RotateAccountKey);
+    }
+})
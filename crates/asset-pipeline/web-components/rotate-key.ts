@@ -0,0 +1,112 @@
+import { Vault, Cipher, ByteData, AESKey, ECDHKeyPair, ECDHPublicKey } from '../cryptography/vault'
+import { SideDrawer } from './side-drawer'
+
+/**
+ * Alice rotates the Vault key after Bob has been removed.
+ *
+ * - Alice decrypts the current Vault key and creates a new one.
+ * - Every secret, and every version of it, is decrypted with the current key
+ *   and encrypted with the new one.
+ * - The new key is wrapped for each remaining member.
+ * - The server swaps all of it in one go, so Bob's copy of the old key
+ *   no longer decrypts anything.
+ */
+class RotateKey extends SideDrawer {
+
+    constructor() {
+        super()
+
+        const rotateButton = this.querySelector('#rotate-key-button')
+
+        if(rotateButton) {
+            rotateButton.addEventListener('click', async event => {
+                event.preventDefault()
+                await this.rotateKey()
+            })
+        } else {
+            console.error('Could not find required elements')
+        }
+    }
+
+    private async rotateKey() {
+        const form = document.getElementById('rotate-key-form')
+        const membersInput = this.querySelector('#rotated-members')
+        const secretsInput = this.querySelector('#rotated-secrets')
+        const versionsInput = this.querySelector('#rotated-versions')
+
+        if(form instanceof HTMLFormElement
+            && membersInput instanceof HTMLInputElement
+            && secretsInput instanceof HTMLInputElement
+            && versionsInput instanceof HTMLInputElement) {
+
+            try {
+                const vaultKey = await this.decryptSymmetricVaultKey()
+                const newVaultKey = await AESKey.fromRandom()
+
+                membersInput.value = JSON.stringify(await this.wrapForMembers(newVaultKey))
+                secretsInput.value = JSON.stringify(
+                    await this.reencrypt('.rotate-secret', vaultKey, newVaultKey))
+                versionsInput.value = JSON.stringify(
+                    await this.reencrypt('.rotate-version', vaultKey, newVaultKey))
+
+                form.submit()
+            } catch (err) {
+                if (err instanceof Error) {
+                    console.log(err.message)
+                }
+            }
+        }
+    }
+
+    private async wrapForMembers(newVaultKey: AESKey) {
+        const members = []
+        for (const item of Array.from(this.querySelectorAll('.rotate-member'))) {
+            if(item instanceof HTMLElement) {
+                const memberECDHPublicKey = await ECDHPublicKey.import(
+                    ByteData.fromB64(item.dataset.ecdhPubKey || ''))
+                const epherealKeyPair = await ECDHKeyPair.fromRandom()
+                const aesKey = await epherealKeyPair.privateKey.deriveAESKey(memberECDHPublicKey)
+                const wrappedVaultKey = await aesKey.wrap(newVaultKey)
+                const ecdhPublicKeyData = await epherealKeyPair.publicKey.export()
+
+                members.push({
+                    user_id: parseInt(item.dataset.userId || '0'),
+                    ecdh_public_key: ecdhPublicKeyData.b64,
+                    encrypted_vault_key: wrappedVaultKey.string
+                })
+            }
+        }
+        return members
+    }
+
+    private async reencrypt(selector: string, vaultKey: AESKey, newVaultKey: AESKey) {
+        const ciphers = []
+        for (const item of Array.from(this.querySelectorAll(selector))) {
+            if(item instanceof HTMLElement) {
+                const name = await vaultKey.decrypt(Cipher.fromString(item.dataset.name || ''))
+                const secret = await vaultKey.decrypt(Cipher.fromString(item.dataset.secret || ''))
+
+                ciphers.push({
+                    id: parseInt(item.dataset.id || '0'),
+                    name: (await newVaultKey.encrypt(name)).string,
+                    secret: (await newVaultKey.encrypt(secret)).string
+                })
+            }
+        }
+        return ciphers
+    }
+
+    private async decryptSymmetricVaultKey(): Promise<AESKey> {
+        const ecdhPublicKeyInput = this.querySelector('#user-vault-ecdh-public-key') as HTMLInputElement
+        const encryptedVaultKeyInput = this.querySelector('#encrypted-vault-key') as HTMLInputElement
+        const vaultKeyCipher = Cipher.fromString(encryptedVaultKeyInput.value)
+        const ecdhPublicKey = await ECDHPublicKey.import(ByteData.fromB64(ecdhPublicKeyInput.value))
+        return await Vault.decryptVaultKey(vaultKeyCipher, ecdhPublicKey)
+    }
+}
+
+document.addEventListener('readystatechange', () => {
+    if (document.readyState == 'complete') {
+        customElements.define('rotate-key', RotateKey);
+    }
+})
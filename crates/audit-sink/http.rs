@@ -0,0 +1,45 @@
+use crate::{AuditEvent, Error, Options};
+use reqwest::{Certificate, Client, Url};
+use std::time::Duration;
+
+pub struct HttpSink {
+    url: Url,
+    token: Option<String>,
+    client: Client,
+}
+
+impl HttpSink {
+    pub fn new(url: Url, options: Options) -> Result<HttpSink, Error> {
+        let mut builder = Client::builder().timeout(Duration::from_secs(30));
+
+        if let Some(pem) = &options.ca_certificates {
+            let certificate = Certificate::from_pem(pem)?;
+            builder = builder.add_root_certificate(certificate);
+        }
+
+        Ok(HttpSink {
+            url,
+            token: options.token,
+            client: builder.build()?,
+        })
+    }
+
+    // The events are posted as one JSON array.
+    pub async fn send(&mut self, events: &[AuditEvent]) -> Result<(), Error> {
+        let mut request = self.client.post(self.url.clone()).json(events);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await?;
+
+        if !response.status().is_success() {
+            return Err(Error::Http(format!(
+                "The collector responded with {}",
+                response.status()
+            )));
+        }
+
+        Ok(())
+    }
+}
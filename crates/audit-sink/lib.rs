@@ -0,0 +1,122 @@
+//! Sends audit events on to a collector i.e. a SIEM, either as RFC 5424
+//! syslog over UDP, TCP or TLS, or as JSON over HTTP.
+mod http;
+mod syslog;
+
+use serde::Serialize;
+use std::fmt;
+
+pub use http::HttpSink;
+pub use syslog::{SyslogSink, Transport};
+
+// One entry from the audit trail.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct AuditEvent {
+    pub id: i32,
+    pub organisation_id: i32,
+    pub sequence: i32,
+    // ISO 8601
+    pub created_at: String,
+    pub user_id: Option<i32>,
+    pub service_account_id: Option<i32>,
+    pub access_type: String,
+    pub action: String,
+    pub description: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub vault_id: Option<i32>,
+    pub secret_id: Option<i32>,
+    pub environment_id: Option<i32>,
+    pub target_user_id: Option<i32>,
+    pub target_service_account_id: Option<i32>,
+    pub hash: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    // PEM certificates to trust as well as the usual roots, i.e. for a
+    // collector with a private CA.
+    pub ca_certificates: Option<Vec<u8>>,
+    // Sent as a bearer token to HTTP collectors.
+    pub token: Option<String>,
+    // Who we are in syslog messages.
+    pub hostname: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Config(String),
+    Io(std::io::Error),
+    Http(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Config(cause) => write!(f, "Audit Sink Config: {}", cause),
+            Error::Io(cause) => write!(f, "Audit Sink IO: {}", cause),
+            Error::Http(cause) => write!(f, "Audit Sink HTTP: {}", cause),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Error {
+        Error::Http(err.to_string())
+    }
+}
+
+pub enum Sink {
+    Syslog(SyslogSink),
+    Http(HttpSink),
+}
+
+impl Sink {
+    // The scheme picks the sink i.e. udp://host:514, tcp://host:601,
+    // tls://host:6514 or https://siem.example.com/collect
+    pub fn new(url: &str, options: Options) -> Result<Sink, Error> {
+        let parsed = reqwest::Url::parse(url).map_err(|e| Error::Config(e.to_string()))?;
+
+        let transport = match parsed.scheme() {
+            "udp" => Transport::Udp,
+            "tcp" => Transport::Tcp,
+            "tls" => Transport::Tls,
+            "http" | "https" => return Ok(Sink::Http(HttpSink::new(parsed, options)?)),
+            scheme => {
+                return Err(Error::Config(format!(
+                    "{} isn't supported, use udp, tcp, tls, http or https",
+                    scheme
+                )))
+            }
+        };
+
+        let host = parsed
+            .host_str()
+            .ok_or_else(|| Error::Config("The collector needs a host".to_string()))?;
+        let port = parsed.port().unwrap_or_else(|| transport.default_port());
+
+        Ok(Sink::Syslog(SyslogSink::new(
+            transport, host, port, options,
+        )?))
+    }
+
+    // Either every event was accepted or we return an error and the caller
+    // should try them all again later.
+    pub async fn send(&mut self, events: &[AuditEvent]) -> Result<(), Error> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        match self {
+            Sink::Syslog(sink) => sink.send(events).await,
+            Sink::Http(sink) => sink.send(events).await,
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use crate::{AuditEvent, Error, Options};
+use std::convert::TryFrom;
+use std::io::{BufReader, ErrorKind};
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpStream, UdpSocket};
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls::{self, ClientConfig, OwnedTrustAnchor, RootCertStore, ServerName};
+use tokio_rustls::TlsConnector;
+
+// log audit (13) * 8 + notice (5)
+const PRIORITY: u8 = 109;
+const APP_NAME: &str = "cloak";
+// The IANA private enterprise number for examples, there's no registered one.
+const SD_ID: &str = "audit@32473";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Transport {
+    Udp,
+    Tcp,
+    Tls,
+}
+
+impl Transport {
+    // From RFC 5426, RFC 6587 and RFC 5425.
+    pub fn default_port(&self) -> u16 {
+        match self {
+            Transport::Udp => 514,
+            Transport::Tcp => 601,
+            Transport::Tls => 6514,
+        }
+    }
+}
+
+enum Connection {
+    Udp(UdpSocket),
+    Tcp(TcpStream),
+    Tls(Box<TlsStream<TcpStream>>),
+}
+
+pub struct SyslogSink {
+    transport: Transport,
+    host: String,
+    port: u16,
+    hostname: String,
+    tls: Option<TlsConnector>,
+    // Made when we first send and dropped on an error so we reconnect.
+    connection: Option<Connection>,
+}
+
+impl SyslogSink {
+    pub fn new(
+        transport: Transport,
+        host: &str,
+        port: u16,
+        options: Options,
+    ) -> Result<SyslogSink, Error> {
+        let tls = if transport == Transport::Tls {
+            Some(tls_connector(options.ca_certificates.as_deref())?)
+        } else {
+            None
+        };
+
+        Ok(SyslogSink {
+            transport,
+            host: host.to_string(),
+            port,
+            hostname: options.hostname.unwrap_or_else(|| "-".to_string()),
+            tls,
+            connection: None,
+        })
+    }
+
+    pub async fn send(&mut self, events: &[AuditEvent]) -> Result<(), Error> {
+        let result = self.send_all(events).await;
+        if result.is_err() {
+            self.connection = None;
+        }
+        result
+    }
+
+    async fn send_all(&mut self, events: &[AuditEvent]) -> Result<(), Error> {
+        if self.connection.is_none() {
+            self.connection = Some(self.connect().await?);
+        }
+
+        for event in events {
+            let message = format_message(event, &self.hostname);
+            match self.connection.as_mut() {
+                // One message per datagram.
+                Some(Connection::Udp(socket)) => {
+                    socket.send(message.as_bytes()).await?;
+                }
+                // Octet counting, so messages can contain new lines.
+                Some(Connection::Tcp(stream)) => {
+                    let frame = format!("{} {}", message.len(), message);
+                    stream.write_all(frame.as_bytes()).await?;
+                }
+                Some(Connection::Tls(stream)) => {
+                    let frame = format!("{} {}", message.len(), message);
+                    stream.write_all(frame.as_bytes()).await?;
+                }
+                None => unreachable!("connected above"),
+            }
+        }
+
+        match self.connection.as_mut() {
+            Some(Connection::Tcp(stream)) => stream.flush().await?,
+            Some(Connection::Tls(stream)) => stream.flush().await?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    async fn connect(&self) -> Result<Connection, Error> {
+        let address = (self.host.as_str(), self.port);
+        match self.transport {
+            Transport::Udp => {
+                let socket = UdpSocket::bind("0.0.0.0:0").await?;
+                socket.connect(address).await?;
+                Ok(Connection::Udp(socket))
+            }
+            Transport::Tcp => Ok(Connection::Tcp(TcpStream::connect(address).await?)),
+            Transport::Tls => {
+                let stream = TcpStream::connect(address).await?;
+                let server_name = ServerName::try_from(self.host.as_str())
+                    .map_err(|e| Error::Config(e.to_string()))?;
+                let connector = self.tls.clone().expect("made for TLS in new");
+                let stream = connector.connect(server_name, stream).await?;
+                Ok(Connection::Tls(Box::new(stream)))
+            }
+        }
+    }
+}
+
+fn tls_connector(ca_certificates: Option<&[u8]>) -> Result<TlsConnector, Error> {
+    let mut roots = RootCertStore::empty();
+    roots.add_server_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.0.iter().map(|anchor| {
+        OwnedTrustAnchor::from_subject_spki_name_constraints(
+            anchor.subject,
+            anchor.spki,
+            anchor.name_constraints,
+        )
+    }));
+
+    if let Some(pem) = ca_certificates {
+        let certificates = rustls_pemfile::certs(&mut BufReader::new(pem))
+            .map_err(|e| Error::Config(e.to_string()))?;
+        for certificate in certificates {
+            roots
+                .add(&rustls::Certificate(certificate))
+                .map_err(|e| Error::Io(std::io::Error::new(ErrorKind::InvalidData, e)))?;
+        }
+    }
+
+    let config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+
+    Ok(TlsConnector::from(Arc::new(config)))
+}
+
+// <PRI>VERSION TIMESTAMP HOSTNAME APP-NAME PROCID MSGID [SD] BOM MSG
+pub fn format_message(event: &AuditEvent, hostname: &str) -> String {
+    let mut params = vec![
+        ("organisation_id", event.organisation_id.to_string()),
+        ("sequence", event.sequence.to_string()),
+        ("access_type", event.access_type.clone()),
+    ];
+    let optional = [
+        ("user_id", event.user_id.map(|id| id.to_string())),
+        (
+            "service_account_id",
+            event.service_account_id.map(|id| id.to_string()),
+        ),
+        ("ip_address", event.ip_address.clone()),
+        ("user_agent", event.user_agent.clone()),
+        ("vault_id", event.vault_id.map(|id| id.to_string())),
+        ("secret_id", event.secret_id.map(|id| id.to_string())),
+        (
+            "environment_id",
+            event.environment_id.map(|id| id.to_string()),
+        ),
+        (
+            "target_user_id",
+            event.target_user_id.map(|id| id.to_string()),
+        ),
+        (
+            "target_service_account_id",
+            event.target_service_account_id.map(|id| id.to_string()),
+        ),
+    ];
+    for (name, value) in optional {
+        if let Some(value) = value {
+            params.push((name, value));
+        }
+    }
+    params.push(("hash", event.hash.clone()));
+
+    let structured_data: String = params
+        .iter()
+        .map(|(name, value)| format!(" {}=\"{}\"", name, escape_param(value)))
+        .collect();
+
+    format!(
+        "<{}>1 {} {} {} {} {} [{}{}] \u{feff}{}",
+        PRIORITY,
+        event.created_at,
+        header_field(hostname, 255),
+        APP_NAME,
+        std::process::id(),
+        header_field(&event.action, 32),
+        SD_ID,
+        structured_data,
+        event.description
+    )
+}
+
+// Header fields are printable ASCII without spaces, or - when empty.
+fn header_field(value: &str, max: usize) -> String {
+    let value: String = value
+        .chars()
+        .filter(|c| c.is_ascii_graphic())
+        .take(max)
+        .collect();
+    if value.is_empty() {
+        "-".to_string()
+    } else {
+        value
+    }
+}
+
+fn escape_param(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(']', "\\]")
+}
@@ -0,0 +1,247 @@
+use audit_sink::{AuditEvent, Options, Sink};
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, UdpSocket};
+use tokio_rustls::rustls::{Certificate, PrivateKey, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+fn events() -> Vec<AuditEvent> {
+    vec![
+        AuditEvent {
+            id: 1,
+            organisation_id: 7,
+            sequence: 1,
+            created_at: "2023-01-24T09:00:00.000000".to_string(),
+            user_id: Some(3),
+            access_type: "Web".to_string(),
+            action: "CreateSecret".to_string(),
+            description: "Created a secret".to_string(),
+            vault_id: Some(11),
+            hash: "abc".to_string(),
+            ..Default::default()
+        },
+        AuditEvent {
+            id: 2,
+            organisation_id: 7,
+            sequence: 2,
+            created_at: "2023-01-24T09:01:00.000000".to_string(),
+            service_account_id: Some(5),
+            access_type: "ServiceAccount".to_string(),
+            action: "AccessSecrets".to_string(),
+            description: "Fetched secrets\nfor \"prod\" [eu]".to_string(),
+            user_agent: Some("cli \"1.0\" [beta]".to_string()),
+            hash: "def".to_string(),
+            ..Default::default()
+        },
+    ]
+}
+
+// Reads RFC 6587 octet counted frames.
+async fn read_frames<R: AsyncRead + Unpin>(mut reader: R, count: usize) -> Vec<String> {
+    let mut frames = Vec::new();
+    for _ in 0..count {
+        let mut length = String::new();
+        loop {
+            let byte = reader.read_u8().await.unwrap();
+            if byte == b' ' {
+                break;
+            }
+            length.push(byte as char);
+        }
+        let mut message = vec![0; length.parse().unwrap()];
+        reader.read_exact(&mut message).await.unwrap();
+        frames.push(String::from_utf8(message).unwrap());
+    }
+    frames
+}
+
+fn check_messages(messages: &[String]) {
+    assert_eq!(messages.len(), 2);
+
+    assert!(messages[0].starts_with("<109>1 2023-01-24T09:00:00.000000 - cloak "));
+    assert!(messages[0].contains(" CreateSecret [audit@32473 organisation_id=\"7\""));
+    assert!(messages[0].contains(" user_id=\"3\""));
+    assert!(messages[0].contains(" vault_id=\"11\""));
+    assert!(messages[0].contains(" hash=\"abc\"] \u{feff}Created a secret"));
+    assert!(!messages[0].contains("service_account_id"));
+
+    assert!(messages[1].contains(" AccessSecrets [audit@32473 "));
+    assert!(messages[1].contains(" user_agent=\"cli \\\"1.0\\\" [beta\\]\""));
+    assert!(messages[1].ends_with("\u{feff}Fetched secrets\nfor \"prod\" [eu]"));
+}
+
+#[tokio::test]
+async fn syslog_over_udp() {
+    let listener = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("udp://{}", listener.local_addr().unwrap());
+
+    let mut sink = Sink::new(&url, Options::default()).unwrap();
+    sink.send(&events()).await.unwrap();
+
+    let mut messages = Vec::new();
+    let mut buffer = vec![0; 8192];
+    for _ in 0..2 {
+        let size = listener.recv(&mut buffer).await.unwrap();
+        messages.push(String::from_utf8(buffer[..size].to_vec()).unwrap());
+    }
+
+    check_messages(&messages);
+}
+
+#[tokio::test]
+async fn syslog_over_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("tcp://{}", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        read_frames(stream, 2).await
+    });
+
+    let mut sink = Sink::new(&url, Options::default()).unwrap();
+    sink.send(&events()).await.unwrap();
+
+    check_messages(&server.await.unwrap());
+}
+
+#[tokio::test]
+async fn syslog_over_tls() {
+    let certificate = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    let config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(
+            vec![Certificate(certificate.serialize_der().unwrap())],
+            PrivateKey(certificate.serialize_private_key_der()),
+        )
+        .unwrap();
+    let acceptor = TlsAcceptor::from(Arc::new(config));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("tls://localhost:{}", listener.local_addr().unwrap().port());
+
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let stream = acceptor.accept(stream).await.unwrap();
+        read_frames(stream, 2).await
+    });
+
+    let options = Options {
+        ca_certificates: Some(certificate.serialize_pem().unwrap().into_bytes()),
+        ..Default::default()
+    };
+    let mut sink = Sink::new(&url, options).unwrap();
+    sink.send(&events()).await.unwrap();
+
+    check_messages(&server.await.unwrap());
+}
+
+#[tokio::test]
+async fn syslog_over_tls_rejects_unknown_certificates() {
+    let certificate = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    let config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(
+            vec![Certificate(certificate.serialize_der().unwrap())],
+            PrivateKey(certificate.serialize_private_key_der()),
+        )
+        .unwrap();
+    let acceptor = TlsAcceptor::from(Arc::new(config));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("tls://localhost:{}", listener.local_addr().unwrap().port());
+
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let _ = acceptor.accept(stream).await;
+    });
+
+    // We weren't told to trust the collector's CA.
+    let mut sink = Sink::new(&url, Options::default()).unwrap();
+    assert!(sink.send(&events()).await.is_err());
+}
+
+#[tokio::test]
+async fn json_over_http() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/collect", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+
+        // Read the headers then as much body as they say there is.
+        let mut request = Vec::new();
+        let mut buffer = vec![0; 8192];
+        let body_start = loop {
+            let size = stream.read(&mut buffer).await.unwrap();
+            request.extend_from_slice(&buffer[..size]);
+            if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                break end + 4;
+            }
+        };
+        let headers = String::from_utf8(request[..body_start].to_vec())
+            .unwrap()
+            .to_lowercase();
+        let length: usize = headers
+            .lines()
+            .find_map(|line| line.strip_prefix("content-length: "))
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap();
+        while request.len() < body_start + length {
+            let size = stream.read(&mut buffer).await.unwrap();
+            request.extend_from_slice(&buffer[..size]);
+        }
+
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+            .await
+            .unwrap();
+
+        let body: serde_json::Value = serde_json::from_slice(&request[body_start..]).unwrap();
+        (headers, body)
+    });
+
+    let options = Options {
+        token: Some("secret-token".to_string()),
+        ..Default::default()
+    };
+    let mut sink = Sink::new(&url, options).unwrap();
+    sink.send(&events()).await.unwrap();
+
+    let (headers, body) = server.await.unwrap();
+    assert!(headers.starts_with("post /collect http/1.1"));
+    assert!(headers.contains("authorization: bearer secret-token"));
+    assert_eq!(body.as_array().unwrap().len(), 2);
+    assert_eq!(body[0]["action"], "CreateSecret");
+    assert_eq!(body[0]["vault_id"], 11);
+    assert_eq!(body[1]["service_account_id"], 5);
+    assert_eq!(body[1]["user_id"], serde_json::Value::Null);
+}
+
+#[tokio::test]
+async fn http_errors_are_reported() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/collect", listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buffer = vec![0; 8192];
+        let _ = stream.read(&mut buffer).await;
+        stream
+            .write_all(b"HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+            .await
+            .unwrap();
+    });
+
+    let mut sink = Sink::new(&url, Options::default()).unwrap();
+    assert!(sink.send(&events()).await.is_err());
+}
+
+#[test]
+fn unknown_schemes_are_rejected() {
+    assert!(Sink::new("ftp://collector", Options::default()).is_err());
+    assert!(Sink::new("not a url", Options::default()).is_err());
+}
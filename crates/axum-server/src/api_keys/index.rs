@@ -0,0 +1,34 @@
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use axum::{
+    extract::{Extension, Path},
+    response::Html,
+};
+use db::queries;
+use db::Pool;
+
+pub async fn index(
+    Path(organisation_id): Path<i32>,
+    current_user: Authentication,
+    Extension(pool): Extension<Pool>,
+) -> Result<Html<String>, CustomError> {
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    let team = queries::organisations::organisation()
+        .bind(&*transaction, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    let api_keys = queries::api_keys::get_all()
+        .bind(&*transaction, &current_user.user_id, &organisation_id)
+        .all()
+        .await?;
+
+    Ok(Html(ui_components::api_keys::index::index(
+        team.id, api_keys, None,
+    )))
+}
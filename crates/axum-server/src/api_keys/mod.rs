@@ -0,0 +1,28 @@
+mod index;
+mod new_api_key;
+mod revoke;
+
+use axum::{
+    routing::{get, post},
+    Router,
+};
+use sha2::{Digest, Sha256};
+
+pub fn routes() -> Router {
+    Router::new()
+        .route("/app/team/:organisation_id/api_keys", get(index::index))
+        .route(
+            "/app/team/:organisation_id/api_keys/new",
+            post(new_api_key::new),
+        )
+        .route(
+            "/app/team/:organisation_id/api_keys/revoke",
+            post(revoke::revoke),
+        )
+}
+
+// We only store the hash of a key, the same way we do for invitations.
+pub fn hash_api_key(api_key: &str) -> String {
+    let api_key_hash = Sha256::digest(api_key.as_bytes());
+    base64::encode_config(api_key_hash, base64::URL_SAFE_NO_PAD)
+}
@@ -0,0 +1,87 @@
+use crate::audit::Source;
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use axum::{
+    extract::{Extension, Form, Path},
+    response::Html,
+};
+use db::queries;
+use db::types::public::{AuditAccessType, AuditAction};
+use db::Pool;
+use rand::Rng;
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate, Default, Debug)]
+pub struct NewApiKey {
+    #[validate(length(min = 1, message = "The name is mandatory"))]
+    pub name: String,
+    // Zero means the key never expires.
+    pub expires_in_days: i32,
+}
+
+pub async fn new(
+    Path(organisation_id): Path<i32>,
+    current_user: Authentication,
+    source: Source,
+    Form(new_api_key): Form<NewApiKey>,
+    Extension(pool): Extension<Pool>,
+) -> Result<Html<String>, CustomError> {
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    let team = queries::organisations::organisation()
+        .bind(&*transaction, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    let api_key = rand::thread_rng().gen::<[u8; 32]>();
+    let api_key = format!(
+        "cloak_{}",
+        base64::encode_config(api_key, base64::URL_SAFE_NO_PAD)
+    );
+
+    let expires_in_days = if new_api_key.expires_in_days > 0 {
+        Some(new_api_key.expires_in_days)
+    } else {
+        None
+    };
+
+    queries::api_keys::insert()
+        .bind(
+            &*transaction,
+            &current_user.user_id,
+            &organisation_id,
+            &new_api_key.name.as_ref(),
+            &super::hash_api_key(&api_key).as_ref(),
+            &expires_in_days,
+        )
+        .await?;
+
+    crate::audit::Event::new(
+        AuditAction::CreateApiKey,
+        AuditAccessType::Web,
+        format!("{} API key created", &new_api_key.name),
+    )
+    .by_user(current_user.user_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
+
+    let api_keys = queries::api_keys::get_all()
+        .bind(&*transaction, &current_user.user_id, &organisation_id)
+        .all()
+        .await?;
+
+    transaction.commit().await?;
+
+    // This is the only time the user gets to see the key.
+    Ok(Html(ui_components::api_keys::index::index(
+        team.id,
+        api_keys,
+        Some(api_key),
+    )))
+}
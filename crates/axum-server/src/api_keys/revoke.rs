@@ -0,0 +1,67 @@
+use crate::audit::Source;
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use axum::{
+    extract::{Extension, Form, Path},
+    response::IntoResponse,
+};
+use db::queries;
+use db::types::public::{AuditAccessType, AuditAction};
+use db::Pool;
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate, Default, Debug)]
+pub struct RevokeApiKey {
+    pub api_key_id: i32,
+}
+
+pub async fn revoke(
+    Path(organisation_id): Path<i32>,
+    current_user: Authentication,
+    source: Source,
+    Form(idor_revoke_api_key): Form<RevokeApiKey>,
+    Extension(pool): Extension<Pool>,
+) -> Result<impl IntoResponse, CustomError> {
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    let team = queries::organisations::organisation()
+        .bind(&*transaction, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    let api_key = queries::api_keys::get()
+        .bind(
+            &*transaction,
+            &idor_revoke_api_key.api_key_id,
+            &current_user.user_id,
+        )
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    queries::api_keys::delete()
+        .bind(&*transaction, &api_key.id, &current_user.user_id)
+        .await?;
+
+    crate::audit::Event::new(
+        AuditAction::RevokeApiKey,
+        AuditAccessType::Web,
+        format!("{} API key revoked", api_key.name),
+    )
+    .by_user(current_user.user_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
+
+    transaction.commit().await?;
+
+    crate::layout::redirect_and_snackbar(
+        &ui_components::routes::api_keys::index_route(team.id),
+        "API Key Revoked",
+    )
+}
@@ -1,119 +1,195 @@
+use crate::audit::{Event, Source};
 use crate::{authentication, errors::CustomError};
 use db::queries;
+use db::types::public::{AuditAccessType, AuditAction, Permission, VaultRole};
 use db::Pool;
+use futures::{Stream, StreamExt};
 use grpc_api::vault::*;
+use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+use p256::{pkcs8::DecodePublicKey, PublicKey};
+use rand::Rng;
+use std::pin::Pin;
+use tokio::sync::broadcast;
 use tonic::{Code, Request, Response, Status};
 
 pub struct VaultService {
     pub pool: Pool,
+    pub config: crate::config::Config,
+    // The id of every service account whose secrets have changed.
+    pub service_account_changes: broadcast::Sender<i32>,
 }
 
 #[tonic::async_trait]
 impl grpc_api::vault::vault_server::Vault for VaultService {
-    async fn get_service_account(
+    async fn get_service_account_challenge(
         &self,
-        request: Request<GetServiceAccountRequest>,
-    ) -> Result<Response<GetServiceAccountResponse>, Status> {
+        request: Request<GetServiceAccountChallengeRequest>,
+    ) -> Result<Response<GetServiceAccountChallengeResponse>, Status> {
         let req = request.into_inner();
 
         // Create a transaction and setup RLS
-        let mut client = self
-            .pool
-            .get()
-            .await
-            .map_err(|e| CustomError::Database(e.to_string()))?;
-        let transaction = client
-            .transaction()
-            .await
-            .map_err(|e| CustomError::Database(e.to_string()))?;
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_service_account(&mut client, &req.ecdh_public_key)
+                .await?;
 
-        super::rls::set_row_level_security_ecdh_public_key(&transaction, &req.ecdh_public_key)
+        let service_account = queries::service_accounts::get_by_ecdh_public_key()
+            .bind(&*transaction, &req.ecdh_public_key.as_ref())
+            .opt()
             .await
-            .map_err(|e| CustomError::Database(e.to_string()))?;
+            .map_err(CustomError::from)?
+            .ok_or_else(CustomError::not_found)?;
 
-        let service_account = queries::service_accounts::get_by_ecdh_public_key()
-            .bind(&transaction, &req.ecdh_public_key.as_ref())
-            .one()
+        // Tidy up any challenges that were never used.
+        queries::service_account_challenges::delete_expired()
+            .bind(&*transaction, &service_account.id)
             .await
-            .map_err(|e| CustomError::Database(e.to_string()))?;
+            .map_err(CustomError::from)?;
 
-        let secrets = queries::service_account_secrets::get_all_dangerous()
-            .bind(&transaction, &service_account.id)
-            .all()
+        let nonce = rand::thread_rng().gen::<[u8; 32]>();
+        let nonce = base64::encode(nonce);
+
+        queries::service_account_challenges::insert()
+            .bind(&*transaction, &service_account.id, &nonce.as_ref())
             .await
-            .map_err(|e| CustomError::Database(e.to_string()))?;
+            .map_err(CustomError::from)?;
 
-        let secrets = secrets
-            .into_iter()
-            .map(|secret| ServiceAccountSecret {
-                encrypted_name: secret.name,
-                name_blind_index: secret.name_blind_index,
-                encrypted_secret_value: secret.secret,
-                ecdh_public_key: secret.ecdh_public_key,
-            })
-            .collect();
+        transaction.commit().await?;
 
-        let response = GetServiceAccountResponse {
-            service_account_id: service_account.id as u32,
-            secrets,
-        };
+        Ok(Response::new(GetServiceAccountChallengeResponse { nonce }))
+    }
+
+    async fn get_service_account(
+        &self,
+        request: Request<GetServiceAccountRequest>,
+    ) -> Result<Response<GetServiceAccountResponse>, Status> {
+        let source = request_source(&request, self.config.trusted_proxies);
+        let req = request.into_inner();
+
+        // The public key is not a secret, so the caller has to prove
+        // they hold the private key by signing our challenge.
+        verify_signature(&req.ecdh_public_key, &req.nonce, &req.signature)?;
+
+        let response = service_account_snapshot(
+            &self.pool,
+            &req.ecdh_public_key,
+            Some(req.nonce.as_str()),
+            &source,
+        )
+        .await?;
 
         return Ok(Response::new(response));
     }
 
+    type WatchServiceAccountStream =
+        Pin<Box<dyn Stream<Item = Result<GetServiceAccountResponse, Status>> + Send>>;
+
+    async fn watch_service_account(
+        &self,
+        request: Request<GetServiceAccountRequest>,
+    ) -> Result<Response<Self::WatchServiceAccountStream>, Status> {
+        let source = request_source(&request, self.config.trusted_proxies);
+        let req = request.into_inner();
+
+        verify_signature(&req.ecdh_public_key, &req.nonce, &req.signature)?;
+
+        // Subscribe before taking the first snapshot so we can't miss a change.
+        let changes = self.service_account_changes.subscribe();
+
+        let first_snapshot = service_account_snapshot(
+            &self.pool,
+            &req.ecdh_public_key,
+            Some(req.nonce.as_str()),
+            &source,
+        )
+        .await?;
+
+        let watch = Watch {
+            pool: self.pool.clone(),
+            changes,
+            service_account_id: first_snapshot.service_account_id as i32,
+            ecdh_public_key: req.ecdh_public_key,
+            source,
+        };
+
+        // The challenge has been used, so later snapshots skip it.
+        let updates = futures::stream::unfold(Some(watch), |watch| async move {
+            let mut watch = watch?;
+            loop {
+                match watch.changes.recv().await {
+                    Ok(service_account_id) if service_account_id == watch.service_account_id => {
+                        break
+                    }
+                    Ok(_) => continue,
+                    // We missed some changes, one of them could be ours.
+                    Err(broadcast::error::RecvError::Lagged(_)) => break,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+
+            let snapshot =
+                service_account_snapshot(&watch.pool, &watch.ecdh_public_key, None, &watch.source)
+                    .await;
+
+            match snapshot {
+                Ok(snapshot) => Some((Ok(snapshot), Some(watch))),
+                // i.e. the service account was deleted, send the error and stop.
+                Err(e) => Some((Err(Status::from(e)), None)),
+            }
+        });
+
+        let stream =
+            futures::stream::once(async move { Ok::<_, Status>(first_snapshot) }).chain(updates);
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
     async fn get_vault(
         &self,
         request: Request<GetVaultRequest>,
     ) -> Result<Response<GetVaultResponse>, Status> {
-        let authenticated_user = authenticate(&request).await?;
+        let authenticated_user = authenticate(&request, &self.pool, None).await?;
 
         let req = request.into_inner();
 
         // Create a transaction and setup RLS
-        let mut client = self
-            .pool
-            .get()
-            .await
-            .map_err(|e| CustomError::Database(e.to_string()))?;
-        let transaction = client
-            .transaction()
-            .await
-            .map_err(|e| CustomError::Database(e.to_string()))?;
-        super::rls::set_row_level_security_user(&transaction, &authenticated_user)
-            .await
-            .map_err(|e| CustomError::Database(e.to_string()))?;
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_user(&mut client, &authenticated_user).await?;
 
         let secrets = queries::secrets::get_all()
-            .bind(&transaction, &(req.vault_id as i32))
+            .bind(&*transaction, &(req.vault_id as i32))
             .all()
             .await
-            .map_err(|e| CustomError::Database(e.to_string()))?;
+            .map_err(CustomError::from)?;
 
         let vault = queries::vaults::get()
             .bind(
-                &transaction,
+                &*transaction,
                 &(req.vault_id as i32),
                 &(authenticated_user.user_id as i32),
             )
-            .one()
+            .opt()
             .await
-            .map_err(|e| CustomError::Database(e.to_string()))?;
+            .map_err(CustomError::from)?
+            .ok_or_else(CustomError::not_found)?;
 
         let user_vault = queries::user_vaults::get()
             .bind(
-                &transaction,
+                &*transaction,
                 &(authenticated_user.user_id as i32),
                 &(req.vault_id as i32),
             )
-            .one()
+            .opt()
             .await
-            .map_err(|e| CustomError::Database(e.to_string()))?;
+            .map_err(CustomError::from)?
+            .ok_or_else(CustomError::not_found)?;
 
         let service_accounts = queries::service_accounts::get_by_vault()
-            .bind(&transaction, &(req.vault_id as i32))
+            .bind(&*transaction, &(req.vault_id as i32))
             .all()
             .await
-            .map_err(|e| CustomError::Database(e.to_string()))?;
+            .map_err(CustomError::from)?;
 
         let secrets = secrets
             .into_iter()
@@ -122,22 +198,27 @@ impl grpc_api::vault::vault_server::Vault for VaultService {
                 name_blind_index: s.name_blind_index,
                 encrypted_secret_value: s.secret,
                 environment_id: s.environment_id as u32,
+                id: s.id as u32,
+                updated_at: s.updated_at,
             })
             .collect();
 
+        // A service account whose key is being rotated is listed once for
+        // each key, so secrets get encrypted for both.
         let service_accounts = service_accounts
             .into_iter()
             .filter_map(|s| {
-                if let Some(env_id) = s.environment_id {
-                    Some(ServiceAccount {
-                        service_account_id: s.id as u32,
-                        environment_id: env_id as u32,
-                        public_ecdh_key: s.ecdh_public_key,
-                    })
-                } else {
-                    None
-                }
+                s.environment_id.map(|env_id| {
+                    std::iter::once(s.ecdh_public_key)
+                        .chain(s.previous_ecdh_public_key)
+                        .map(move |public_ecdh_key| ServiceAccount {
+                            service_account_id: s.id as u32,
+                            environment_id: env_id as u32,
+                            public_ecdh_key,
+                        })
+                })
             })
+            .flatten()
             .collect();
 
         let response = GetVaultResponse {
@@ -146,6 +227,7 @@ impl grpc_api::vault::vault_server::Vault for VaultService {
             user_vault_public_ecdh_key: user_vault.ecdh_public_key,
             secrets,
             service_accounts,
+            key_version: user_vault.key_version as u32,
         };
 
         Ok(Response::new(response))
@@ -155,95 +237,1533 @@ impl grpc_api::vault::vault_server::Vault for VaultService {
         &self,
         request: Request<CreateSecretsRequest>,
     ) -> Result<Response<CreateSecretsResponse>, Status> {
-        let authenticated_user = authenticate(&request).await?;
+        let authenticated_user = authenticate(&request, &self.pool, None).await?;
+
+        let source = request_source(&request, self.config.trusted_proxies);
 
         // Create a transaction and setup RLS
-        let mut client = self
-            .pool
-            .get()
-            .await
-            .map_err(|e| CustomError::Database(e.to_string()))?;
-        let transaction = client
-            .transaction()
-            .await
-            .map_err(|e| CustomError::Database(e.to_string()))?;
-        super::rls::set_row_level_security_user(&transaction, &authenticated_user)
-            .await
-            .map_err(|e| CustomError::Database(e.to_string()))?;
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_user(&mut client, &authenticated_user).await?;
 
         let service_account = request.into_inner();
 
         for account_secret in service_account.account_secrets {
             // Get the service account this request is trying to access
             let sa = queries::service_accounts::get_dangerous()
-                .bind(&transaction, &(account_secret.service_account_id as i32))
-                .one()
+                .bind(&*transaction, &(account_secret.service_account_id as i32))
+                .opt()
                 .await
-                .map_err(|e| CustomError::Database(e.to_string()))?;
+                .map_err(CustomError::from)?
+                .ok_or_else(CustomError::not_found)?;
 
-            // If the vault is already connected we can do an IDOR check
-            // And see if the user actually has access to the vault.
-            if let Some(vault_id) = sa.vault_id {
-                // Blow up, if the user doesn't have access to the vault.
-                queries::service_account_secrets::get_users_vaults()
-                    .bind(
-                        &transaction,
-                        &(authenticated_user.user_id as i32),
-                        &vault_id,
-                    )
-                    .all()
-                    .await
-                    .map_err(|e| CustomError::Database(e.to_string()))?;
-            }
+            // Secrets come from a vault, so there is nothing to share with a
+            // service account that isn't connected to one.
+            let vault_id = sa.vault_id.ok_or_else(|| {
+                CustomError::InvalidInput(format!(
+                    "Service account {} is not connected to a vault",
+                    sa.name
+                ))
+            })?;
+
+            // Blow up, if the user doesn't have access to the vault.
+            queries::service_account_secrets::get_users_vaults()
+                .bind(&*transaction, &authenticated_user.user_id, &vault_id)
+                .opt()
+                .await
+                .map_err(CustomError::from)?
+                .ok_or_else(CustomError::not_found)?;
+
+            // Viewers can't change what a service account sees.
+            super::rbac::require_vault_role(
+                &transaction,
+                authenticated_user.user_id,
+                vault_id,
+                VaultRole::Editor,
+            )
+            .await?;
+
+            let shared = account_secret.secrets.len();
+
+            // Empty means the current key of the service account.
+            let service_account_ecdh_public_key =
+                Some(account_secret.service_account_ecdh_public_key.as_str())
+                    .filter(|key| !key.is_empty());
 
             // If yes, save the secret
             for secret in account_secret.secrets {
                 queries::service_account_secrets::insert()
                     .bind(
-                        &transaction,
+                        &*transaction,
                         &(account_secret.service_account_id as i32),
                         &secret.encrypted_name.as_ref(),
                         &secret.name_blind_index.as_ref(),
                         &secret.encrypted_secret_value.as_ref(),
                         &account_secret.public_ecdh_key.as_ref(),
+                        &service_account_ecdh_public_key,
                     )
                     .await
-                    .map_err(|e| CustomError::Database(e.to_string()))?;
+                    .map_err(CustomError::from)?;
             }
+
+            Event::new(
+                AuditAction::ShareSecrets,
+                AuditAccessType::API,
+                format!("{} secrets shared with service account {}", shared, sa.name),
+            )
+            .by_user(authenticated_user.user_id)
+            .vault(vault_id)
+            .target_service_account(sa.id)
+            .source(&source)
+            .insert(&transaction, sa.organisation_id)
+            .await?;
         }
 
-        transaction
-            .commit()
-            .await
-            .map_err(|e| CustomError::Database(e.to_string()))?;
+        transaction.commit().await?;
 
         let response = CreateSecretsResponse {};
 
         Ok(Response::new(response))
     }
+
+    async fn list_vaults(
+        &self,
+        request: Request<ListVaultsRequest>,
+    ) -> Result<Response<ListVaultsResponse>, Status> {
+        let authenticated_user = authenticate(
+            &request,
+            &self.pool,
+            Some(request.get_ref().organisation_id),
+        )
+        .await?;
+
+        let req = request.into_inner();
+
+        // Create a transaction and setup RLS
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_user(&mut client, &authenticated_user).await?;
+
+        let vaults = queries::vaults::get_all()
+            .bind(
+                &*transaction,
+                &authenticated_user.user_id,
+                &(req.organisation_id as i32),
+            )
+            .all()
+            .await
+            .map_err(CustomError::from)?;
+
+        let mut summary_vaults: Vec<VaultSummary> = Default::default();
+
+        for vault in vaults {
+            let user_count = queries::vaults::user_vault_count()
+                .bind(&*transaction, &vault.id)
+                .one()
+                .await
+                .map_err(CustomError::from)?;
+
+            let secrets_count = queries::vaults::secrets_count()
+                .bind(&*transaction, &vault.id)
+                .one()
+                .await
+                .map_err(CustomError::from)?;
+
+            summary_vaults.push(VaultSummary {
+                id: vault.id as u32,
+                name: vault.name,
+                user_count: user_count as u32,
+                secrets_count: secrets_count as u32,
+                created_at: vault.created_at,
+                updated_at: vault.updated_at,
+                key_rotation_needed: vault.key_rotation_needed,
+            });
+        }
+
+        let response = ListVaultsResponse {
+            vaults: summary_vaults,
+        };
+
+        Ok(Response::new(response))
+    }
+
+    async fn create_vault(
+        &self,
+        request: Request<CreateVaultRequest>,
+    ) -> Result<Response<CreateVaultResponse>, Status> {
+        let authenticated_user = authenticate(
+            &request,
+            &self.pool,
+            Some(request.get_ref().organisation_id),
+        )
+        .await?;
+
+        let source = request_source(&request, self.config.trusted_proxies);
+        let req = request.into_inner();
+
+        // Create a transaction and setup RLS
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_user(&mut client, &authenticated_user).await?;
+
+        super::rbac::require(
+            &transaction,
+            authenticated_user.user_id,
+            req.organisation_id as i32,
+            Permission::CreateVault,
+        )
+        .await?;
+
+        // Blow up if the user isn't in the team
+        queries::organisations::organisation()
+            .bind(&*transaction, &(req.organisation_id as i32))
+            .opt()
+            .await
+            .map_err(CustomError::from)?
+            .ok_or_else(CustomError::not_found)?;
+
+        let vault_id = queries::vaults::insert()
+            .bind(
+                &*transaction,
+                &(req.organisation_id as i32),
+                &req.name.as_ref(),
+            )
+            .one()
+            .await
+            .map_err(CustomError::from)?;
+
+        queries::vaults::insert_user_vaults()
+            .bind(
+                &*transaction,
+                &authenticated_user.user_id,
+                &vault_id,
+                &req.public_key.as_ref(),
+                &req.encrypted_vault_key.as_ref(),
+            )
+            .await
+            .map_err(CustomError::from)?;
+
+        Event::new(
+            AuditAction::CreateVault,
+            AuditAccessType::API,
+            format!("{} vault created", &req.name),
+        )
+        .by_user(authenticated_user.user_id)
+        .vault(vault_id)
+        .source(&source)
+        .insert(&transaction, req.organisation_id as i32)
+        .await?;
+
+        queries::environments::setup_environments()
+            .bind(&*transaction, &vault_id)
+            .await
+            .map_err(CustomError::from)?;
+
+        transaction.commit().await?;
+
+        let response = CreateVaultResponse {
+            vault_id: vault_id as u32,
+        };
+
+        Ok(Response::new(response))
+    }
+
+    async fn delete_vault(
+        &self,
+        request: Request<DeleteVaultRequest>,
+    ) -> Result<Response<DeleteVaultResponse>, Status> {
+        let authenticated_user = authenticate(
+            &request,
+            &self.pool,
+            Some(request.get_ref().organisation_id),
+        )
+        .await?;
+
+        let source = request_source(&request, self.config.trusted_proxies);
+        let req = request.into_inner();
+
+        // Create a transaction and setup RLS
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_user(&mut client, &authenticated_user).await?;
+
+        let organisation_id = super::rbac::vault_team(
+            &transaction,
+            authenticated_user.user_id,
+            req.vault_id as i32,
+            req.organisation_id as i32,
+        )
+        .await?;
+
+        super::rbac::require(
+            &transaction,
+            authenticated_user.user_id,
+            organisation_id,
+            Permission::DeleteVault,
+        )
+        .await?;
+
+        // As well as the team permission, only the vault's Admins can delete it.
+        super::rbac::require_vault_role(
+            &transaction,
+            authenticated_user.user_id,
+            req.vault_id as i32,
+            VaultRole::Admin,
+        )
+        .await?;
+
+        // Blow up if the user doesn't have access to the vault
+        let vault = queries::vaults::get()
+            .bind(
+                &*transaction,
+                &(req.vault_id as i32),
+                &authenticated_user.user_id,
+            )
+            .opt()
+            .await
+            .map_err(CustomError::from)?
+            .ok_or_else(CustomError::not_found)?;
+
+        queries::vaults::delete()
+            .bind(
+                &*transaction,
+                &(req.vault_id as i32),
+                &authenticated_user.user_id,
+            )
+            .await
+            .map_err(CustomError::from)?;
+
+        Event::new(
+            AuditAction::DeleteVault,
+            AuditAccessType::API,
+            format!("{} vault deleted", vault.name),
+        )
+        .by_user(authenticated_user.user_id)
+        .vault(req.vault_id as i32)
+        .source(&source)
+        .insert(&transaction, organisation_id)
+        .await?;
+
+        transaction.commit().await?;
+
+        let response = DeleteVaultResponse {};
+
+        Ok(Response::new(response))
+    }
+
+    async fn list_environments(
+        &self,
+        request: Request<ListEnvironmentsRequest>,
+    ) -> Result<Response<ListEnvironmentsResponse>, Status> {
+        let authenticated_user = authenticate(
+            &request,
+            &self.pool,
+            Some(request.get_ref().organisation_id),
+        )
+        .await?;
+
+        let req = request.into_inner();
+
+        // Create a transaction and setup RLS
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_user(&mut client, &authenticated_user).await?;
+
+        let environments = queries::environments::get_all()
+            .bind(&*transaction, &(req.vault_id as i32))
+            .all()
+            .await
+            .map_err(CustomError::from)?;
+
+        let environments = environments
+            .into_iter()
+            .map(|env| Environment {
+                id: env.id as u32,
+                name: env.name,
+            })
+            .collect();
+
+        let response = ListEnvironmentsResponse { environments };
+
+        Ok(Response::new(response))
+    }
+
+    async fn add_secret(
+        &self,
+        request: Request<AddSecretRequest>,
+    ) -> Result<Response<AddSecretResponse>, Status> {
+        let authenticated_user = authenticate(
+            &request,
+            &self.pool,
+            Some(request.get_ref().organisation_id),
+        )
+        .await?;
+
+        let source = request_source(&request, self.config.trusted_proxies);
+        let req = request.into_inner();
+
+        let secret = req
+            .secret
+            .ok_or_else(|| CustomError::InvalidInput("The secret is mandatory".to_string()))?;
+
+        // Create a transaction and setup RLS
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_user(&mut client, &authenticated_user).await?;
+
+        // Viewers can only read secrets.
+        super::rbac::require_vault_role(
+            &transaction,
+            authenticated_user.user_id,
+            req.vault_id as i32,
+            VaultRole::Editor,
+        )
+        .await?;
+
+        // This will blow up if the user doesn't have access to the vault
+        let organisation_id = super::rbac::vault_team(
+            &transaction,
+            authenticated_user.user_id,
+            req.vault_id as i32,
+            req.organisation_id as i32,
+        )
+        .await?;
+
+        super::vaults::ensure_key_version(
+            &transaction,
+            req.vault_id as i32,
+            req.key_version as i32,
+        )
+        .await?;
+
+        queries::secrets::insert()
+            .bind(
+                &*transaction,
+                &(req.vault_id as i32),
+                &secret.encrypted_name.as_ref(),
+                &secret.name_blind_index.as_ref(),
+                &secret.encrypted_secret_value.as_ref(),
+                &(secret.environment_id as i32),
+                &(req.key_version as i32),
+            )
+            .await
+            .map_err(CustomError::from)?;
+
+        Event::new(
+            AuditAction::AddSecret,
+            AuditAccessType::API,
+            format!("Secret created for Vault with ID {}", req.vault_id),
+        )
+        .by_user(authenticated_user.user_id)
+        .vault(req.vault_id as i32)
+        .environment(secret.environment_id as i32)
+        .source(&source)
+        .insert(&transaction, organisation_id)
+        .await?;
+
+        transaction.commit().await?;
+
+        let response = AddSecretResponse {};
+
+        Ok(Response::new(response))
+    }
+
+    async fn delete_secret(
+        &self,
+        request: Request<DeleteSecretRequest>,
+    ) -> Result<Response<DeleteSecretResponse>, Status> {
+        let authenticated_user = authenticate(
+            &request,
+            &self.pool,
+            Some(request.get_ref().organisation_id),
+        )
+        .await?;
+
+        let source = request_source(&request, self.config.trusted_proxies);
+        let req = request.into_inner();
+
+        // Create a transaction and setup RLS
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_user(&mut client, &authenticated_user).await?;
+
+        // Viewers can only read secrets.
+        super::rbac::require_vault_role(
+            &transaction,
+            authenticated_user.user_id,
+            req.vault_id as i32,
+            VaultRole::Editor,
+        )
+        .await?;
+
+        let organisation_id = super::rbac::vault_team(
+            &transaction,
+            authenticated_user.user_id,
+            req.vault_id as i32,
+            req.organisation_id as i32,
+        )
+        .await?;
+
+        let secret = queries::secrets::get()
+            .bind(&*transaction, &(req.secret_id as i32))
+            .opt()
+            .await
+            .map_err(CustomError::from)?
+            .ok_or_else(CustomError::not_found)?;
+
+        if secret.vault_id != req.vault_id as i32 {
+            return Err(CustomError::NotFound("Secret is not in this vault".to_string()).into());
+        }
+
+        queries::secrets::delete_secret()
+            .bind(&*transaction, &secret.id)
+            .await
+            .map_err(CustomError::from)?;
+
+        Event::new(
+            AuditAction::DeleteSecret,
+            AuditAccessType::API,
+            format!("Secret deleted from Vault with ID {}", req.vault_id),
+        )
+        .by_user(authenticated_user.user_id)
+        .vault(req.vault_id as i32)
+        .secret(secret.id)
+        .environment(secret.environment_id)
+        .source(&source)
+        .insert(&transaction, organisation_id)
+        .await?;
+
+        // Service accounts have their own copy of the secret.
+        queries::secrets::delete_service_account()
+            .bind(
+                &*transaction,
+                &secret.name_blind_index.as_ref(),
+                &secret.vault_id,
+            )
+            .await
+            .map_err(CustomError::from)?;
+
+        transaction.commit().await?;
+
+        let response = DeleteSecretResponse {};
+
+        Ok(Response::new(response))
+    }
+
+    async fn update_secrets(
+        &self,
+        request: Request<UpdateSecretsRequest>,
+    ) -> Result<Response<UpdateSecretsResponse>, Status> {
+        let authenticated_user = authenticate(
+            &request,
+            &self.pool,
+            Some(request.get_ref().organisation_id),
+        )
+        .await?;
+
+        let source = request_source(&request, self.config.trusted_proxies);
+        let req = request.into_inner();
+
+        // Each service account gets exactly one copy of the secret.
+        let mut copies = Vec::new();
+        for account_secret in req.account_secrets {
+            let secret = account_secret.secrets.into_iter().next().ok_or_else(|| {
+                CustomError::InvalidInput("Each service account needs a secret".to_string())
+            })?;
+            copies.push(super::secrets::ServiceAccountSecretCopy {
+                service_account_id: account_secret.service_account_id as i32,
+                service_account_ecdh_public_key: Some(
+                    account_secret.service_account_ecdh_public_key,
+                )
+                .filter(|key| !key.is_empty()),
+                public_ecdh_key: account_secret.public_ecdh_key,
+                encrypted_name: secret.encrypted_name,
+                encrypted_secret_value: secret.encrypted_secret_value,
+            });
+        }
+
+        // Create a transaction and setup RLS
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_user(&mut client, &authenticated_user).await?;
+
+        // Viewers can only read secrets.
+        super::rbac::require_vault_role(
+            &transaction,
+            authenticated_user.user_id,
+            req.vault_id as i32,
+            VaultRole::Editor,
+        )
+        .await?;
+
+        let organisation_id = super::rbac::vault_team(
+            &transaction,
+            authenticated_user.user_id,
+            req.vault_id as i32,
+            req.organisation_id as i32,
+        )
+        .await?;
+
+        let secret = queries::secrets::get()
+            .bind(&*transaction, &(req.secret_id as i32))
+            .opt()
+            .await
+            .map_err(CustomError::from)?
+            .ok_or_else(CustomError::not_found)?;
+
+        if secret.vault_id != req.vault_id as i32 {
+            return Err(CustomError::NotFound("Secret is not in this vault".to_string()).into());
+        }
+
+        super::vaults::ensure_key_version(
+            &transaction,
+            req.vault_id as i32,
+            req.key_version as i32,
+        )
+        .await?;
+
+        let updated = queries::secrets::update_secret()
+            .bind(
+                &*transaction,
+                &req.encrypted_secret_value.as_ref(),
+                &(req.key_version as i32),
+                &secret.id,
+                &req.updated_at.as_ref(),
+            )
+            .await
+            .map_err(CustomError::from)?;
+
+        if updated == 0 {
+            return Err(CustomError::Conflict(
+                "The secret has been changed by someone else".to_string(),
+            )
+            .into());
+        }
+
+        super::secrets::replace_service_account_secrets(
+            &transaction,
+            secret.vault_id,
+            secret.environment_id,
+            &secret.name_blind_index,
+            &copies,
+        )
+        .await?;
+
+        Event::new(
+            AuditAction::UpdateSecret,
+            AuditAccessType::API,
+            format!(
+                "Secret {} updated in Vault with ID {}",
+                secret.id, req.vault_id
+            ),
+        )
+        .by_user(authenticated_user.user_id)
+        .vault(req.vault_id as i32)
+        .secret(secret.id)
+        .environment(secret.environment_id)
+        .source(&source)
+        .insert(&transaction, organisation_id)
+        .await?;
+
+        transaction.commit().await?;
+
+        let response = UpdateSecretsResponse {};
+
+        Ok(Response::new(response))
+    }
+
+    async fn list_service_accounts(
+        &self,
+        request: Request<ListServiceAccountsRequest>,
+    ) -> Result<Response<ListServiceAccountsResponse>, Status> {
+        let authenticated_user = authenticate(
+            &request,
+            &self.pool,
+            Some(request.get_ref().organisation_id),
+        )
+        .await?;
+
+        let req = request.into_inner();
+
+        // Create a transaction and setup RLS
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_user(&mut client, &authenticated_user).await?;
+
+        let service_accounts = queries::service_accounts::get_all()
+            .bind(&*transaction, &(req.organisation_id as i32))
+            .all()
+            .await
+            .map_err(CustomError::from)?;
+
+        let service_accounts = service_accounts
+            .into_iter()
+            .map(|sa| ServiceAccountSummary {
+                id: sa.id as u32,
+                name: sa.account_name,
+                vault_id: sa.vault_id.unwrap_or_default() as u32,
+                vault_name: sa.vault_name.unwrap_or_default(),
+                environment_name: sa.environment_name.unwrap_or_default(),
+                ecdh_public_key: sa.ecdh_public_key,
+                encrypted_ecdh_private_key: sa.encrypted_ecdh_private_key,
+                created_at: sa.created_at,
+                updated_at: sa.updated_at,
+            })
+            .collect();
+
+        let response = ListServiceAccountsResponse { service_accounts };
+
+        Ok(Response::new(response))
+    }
+
+    async fn create_service_account(
+        &self,
+        request: Request<CreateServiceAccountRequest>,
+    ) -> Result<Response<CreateServiceAccountResponse>, Status> {
+        let authenticated_user = authenticate(
+            &request,
+            &self.pool,
+            Some(request.get_ref().organisation_id),
+        )
+        .await?;
+
+        let source = request_source(&request, self.config.trusted_proxies);
+        let req = request.into_inner();
+
+        // Create a transaction and setup RLS
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_user(&mut client, &authenticated_user).await?;
+
+        super::rbac::require(
+            &transaction,
+            authenticated_user.user_id,
+            req.organisation_id as i32,
+            Permission::ManageServiceAccounts,
+        )
+        .await?;
+
+        queries::service_accounts::insert()
+            .bind(
+                &*transaction,
+                &(req.organisation_id as i32),
+                &req.name.as_ref(),
+                &req.ecdh_public_key.as_ref(),
+                &req.encrypted_ecdh_private_key.as_ref(),
+            )
+            .await
+            .map_err(CustomError::from)?;
+
+        Event::new(
+            AuditAction::NewServiceAccount,
+            AuditAccessType::API,
+            format!("Service account {} created", &req.name),
+        )
+        .by_user(authenticated_user.user_id)
+        .source(&source)
+        .insert(&transaction, req.organisation_id as i32)
+        .await?;
+
+        transaction.commit().await?;
+
+        let response = CreateServiceAccountResponse {};
+
+        Ok(Response::new(response))
+    }
+
+    async fn connect_service_account(
+        &self,
+        request: Request<ConnectServiceAccountRequest>,
+    ) -> Result<Response<ConnectServiceAccountResponse>, Status> {
+        let authenticated_user = authenticate(
+            &request,
+            &self.pool,
+            Some(request.get_ref().organisation_id),
+        )
+        .await?;
+
+        let source = request_source(&request, self.config.trusted_proxies);
+        let req = request.into_inner();
+
+        // Create a transaction and setup RLS
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_user(&mut client, &authenticated_user).await?;
+
+        let organisation_id = super::rbac::service_account_team(
+            &transaction,
+            req.service_account_id as i32,
+            req.organisation_id as i32,
+        )
+        .await?;
+
+        // The vault has to be in the same team as the service account.
+        super::rbac::vault_team(
+            &transaction,
+            authenticated_user.user_id,
+            req.vault_id as i32,
+            organisation_id,
+        )
+        .await?;
+
+        super::rbac::require(
+            &transaction,
+            authenticated_user.user_id,
+            organisation_id,
+            Permission::ManageServiceAccounts,
+        )
+        .await?;
+
+        queries::service_accounts::connect()
+            .bind(
+                &*transaction,
+                &(req.vault_id as i32),
+                &(req.environment_id as i32),
+                &(req.service_account_id as i32),
+                &authenticated_user.user_id,
+                &organisation_id,
+            )
+            .await
+            .map_err(CustomError::from)?;
+
+        Event::new(
+            AuditAction::ConnectServiceAccount,
+            AuditAccessType::API,
+            format!("Service account {} connected", req.service_account_id),
+        )
+        .by_user(authenticated_user.user_id)
+        .vault(req.vault_id as i32)
+        .environment(req.environment_id as i32)
+        .target_service_account(req.service_account_id as i32)
+        .source(&source)
+        .insert(&transaction, organisation_id)
+        .await?;
+
+        transaction.commit().await?;
+
+        let response = ConnectServiceAccountResponse {};
+
+        Ok(Response::new(response))
+    }
+
+    async fn delete_service_account(
+        &self,
+        request: Request<DeleteServiceAccountRequest>,
+    ) -> Result<Response<DeleteServiceAccountResponse>, Status> {
+        let authenticated_user = authenticate(
+            &request,
+            &self.pool,
+            Some(request.get_ref().organisation_id),
+        )
+        .await?;
+
+        let source = request_source(&request, self.config.trusted_proxies);
+        let req = request.into_inner();
+
+        // Create a transaction and setup RLS
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_user(&mut client, &authenticated_user).await?;
+
+        let organisation_id = super::rbac::service_account_team(
+            &transaction,
+            req.service_account_id as i32,
+            req.organisation_id as i32,
+        )
+        .await?;
+
+        super::rbac::require(
+            &transaction,
+            authenticated_user.user_id,
+            organisation_id,
+            Permission::ManageServiceAccounts,
+        )
+        .await?;
+
+        queries::service_accounts::delete_service_account()
+            .bind(
+                &*transaction,
+                &(req.service_account_id as i32),
+                &organisation_id,
+            )
+            .await
+            .map_err(CustomError::from)?;
+
+        Event::new(
+            AuditAction::DeleteServiceAccount,
+            AuditAccessType::API,
+            format!("Service account {} deleted", req.service_account_id),
+        )
+        .by_user(authenticated_user.user_id)
+        .target_service_account(req.service_account_id as i32)
+        .source(&source)
+        .insert(&transaction, organisation_id)
+        .await?;
+
+        transaction.commit().await?;
+
+        let response = DeleteServiceAccountResponse {};
+
+        Ok(Response::new(response))
+    }
+
+    async fn list_members(
+        &self,
+        request: Request<ListMembersRequest>,
+    ) -> Result<Response<ListMembersResponse>, Status> {
+        let authenticated_user = authenticate(
+            &request,
+            &self.pool,
+            Some(request.get_ref().organisation_id),
+        )
+        .await?;
+
+        let req = request.into_inner();
+
+        // Create a transaction and setup RLS
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_user(&mut client, &authenticated_user).await?;
+
+        // Blow up if the user doesn't have access to the vault
+        queries::user_vaults::get()
+            .bind(
+                &*transaction,
+                &authenticated_user.user_id,
+                &(req.vault_id as i32),
+            )
+            .opt()
+            .await
+            .map_err(CustomError::from)?
+            .ok_or_else(CustomError::not_found)?;
+
+        let members = queries::user_vaults::get_users()
+            .bind(&*transaction, &(req.vault_id as i32))
+            .all()
+            .await
+            .map_err(CustomError::from)?;
+
+        let members = members
+            .into_iter()
+            .map(|member| VaultMember {
+                user_id: member.user_id as u32,
+                email: member.email,
+                role: format!("{:?}", member.role),
+                environments: member.environments.unwrap_or_default(),
+            })
+            .collect();
+
+        let response = ListMembersResponse { members };
+
+        Ok(Response::new(response))
+    }
+
+    async fn list_non_members(
+        &self,
+        request: Request<ListNonMembersRequest>,
+    ) -> Result<Response<ListNonMembersResponse>, Status> {
+        let authenticated_user = authenticate(
+            &request,
+            &self.pool,
+            Some(request.get_ref().organisation_id),
+        )
+        .await?;
+
+        let req = request.into_inner();
+
+        // Create a transaction and setup RLS
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_user(&mut client, &authenticated_user).await?;
+
+        // Blow up if the user doesn't have access to the vault
+        queries::user_vaults::get()
+            .bind(
+                &*transaction,
+                &authenticated_user.user_id,
+                &(req.vault_id as i32),
+            )
+            .opt()
+            .await
+            .map_err(CustomError::from)?
+            .ok_or_else(CustomError::not_found)?;
+
+        let organisation_id = super::rbac::vault_team(
+            &transaction,
+            authenticated_user.user_id,
+            req.vault_id as i32,
+            req.organisation_id as i32,
+        )
+        .await?;
+
+        let non_members = queries::user_vaults::get_non_members()
+            .bind(&*transaction, &organisation_id, &(req.vault_id as i32))
+            .all()
+            .await
+            .map_err(CustomError::from)?;
+
+        let non_members = non_members
+            .into_iter()
+            .map(|non_member| NonMember {
+                user_id: non_member.id as u32,
+                email: non_member.email,
+                ecdh_public_key: non_member.ecdh_public_key,
+            })
+            .collect();
+
+        let response = ListNonMembersResponse { non_members };
+
+        Ok(Response::new(response))
+    }
+
+    async fn add_member_to_vault(
+        &self,
+        request: Request<AddMemberToVaultRequest>,
+    ) -> Result<Response<AddMemberToVaultResponse>, Status> {
+        let authenticated_user = authenticate(
+            &request,
+            &self.pool,
+            Some(request.get_ref().organisation_id),
+        )
+        .await?;
+
+        let source = request_source(&request, self.config.trusted_proxies);
+        let req = request.into_inner();
+
+        let role = super::rbac::parse_vault_role(&req.role)?;
+
+        // Create a transaction and setup RLS
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_user(&mut client, &authenticated_user).await?;
+
+        // Only vault Admins can add members.
+        super::rbac::require_vault_role(
+            &transaction,
+            authenticated_user.user_id,
+            req.vault_id as i32,
+            VaultRole::Admin,
+        )
+        .await?;
+
+        // Do an IDOR check, does this user have access to the vault. This will
+        // blow up if we don't
+        let vault = queries::vaults::get()
+            .bind(
+                &*transaction,
+                &(req.vault_id as i32),
+                &authenticated_user.user_id,
+            )
+            .opt()
+            .await
+            .map_err(CustomError::from)?
+            .ok_or_else(CustomError::not_found)?;
+
+        if vault.organisation_id != req.organisation_id as i32 {
+            return Err(CustomError::NotFound("Vault is not in this team".to_string()).into());
+        }
+
+        super::vaults::ensure_key_version(&transaction, vault.id, req.key_version as i32).await?;
+
+        queries::user_vaults::insert()
+            .bind(
+                &*transaction,
+                &(req.user_id as i32),
+                &vault.id,
+                &req.ecdh_public_key.as_ref(),
+                &req.wrapped_vault_key.as_ref(),
+                &role,
+                &(req.key_version as i32),
+            )
+            .await
+            .map_err(CustomError::from)?;
+
+        for env in req.environment_ids {
+            queries::environments::connect_environment_to_user()
+                .bind(&*transaction, &(req.user_id as i32), &(env as i32))
+                .await
+                .map_err(CustomError::from)?;
+        }
+
+        Event::new(
+            AuditAction::AddMember,
+            AuditAccessType::API,
+            format!("User {} added to vault {}", req.user_id, vault.name),
+        )
+        .by_user(authenticated_user.user_id)
+        .vault(req.vault_id as i32)
+        .target_user(req.user_id as i32)
+        .source(&source)
+        .insert(&transaction, vault.organisation_id)
+        .await?;
+
+        transaction.commit().await?;
+
+        let response = AddMemberToVaultResponse {};
+
+        Ok(Response::new(response))
+    }
+
+    async fn remove_member_from_vault(
+        &self,
+        request: Request<RemoveMemberFromVaultRequest>,
+    ) -> Result<Response<RemoveMemberFromVaultResponse>, Status> {
+        let authenticated_user = authenticate(
+            &request,
+            &self.pool,
+            Some(request.get_ref().organisation_id),
+        )
+        .await?;
+
+        let source = request_source(&request, self.config.trusted_proxies);
+        let req = request.into_inner();
+
+        // Create a transaction and setup RLS
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_user(&mut client, &authenticated_user).await?;
+
+        // Anyone can take themselves out of a vault.
+        if req.user_id as i32 != authenticated_user.user_id {
+            super::rbac::require_vault_role(
+                &transaction,
+                authenticated_user.user_id,
+                req.vault_id as i32,
+                VaultRole::Admin,
+            )
+            .await?;
+        }
+
+        // Before they're removed, while the vault is still visible.
+        let organisation_id = super::rbac::vault_team(
+            &transaction,
+            authenticated_user.user_id,
+            req.vault_id as i32,
+            req.organisation_id as i32,
+        )
+        .await?;
+
+        queries::user_vaults::remove_user_from_vault()
+            .bind(
+                &*transaction,
+                &(req.vault_id as i32),
+                &(req.user_id as i32),
+                &authenticated_user.user_id,
+            )
+            .await
+            .map_err(CustomError::from)?;
+
+        super::rbac::ensure_vault_administrator(&transaction, req.vault_id as i32).await?;
+
+        Event::new(
+            AuditAction::DeleteMember,
+            AuditAccessType::API,
+            format!(
+                "User {} removed from vault with ID {}",
+                req.user_id, req.vault_id
+            ),
+        )
+        .by_user(authenticated_user.user_id)
+        .vault(req.vault_id as i32)
+        .target_user(req.user_id as i32)
+        .source(&source)
+        .insert(&transaction, organisation_id)
+        .await?;
+
+        transaction.commit().await?;
+
+        let response = RemoveMemberFromVaultResponse {};
+
+        Ok(Response::new(response))
+    }
+
+    async fn list_team_members(
+        &self,
+        request: Request<ListTeamMembersRequest>,
+    ) -> Result<Response<ListTeamMembersResponse>, Status> {
+        let authenticated_user = authenticate(
+            &request,
+            &self.pool,
+            Some(request.get_ref().organisation_id),
+        )
+        .await?;
+
+        let req = request.into_inner();
+
+        // Create a transaction and setup RLS
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_user(&mut client, &authenticated_user).await?;
+
+        let members = queries::organisations::get_users()
+            .bind(&*transaction, &(req.organisation_id as i32))
+            .all()
+            .await
+            .map_err(CustomError::from)?;
+
+        let members = members
+            .into_iter()
+            .map(|member| TeamMember {
+                user_id: member.id as u32,
+                email: member.email,
+                first_name: member.first_name.unwrap_or_default(),
+                last_name: member.last_name.unwrap_or_default(),
+                ecdh_public_key: member.ecdh_public_key,
+                roles: member
+                    .roles
+                    .iter()
+                    .map(|role| format!("{:?}", role))
+                    .chain(member.team_role)
+                    .collect(),
+            })
+            .collect();
+
+        let response = ListTeamMembersResponse { members };
+
+        Ok(Response::new(response))
+    }
+
+    async fn invite_team_member(
+        &self,
+        request: Request<InviteTeamMemberRequest>,
+    ) -> Result<Response<InviteTeamMemberResponse>, Status> {
+        let authenticated_user = authenticate(
+            &request,
+            &self.pool,
+            Some(request.get_ref().organisation_id),
+        )
+        .await?;
+
+        let source = request_source(&request, self.config.trusted_proxies);
+        let req = request.into_inner();
+
+        // Create a transaction and setup RLS
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_user(&mut client, &authenticated_user).await?;
+
+        super::rbac::require(
+            &transaction,
+            authenticated_user.user_id,
+            req.organisation_id as i32,
+            Permission::InviteMembers,
+        )
+        .await?;
+
+        let new_invite = super::team::create_invite::NewInvite {
+            email: req.email,
+            first_name: req.first_name,
+            last_name: req.last_name,
+            role: if req.admin {
+                "Administrator".to_string()
+            } else {
+                "Collaborator".to_string()
+            },
+        };
+
+        let (invitation_verifier_base64, invitation_selector_base64) =
+            super::team::create_invite::create(
+                &transaction,
+                authenticated_user.user_id,
+                &new_invite,
+                req.organisation_id as i32,
+            )
+            .await?;
+
+        Event::new(
+            AuditAction::CreateInvite,
+            AuditAccessType::API,
+            format!("{} invited", &new_invite.email),
+        )
+        .by_user(authenticated_user.user_id)
+        .source(&source)
+        .insert(&transaction, req.organisation_id as i32)
+        .await?;
+
+        transaction.commit().await?;
+
+        super::team::create_invite::send_invite_email(
+            &self.config,
+            &new_invite.email,
+            &invitation_selector_base64,
+            &invitation_verifier_base64,
+        );
+
+        let response = InviteTeamMemberResponse {};
+
+        Ok(Response::new(response))
+    }
+
+    async fn remove_team_member(
+        &self,
+        request: Request<RemoveTeamMemberRequest>,
+    ) -> Result<Response<RemoveTeamMemberResponse>, Status> {
+        let authenticated_user = authenticate(
+            &request,
+            &self.pool,
+            Some(request.get_ref().organisation_id),
+        )
+        .await?;
+
+        let source = request_source(&request, self.config.trusted_proxies);
+        let req = request.into_inner();
+
+        // Create a transaction and setup RLS
+        let mut client = self.pool.get().await.map_err(CustomError::from)?;
+        let transaction =
+            super::rls::RlsTransaction::for_user(&mut client, &authenticated_user).await?;
+
+        super::rbac::require(
+            &transaction,
+            authenticated_user.user_id,
+            req.organisation_id as i32,
+            Permission::ManageTeam,
+        )
+        .await?;
+
+        super::rbac::ensure_not_owner(&transaction, req.user_id as i32, req.organisation_id as i32)
+            .await?;
+
+        queries::organisations::remove_user()
+            .bind(
+                &*transaction,
+                &(req.user_id as i32),
+                &(req.organisation_id as i32),
+            )
+            .await
+            .map_err(CustomError::from)?;
+
+        // Nobody is removed if that was the last Administrator.
+        super::rbac::ensure_administrator(&transaction, req.organisation_id as i32).await?;
+
+        Event::new(
+            AuditAction::RemoveTeamMember,
+            AuditAccessType::API,
+            format!("{} removed from team", req.user_id),
+        )
+        .by_user(authenticated_user.user_id)
+        .target_user(req.user_id as i32)
+        .source(&source)
+        .insert(&transaction, req.organisation_id as i32)
+        .await?;
+
+        transaction.commit().await?;
+
+        let response = RemoveTeamMemberResponse {};
+
+        Ok(Response::new(response))
+    }
+}
+
+// Everything a stream needs to send the next snapshot.
+struct Watch {
+    pool: Pool,
+    changes: broadcast::Receiver<i32>,
+    service_account_id: i32,
+    ecdh_public_key: String,
+    source: Source,
+}
+
+// Fetch the secrets for a service account and audit the access. If we
+// get a nonce it's consumed as part of the same transaction.
+async fn service_account_snapshot(
+    pool: &Pool,
+    ecdh_public_key: &str,
+    nonce: Option<&str>,
+    source: &Source,
+) -> Result<GetServiceAccountResponse, CustomError> {
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::rls::RlsTransaction::for_service_account(&mut client, ecdh_public_key).await?;
+
+    let service_account = queries::service_accounts::get_by_ecdh_public_key()
+        .bind(&*transaction, &ecdh_public_key)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    if let Some(nonce) = nonce {
+        // Blow up if the challenge wasn't issued to this account, has
+        // expired or has already been used.
+        queries::service_account_challenges::consume()
+            .bind(&*transaction, &service_account.id, &nonce)
+            .one()
+            .await
+            .map_err(|_| CustomError::Unauthorized("Challenge not valid".to_string()))?;
+    }
+
+    if service_account.vault_id.is_none() {
+        return Err(CustomError::NotFound(
+            "Service account is not connected to a vault".to_string(),
+        ));
+    }
+
+    let secrets = queries::service_account_secrets::get_all_dangerous()
+        .bind(&*transaction, &service_account.id, &ecdh_public_key)
+        .all()
+        .await?;
+
+    // Still working during the grace period after the key was rotated.
+    let key_used = if service_account.ecdh_public_key == ecdh_public_key {
+        ""
+    } else {
+        " with its previous key"
+    };
+
+    let mut event = Event::new(
+        AuditAction::AccessSecrets,
+        AuditAccessType::CLI,
+        format!(
+            "{} secrets accessed by service account {}{}",
+            secrets.len(),
+            service_account.account_name,
+            key_used
+        ),
+    )
+    .by_service_account(service_account.id)
+    .source(source);
+    if let Some(vault_id) = service_account.vault_id {
+        event = event.vault(vault_id);
+    }
+    if let Some(environment_id) = service_account.environment_id {
+        event = event.environment(environment_id);
+    }
+    event
+        .insert(&transaction, service_account.organisation_id)
+        .await?;
+
+    transaction.commit().await?;
+
+    let secrets = secrets
+        .into_iter()
+        .map(|secret| ServiceAccountSecret {
+            encrypted_name: secret.name,
+            name_blind_index: secret.name_blind_index,
+            encrypted_secret_value: secret.secret,
+            ecdh_public_key: secret.ecdh_public_key,
+        })
+        .collect();
+
+    Ok(GetServiceAccountResponse {
+        service_account_id: service_account.id as u32,
+        secrets,
+    })
+}
+
+// Check the nonce was signed by the private key that belongs to the
+// base64 DER encoded public key.
+fn verify_signature(
+    ecdh_public_key: &str,
+    nonce: &str,
+    signature: &str,
+) -> Result<(), CustomError> {
+    let public_key_der = base64::decode(ecdh_public_key)
+        .map_err(|_| CustomError::Unauthorized("Public key not base64".to_string()))?;
+    let public_key = PublicKey::from_public_key_der(&public_key_der)
+        .map_err(|_| CustomError::Unauthorized("Public key not valid".to_string()))?;
+
+    let signature = base64::decode(signature)
+        .map_err(|_| CustomError::Unauthorized("Signature not base64".to_string()))?;
+    let signature = Signature::from_der(&signature)
+        .map_err(|_| CustomError::Unauthorized("Signature not valid".to_string()))?;
+
+    VerifyingKey::from(&public_key)
+        .verify(nonce.as_bytes(), &signature)
+        .map_err(|_| CustomError::Unauthorized("Signature does not match".to_string()))
+}
+
+// Where the request came from, for the audit trail. Behind envoy the
+// remote address is the proxy so we prefer x-forwarded-for.
+fn request_source<T>(req: &Request<T>, trusted_proxies: usize) -> Source {
+    let ip_address = crate::audit::client_ip(
+        req.metadata()
+            .get("x-forwarded-for")
+            .and_then(|value| value.to_str().ok()),
+        req.remote_addr().map(|addr| addr.ip()),
+        trusted_proxies,
+    );
+
+    let user_agent = req
+        .metadata()
+        .get("user-agent")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+
+    Source {
+        ip_address,
+        user_agent,
+    }
 }
 
 const X_USER_ID: &str = "x-user-id";
+const API_KEY: &str = "api-key";
+const AUTHENTICATION_TYPE: &str = "authentication-type";
 
 // We have 2 types of authentication
-// 1. If we have a header set to "authentication-type" then envoy with have set a x-user-id
-// 2. If it is not set then we must have an API-KEY which we can use to get the user if.
-async fn authenticate<T>(req: &Request<T>) -> Result<authentication::Authentication, Status> {
-    if let Some(api_key) = req.metadata().get(X_USER_ID) {
-        let user_id = api_key
+// 1. If we have a header set to "authentication-type" then envoy will have checked the
+//    cookie and set x-user-id. Anyone else's x-user-id is ignored.
+// 2. If it is not set then we must have an API-KEY which we can use to get the user.
+//    A key only works in the team it was created in, calls without a team can't use one.
+async fn authenticate<T>(
+    req: &Request<T>,
+    pool: &Pool,
+    organisation_id: Option<u32>,
+) -> Result<authentication::Authentication, Status> {
+    let from_envoy = req
+        .metadata()
+        .get(AUTHENTICATION_TYPE)
+        .and_then(|value| value.to_str().ok())
+        == Some("cookie");
+
+    if let (true, Some(user_id)) = (from_envoy, req.metadata().get(X_USER_ID)) {
+        let user_id = user_id
             .to_str()
-            .map_err(|_| Status::new(Code::Internal, "x-user-id not found"))?;
+            .map_err(|_| Status::new(Code::Unauthenticated, "x-user-id not found"))?;
 
-        let user_id: u32 = user_id
-            .parse::<u32>()
-            .map_err(|_| Status::new(Code::Internal, "x-user-id not parseable as unsigned int"))?;
+        let user_id: u32 = user_id.parse::<u32>().map_err(|_| {
+            Status::new(
+                Code::Unauthenticated,
+                "x-user-id not parseable as unsigned int",
+            )
+        })?;
 
         Ok(authentication::Authentication {
             user_id: user_id as i32,
         })
+    } else if let Some(api_key) = req.metadata().get(API_KEY) {
+        let api_key = api_key
+            .to_str()
+            .map_err(|_| Status::new(Code::Unauthenticated, "api-key not readable"))?;
+
+        let organisation_id = organisation_id.ok_or_else(|| {
+            Status::new(
+                Code::PermissionDenied,
+                "API Keys can only be used for calls made in a team",
+            )
+        })?;
+
+        // We don't know who the caller is yet so there's no RLS identity
+        // to set. The lookup goes through a SECURITY DEFINER function instead.
+        let mut client = pool.get().await.map_err(CustomError::from)?;
+        let transaction = client.transaction().await.map_err(CustomError::from)?;
+
+        // Unknown, expired and other team's keys all come back as None.
+        let user_id = queries::api_keys::user_for_api_key()
+            .bind(
+                &transaction,
+                &super::api_keys::hash_api_key(api_key).as_ref(),
+                &(organisation_id as i32),
+            )
+            .one()
+            .await
+            .map_err(CustomError::from)?
+            .ok_or_else(|| Status::new(Code::Unauthenticated, "API Key not valid"))?;
+
+        Ok(authentication::Authentication { user_id })
     } else {
         Err(Status::new(
-            Code::PermissionDenied,
+            Code::Unauthenticated,
             "You need to set an API Key",
         ))
     }
@@ -0,0 +1,187 @@
+// Posts to the handlers against a scratch database and checks their event is
+// in the audit trail afterwards, so an event written in a transaction that
+// never gets committed fails here.
+//
+// DATABASE_URL needs to be a superuser on a local Postgres i.e. the
+// postgres user in our dev container.
+use axum::{
+    body::Body,
+    extract::Extension,
+    http::{header, Request},
+    Router,
+};
+use db::Pool;
+use std::{env, fs};
+use tower::ServiceExt;
+
+const TEST_DATABASE: &str = "cloak_audit_coverage";
+
+// User 1 owns teams 1 and 2.
+const SEED: &str = "
+    INSERT INTO users (email, master_password_hash, protected_symmetric_key,
+        protected_ecdsa_private_key, ecdsa_public_key, protected_ecdh_private_key, ecdh_public_key)
+    VALUES ('owner@test.com', 'hash', 'key', 'key', 'key', 'key', 'key');
+    INSERT INTO organisations (name, created_by_user_id) VALUES ('Team', 1), ('Other Team', 1);
+    INSERT INTO organisation_users (user_id, organisation_id, roles)
+    VALUES (1, 1, '{Administrator}'), (1, 2, '{Administrator}');
+";
+
+// Run in order, so later posts see the changes of earlier ones.
+struct Post {
+    path: &'static str,
+    form: &'static str,
+    // Expected in the audit trail of this team afterwards.
+    team: i32,
+    action: &'static str,
+}
+
+const POSTS: &[Post] = &[
+    Post {
+        path: "/app/team/1/create_invite",
+        form: "email=invited%40test.com&first_name=Invited&last_name=User&role=Collaborator",
+        team: 1,
+        action: "CreateInvite",
+    },
+    Post {
+        path: "/app/team/1/set_name",
+        form: "name=Renamed",
+        team: 1,
+        action: "RenameTeam",
+    },
+    Post {
+        path: "/app/team/1/roles/new",
+        form: "name=Auditor&view_audit=on",
+        team: 1,
+        action: "CreateRole",
+    },
+    // Team 1's audit trail goes with it.
+    Post {
+        path: "/app/team/1/delete_team",
+        form: "name=Renamed",
+        team: 2,
+        action: "DeleteTeam",
+    },
+];
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn handlers_commit_their_audit_events() {
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL not set");
+    let admin_pool = db::create_pool(&database_url);
+    let admin = admin_pool
+        .get()
+        .await
+        .expect("Couldn't connect to the database");
+
+    // Each on its own as they can't run in the implicit transaction of a batch.
+    admin
+        .batch_execute(&format!(
+            "DROP DATABASE IF EXISTS {TEST_DATABASE} WITH (FORCE)"
+        ))
+        .await
+        .expect("Couldn't drop the old test database");
+    admin
+        .batch_execute(&format!("CREATE DATABASE {TEST_DATABASE}"))
+        .await
+        .expect("Couldn't create the test database");
+
+    let pool = db::create_pool(&test_database_url(&database_url));
+    migrate(&pool).await;
+    pool.get().await.unwrap().batch_execute(SEED).await.unwrap();
+
+    let config = crate::config::Config {
+        port: 0,
+        app_database_url: String::new(),
+        smtp_config: None,
+        audit_sink_config: None,
+        trusted_proxies: 0,
+    };
+    let app = Router::new()
+        .merge(crate::team::routes())
+        .layer(Extension(config))
+        .layer(Extension(pool.clone()));
+
+    let mut failures = Vec::new();
+    for post in POSTS {
+        let request = Request::builder()
+            .method("POST")
+            .uri(post.path)
+            .header("x-user-id", "1")
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from(post.form))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        if !response.status().is_redirection() {
+            failures.push(format!("{}: {}", post.path, response.status()));
+            continue;
+        }
+
+        // Counted from a different connection so only what was committed shows.
+        let audited: i64 = pool
+            .get()
+            .await
+            .unwrap()
+            .query_one(
+                "SELECT COUNT(*) FROM audit_trail
+                WHERE organisation_id = $1 AND action::text = $2",
+                &[&post.team, &post.action],
+            )
+            .await
+            .unwrap()
+            .get(0);
+        if audited != 1 {
+            failures.push(format!("{}: {} {} events", post.path, audited, post.action));
+        }
+    }
+
+    admin
+        .batch_execute(&format!("DROP DATABASE {TEST_DATABASE} WITH (FORCE)"))
+        .await
+        .expect("Couldn't drop the test database");
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+// The same server and credentials as DATABASE_URL.
+fn test_database_url(database_url: &str) -> String {
+    let (url, options) = database_url
+        .split_once('?')
+        .map_or((database_url, None), |(url, options)| (url, Some(options)));
+    let (server, _) = url.rsplit_once('/').expect("DATABASE_URL has no database");
+    match options {
+        Some(options) => format!("{server}/{TEST_DATABASE}?{options}"),
+        None => format!("{server}/{TEST_DATABASE}"),
+    }
+}
+
+// Runs the up part of each dbmate migration in order.
+async fn migrate(pool: &Pool) {
+    let client = pool
+        .get()
+        .await
+        .expect("Couldn't connect to the test database");
+    let folder = concat!(env!("CARGO_MANIFEST_DIR"), "/../db/migrations");
+    let mut migrations: Vec<_> = fs::read_dir(folder)
+        .expect("Couldn't read the migrations")
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    migrations.sort();
+
+    // dbmate creates this and the first migration grants access to it.
+    client
+        .batch_execute("CREATE TABLE schema_migrations (version VARCHAR PRIMARY KEY)")
+        .await
+        .unwrap();
+
+    for migration in migrations {
+        let sql = fs::read_to_string(&migration).unwrap();
+        let up = sql
+            .split("-- migrate:down")
+            .next()
+            .unwrap()
+            .replace("-- migrate:up", "");
+        client
+            .batch_execute(&up)
+            .await
+            .unwrap_or_else(|e| panic!("{} failed: {}", migration.display(), e));
+    }
+}
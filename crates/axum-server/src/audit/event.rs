@@ -0,0 +1,183 @@
+use crate::config::Config;
+use crate::errors::CustomError;
+use axum::{
+    async_trait,
+    extract::{FromRequest, RequestParts},
+};
+use db::queries;
+use db::types::public::{AuditAccessType, AuditAction};
+use db::Transaction;
+use std::convert::Infallible;
+use std::net::IpAddr;
+
+// Where a request came from, so we can record it in the audit trail.
+#[derive(Clone, Debug, Default)]
+pub struct Source {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+#[async_trait]
+impl<B> FromRequest<B> for Source
+where
+    B: Send,
+{
+    type Rejection = Infallible;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        // We sit behind a proxy so the address of the connection is the proxy.
+        let trusted_proxies = req
+            .extensions()
+            .get::<Config>()
+            .map(|config| config.trusted_proxies)
+            .unwrap_or_default();
+
+        let ip_address = client_ip(
+            req.headers()
+                .get("x-forwarded-for")
+                .and_then(|value| value.to_str().ok()),
+            None,
+            trusted_proxies,
+        );
+
+        let user_agent = req
+            .headers()
+            .get("user-agent")
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+
+        Ok(Source {
+            ip_address,
+            user_agent,
+        })
+    }
+}
+
+// Each proxy appends the address it was connected from to X-Forwarded-For,
+// so only the entries added by the proxies we trust are real. Anything to
+// the left of those was sent by the client. Without a proxy in front of us
+// we use the address of the connection.
+pub fn client_ip(
+    forwarded_for: Option<&str>,
+    peer: Option<IpAddr>,
+    trusted_proxies: usize,
+) -> Option<String> {
+    let forwarded: Vec<&str> = forwarded_for
+        .unwrap_or_default()
+        .split(',')
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .collect();
+
+    if trusted_proxies == 0 || forwarded.is_empty() {
+        return peer.map(|ip| ip.to_string());
+    }
+
+    // A shorter list means the request came in through fewer proxies,
+    // the first entry was then added by one of ours.
+    let index = forwarded.len().saturating_sub(trusted_proxies);
+    Some(forwarded[index].to_string())
+}
+
+// An entry for the audit trail. Anything it was carried out on goes in its
+// own column so the audit trail can be filtered by it.
+#[derive(Debug)]
+pub struct Event {
+    action: AuditAction,
+    access_type: AuditAccessType,
+    description: String,
+    user_id: Option<i32>,
+    service_account_id: Option<i32>,
+    vault_id: Option<i32>,
+    secret_id: Option<i32>,
+    environment_id: Option<i32>,
+    target_user_id: Option<i32>,
+    target_service_account_id: Option<i32>,
+    source: Source,
+}
+
+impl Event {
+    pub fn new(action: AuditAction, access_type: AuditAccessType, description: String) -> Event {
+        Event {
+            action,
+            access_type,
+            description,
+            user_id: None,
+            service_account_id: None,
+            vault_id: None,
+            secret_id: None,
+            environment_id: None,
+            target_user_id: None,
+            target_service_account_id: None,
+            source: Source::default(),
+        }
+    }
+
+    // Every event is carried out by either a user or a service account.
+    pub fn by_user(mut self, user_id: i32) -> Event {
+        self.user_id = Some(user_id);
+        self
+    }
+
+    pub fn by_service_account(mut self, service_account_id: i32) -> Event {
+        self.service_account_id = Some(service_account_id);
+        self
+    }
+
+    pub fn vault(mut self, vault_id: i32) -> Event {
+        self.vault_id = Some(vault_id);
+        self
+    }
+
+    pub fn secret(mut self, secret_id: i32) -> Event {
+        self.secret_id = Some(secret_id);
+        self
+    }
+
+    pub fn environment(mut self, environment_id: i32) -> Event {
+        self.environment_id = Some(environment_id);
+        self
+    }
+
+    pub fn target_user(mut self, user_id: i32) -> Event {
+        self.target_user_id = Some(user_id);
+        self
+    }
+
+    pub fn target_service_account(mut self, service_account_id: i32) -> Event {
+        self.target_service_account_id = Some(service_account_id);
+        self
+    }
+
+    pub fn source(mut self, source: &Source) -> Event {
+        self.source = source.clone();
+        self
+    }
+
+    pub async fn insert(
+        self,
+        transaction: &Transaction<'_>,
+        organisation_id: i32,
+    ) -> Result<(), CustomError> {
+        queries::audit::insert()
+            .bind(
+                transaction,
+                &self.user_id,
+                &self.service_account_id,
+                &organisation_id,
+                &self.action,
+                &self.access_type,
+                &self.description.as_ref(),
+                &self.vault_id,
+                &self.secret_id,
+                &self.environment_id,
+                &self.target_user_id,
+                &self.target_service_account_id,
+                &self.source.ip_address.as_deref(),
+                &self.source.user_agent.as_deref(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
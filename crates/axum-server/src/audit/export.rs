@@ -0,0 +1,267 @@
+use crate::audit::Source;
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use crate::rbac::{Authorize, ViewAudit};
+use axum::{
+    body::StreamBody,
+    extract::{Extension, Path, Query},
+    http::header,
+    response::IntoResponse,
+};
+use db::queries;
+use db::types::public::{AuditAccessType, AuditAction};
+use db::{AuditExport, Pool};
+use futures::channel::mpsc;
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+
+#[derive(Deserialize, Default, Debug)]
+pub struct Export {
+    // Dates as YYYY-MM-DD, both inclusive.
+    pub from: String,
+    pub to: String,
+    pub format: String,
+}
+
+const CSV_HEADER: &str = "sequence,created_at,user,service_account,access_type,action,\
+    description,ip_address,user_agent,vault_id,vault,secret_id,environment_id,environment,\
+    target_user_id,target_user,target_service_account_id,target_service_account,\
+    previous_hash,hash\n";
+
+pub async fn export(
+    Path(organisation_id): Path<i32>,
+    current_user: Authentication,
+    source: Source,
+    _: Authorize<ViewAudit>,
+    Extension(pool): Extension<Pool>,
+    Query(export): Query<Export>,
+) -> Result<impl IntoResponse, CustomError> {
+    if !is_date(&export.from) || !is_date(&export.to) {
+        return Err(CustomError::InvalidInput(
+            "Dates should be in the format YYYY-MM-DD".to_string(),
+        ));
+    }
+
+    let (content_type, extension) = match export.format.as_ref() {
+        "csv" => ("text/csv", "csv"),
+        "jsonl" => ("application/x-ndjson", "jsonl"),
+        _ => {
+            return Err(CustomError::InvalidInput(
+                "The format should be csv or jsonl".to_string(),
+            ))
+        }
+    };
+
+    // Record the export before we start, RLS also stops us recording it
+    // for a team we're not in.
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    let description = format!(
+        "Exported the audit trail from {} to {} as {}",
+        export.from, export.to, extension
+    );
+
+    crate::audit::Event::new(
+        AuditAction::ExportAuditTrail,
+        AuditAccessType::Web,
+        description,
+    )
+    .by_user(current_user.user_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
+
+    transaction.commit().await?;
+
+    let filename = format!(
+        "attachment; filename=\"audit-{}-{}-to-{}.{}\"",
+        organisation_id, export.from, export.to, extension
+    );
+
+    // The rows are sent as we read them, so a long history never has to
+    // fit in memory.
+    let (mut sender, receiver) = mpsc::channel(100);
+    let user_id = current_user.user_id;
+    tokio::spawn(async move {
+        if let Err(e) = send_lines(pool, user_id, organisation_id, export, &mut sender).await {
+            tracing::error!("audit export failed: {}", e);
+            // Break the download rather than let it look complete.
+            let _ = sender
+                .send(Err(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    "export failed",
+                )))
+                .await;
+        }
+    });
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, filename),
+        ],
+        StreamBody::new(receiver),
+    ))
+}
+
+async fn send_lines(
+    pool: Pool,
+    user_id: i32,
+    organisation_id: i32,
+    export: Export,
+    lines: &mut mpsc::Sender<Result<String, std::io::Error>>,
+) -> Result<(), CustomError> {
+    let csv = export.format == "csv";
+    // An error sending means the download was cancelled.
+    if csv && lines.send(Ok(CSV_HEADER.to_string())).await.is_err() {
+        return Ok(());
+    }
+
+    let current_user = Authentication { user_id };
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    let entries = queries::audit::export()
+        .bind(
+            &*transaction,
+            &organisation_id,
+            &export.from.as_ref(),
+            &export.to.as_ref(),
+        )
+        .iter()
+        .await?;
+    futures::pin_mut!(entries);
+
+    while let Some(entry) = entries.next().await {
+        let entry = entry?;
+        let line = if csv {
+            to_csv(&entry)
+        } else {
+            to_json_line(&entry)
+        };
+        if lines.send(Ok(line)).await.is_err() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn to_csv(entry: &AuditExport) -> String {
+    let fields = [
+        entry.sequence.to_string(),
+        entry.created_at.clone(),
+        entry.email.clone().unwrap_or_default(),
+        entry.service_account_name.clone().unwrap_or_default(),
+        entry.access_type.clone(),
+        entry.action.clone(),
+        entry.description.clone(),
+        entry.ip_address.clone().unwrap_or_default(),
+        entry.user_agent.clone().unwrap_or_default(),
+        id_field(entry.vault_id),
+        entry.vault_name.clone().unwrap_or_default(),
+        id_field(entry.secret_id),
+        id_field(entry.environment_id),
+        entry.environment_name.clone().unwrap_or_default(),
+        id_field(entry.target_user_id),
+        entry.target_email.clone().unwrap_or_default(),
+        id_field(entry.target_service_account_id),
+        entry
+            .target_service_account_name
+            .clone()
+            .unwrap_or_default(),
+        entry.previous_hash.clone().unwrap_or_default(),
+        entry.hash.clone(),
+    ];
+    let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+    format!("{}\n", fields.join(","))
+}
+
+fn id_field(id: Option<i32>) -> String {
+    id.map(|id| id.to_string()).unwrap_or_default()
+}
+
+// Quote anything that could break the row, and stop spreadsheets treating
+// user supplied text as a formula. Some skip a leading tab or carriage return
+// before looking for one, so those are escaped too.
+fn csv_field(field: &str) -> String {
+    let field = if field.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", field)
+    } else {
+        field.to_string()
+    };
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+fn to_json_line(entry: &AuditExport) -> String {
+    let json = serde_json::json!({
+        "sequence": entry.sequence,
+        "created_at": entry.created_at,
+        "user": entry.email,
+        "service_account": entry.service_account_name,
+        "access_type": entry.access_type,
+        "action": entry.action,
+        "description": entry.description,
+        "ip_address": entry.ip_address,
+        "user_agent": entry.user_agent,
+        "vault_id": entry.vault_id,
+        "vault": entry.vault_name,
+        "secret_id": entry.secret_id,
+        "environment_id": entry.environment_id,
+        "environment": entry.environment_name,
+        "target_user_id": entry.target_user_id,
+        "target_user": entry.target_email,
+        "target_service_account_id": entry.target_service_account_id,
+        "target_service_account": entry.target_service_account_name,
+        "previous_hash": entry.previous_hash,
+        "hash": entry.hash,
+    });
+    format!("{}\n", json)
+}
+
+// YYYY-MM-DD with a day that exists in that month.
+pub fn is_date(date: &str) -> bool {
+    let parts: Vec<&str> = date.split('-').collect();
+    if parts.len() != 3 || parts[0].len() != 4 || parts[1].len() != 2 || parts[2].len() != 2 {
+        return false;
+    }
+    let (year, month, day) = match (
+        parts[0].parse::<u32>(),
+        parts[1].parse::<u32>(),
+        parts[2].parse::<u32>(),
+    ) {
+        (Ok(year), Ok(month), Ok(day)) => (year, month, day),
+        _ => return false,
+    };
+    let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days_in_month).contains(&day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::csv_field;
+
+    #[test]
+    fn escapes_a_leading_tab() {
+        assert_eq!(csv_field("\t=1+1"), "'\t=1+1");
+    }
+
+    #[test]
+    fn escapes_a_leading_carriage_return() {
+        assert_eq!(csv_field("\r=1+1"), "\"'\r=1+1\"");
+    }
+}
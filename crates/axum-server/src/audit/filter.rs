@@ -1,25 +1,37 @@
-use crate::authentication::Authentication;
-use crate::errors::CustomError;
-use axum::{
-    extract::{Extension, Form, Path},
-    response::Html,
-};
-use db::queries;
 use db::types;
-use db::Pool;
+use db::Audit;
 use serde::Deserialize;
 
-#[derive(Deserialize, Default, Debug)]
+// Everything is in the query string so a filtered view can be bookmarked
+// and shared.
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(default)]
 pub struct Filter {
-    pub id: i32,
+    // Page cursors, the entries older than before or newer than after.
+    pub before: i32,
+    pub after: i32,
     pub user: i32,
     pub access_type: u32,
     pub action: u32,
+    pub vault: i32,
+    pub target: i32,
+    // Dates as YYYY-MM-DD, both inclusive.
+    pub from: String,
+    pub to: String,
+    // Text in the description.
+    pub search: String,
 }
 
 impl Filter {
-    pub fn get_id(&self) -> Option<i32> {
-        match self.id {
+    pub fn get_before(&self) -> Option<i32> {
+        match self.before {
+            0 => None,
+            n => Some(n),
+        }
+    }
+
+    pub fn get_after(&self) -> Option<i32> {
+        match self.after {
             0 => None,
             n => Some(n),
         }
@@ -32,12 +44,40 @@ impl Filter {
         }
     }
 
+    pub fn get_vault(&self) -> Option<i32> {
+        match self.vault {
+            0 => None,
+            n => Some(n),
+        }
+    }
+
+    // The user an action was carried out on.
+    pub fn get_target(&self) -> Option<i32> {
+        match self.target {
+            0 => None,
+            n => Some(n),
+        }
+    }
+
+    pub fn get_from(&self) -> Option<&str> {
+        empty_to_none(&self.from)
+    }
+
+    pub fn get_to(&self) -> Option<&str> {
+        empty_to_none(&self.to)
+    }
+
+    pub fn get_search(&self) -> Option<&str> {
+        empty_to_none(self.search.trim())
+    }
+
     pub fn convert_to_access_type(&self) -> Option<types::public::AuditAccessType> {
         match self.access_type {
             0 => None,
             1 => Some(types::public::AuditAccessType::Web),
             2 => Some(types::public::AuditAccessType::CLI),
-            _ => Some(types::public::AuditAccessType::ServiceAccount),
+            3 => Some(types::public::AuditAccessType::ServiceAccount),
+            _ => Some(types::public::AuditAccessType::API),
         }
     }
 
@@ -55,43 +95,125 @@ impl Filter {
             9 => Some(types::public::AuditAction::CreateInvite),
             10 => Some(types::public::AuditAction::RemoveTeamMember),
             11 => Some(types::public::AuditAction::CreateVault),
-            _ => Some(types::public::AuditAction::DeleteVault),
+            12 => Some(types::public::AuditAction::DeleteVault),
+            13 => Some(types::public::AuditAction::CreateApiKey),
+            14 => Some(types::public::AuditAction::RevokeApiKey),
+            15 => Some(types::public::AuditAction::RollbackSecret),
+            16 => Some(types::public::AuditAction::UpdateSecret),
+            17 => Some(types::public::AuditAction::ChangeEnvironmentAccess),
+            18 => Some(types::public::AuditAction::ChangeAuditRetention),
+            19 => Some(types::public::AuditAction::ExportAuditTrail),
+            20 => Some(types::public::AuditAction::AcceptInvite),
+            21 => Some(types::public::AuditAction::RenameTeam),
+            22 => Some(types::public::AuditAction::CreateTeam),
+            23 => Some(types::public::AuditAction::UpdateProfile),
+            24 => Some(types::public::AuditAction::CreateEnvironment),
+            25 => Some(types::public::AuditAction::RenameEnvironment),
+            26 => Some(types::public::AuditAction::DeleteEnvironment),
+            27 => Some(types::public::AuditAction::ShareSecrets),
+            28 => Some(types::public::AuditAction::CreateRole),
+            29 => Some(types::public::AuditAction::DeleteRole),
+            30 => Some(types::public::AuditAction::ChangeRole),
+            31 => Some(types::public::AuditAction::TransferTeam),
+            32 => Some(types::public::AuditAction::LeaveTeam),
+            33 => Some(types::public::AuditAction::RotateVaultKey),
+            34 => Some(types::public::AuditAction::RotateServiceAccountKey),
+            35 => Some(types::public::AuditAction::RetireServiceAccountKey),
+            _ => Some(types::public::AuditAction::DeleteTeam),
         }
     }
-}
 
-pub async fn filter(
-    Path(organisation_id): Path<i32>,
-    current_user: Authentication,
-    Extension(pool): Extension<Pool>,
-    Form(filter_form): Form<Filter>,
-) -> Result<Html<String>, CustomError> {
-    // Create a transaction and setup RLS
-    let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
-
-    let team_users = queries::organisations::get_users()
-        .bind(&transaction, &organisation_id)
-        .all()
-        .await?;
-
-    let audits = queries::audit::audit()
-        .bind(
-            &transaction,
-            &filter_form.get_id(),
-            &filter_form.convert_to_action(),
-            &filter_form.convert_to_access_type(),
-            &filter_form.get_user(),
-            &organisation_id,
-            &(super::PAGE_SIZE + 1),
+    // The links to the pages either side of the audits we're showing. There
+    // was one more audit than we show if there is a page in the direction
+    // we were going.
+    pub fn pages(
+        &self,
+        organisation_id: i32,
+        audits: &[Audit],
+        more: bool,
+    ) -> (Option<String>, Option<String>) {
+        let (newest, oldest) = match (audits.first(), audits.last()) {
+            (Some(newest), Some(oldest)) => (newest.id, oldest.id),
+            _ => return (None, None),
+        };
+
+        let going_back = self.after != 0;
+        let has_newer = if going_back { more } else { self.before != 0 };
+        let has_older = if going_back { true } else { more };
+
+        let prev_page_url = has_newer.then(|| {
+            Filter {
+                before: 0,
+                after: newest,
+                ..self.clone()
+            }
+            .url(organisation_id)
+        });
+        let next_page_url = has_older.then(|| {
+            Filter {
+                before: oldest,
+                after: 0,
+                ..self.clone()
+            }
+            .url(organisation_id)
+        });
+
+        (prev_page_url, next_page_url)
+    }
+
+    // Leaves out anything that isn't set to keep the URL short.
+    pub fn url(&self, organisation_id: i32) -> String {
+        let mut params: Vec<(&str, String)> = Vec::new();
+        let numbers = [
+            ("before", self.before),
+            ("after", self.after),
+            ("user", self.user),
+            ("access_type", self.access_type as i32),
+            ("action", self.action as i32),
+            ("vault", self.vault),
+            ("target", self.target),
+        ];
+        for (name, value) in numbers {
+            if value != 0 {
+                params.push((name, value.to_string()));
+            }
+        }
+        for (name, value) in [
+            ("from", &self.from),
+            ("to", &self.to),
+            ("search", &self.search),
+        ] {
+            if !value.is_empty() {
+                params.push((name, value.clone()));
+            }
+        }
+
+        format!(
+            "{}?{}",
+            ui_components::routes::audit::index_route(organisation_id),
+            serde_urlencoded::to_string(params).unwrap_or_default()
         )
-        .all()
-        .await?;
-
-    Ok(Html(ui_components::audit::index::index(
-        organisation_id,
-        team_users,
-        audits,
-    )))
+    }
+
+    // What the filter drawer shows as selected.
+    pub fn to_ui(&self) -> ui_components::audit::filter::AuditFilter {
+        ui_components::audit::filter::AuditFilter {
+            user: self.user,
+            access_type: self.access_type,
+            action: self.action,
+            vault: self.vault,
+            target: self.target,
+            from: self.from.clone(),
+            to: self.to.clone(),
+            search: self.search.clone(),
+        }
+    }
+}
+
+fn empty_to_none(value: &str) -> Option<&str> {
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
 }
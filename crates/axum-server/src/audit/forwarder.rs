@@ -0,0 +1,117 @@
+use crate::config::AuditSinkConfig;
+use crate::errors::CustomError;
+use audit_sink::{AuditEvent, Sink};
+use db::queries;
+use db::Pool;
+use std::time::Duration;
+
+// How often we look for audit entries to forward when there's nothing waiting.
+const FORWARD_EVERY: Duration = Duration::from_secs(5);
+const BATCH_SIZE: i32 = 100;
+
+// Runs in the background for as long as the server does. Entries are only
+// taken out of the outbox once the collector has them, so if it's down they
+// wait there until it comes back.
+pub async fn forward_audit_events(pool: Pool, config: Option<AuditSinkConfig>) {
+    let mut sink = match config {
+        Some(config) => match Sink::new(&config.url, config.options()) {
+            Ok(sink) => Some(sink),
+            Err(e) => {
+                // Leave the entries in the outbox for when it's fixed.
+                tracing::error!("could not start forwarding the audit trail: {}", e);
+                return;
+            }
+        },
+        None => None,
+    };
+
+    loop {
+        let result = match sink.as_mut() {
+            Some(sink) => forward(&pool, sink).await,
+            None => discard(&pool).await,
+        };
+        match result {
+            // There's probably more waiting.
+            Ok(BATCH_SIZE) => continue,
+            Ok(_) => {}
+            Err(e) => tracing::error!("could not forward audit entries: {}", e),
+        }
+        tokio::time::sleep(FORWARD_EVERY).await;
+    }
+}
+
+async fn forward(pool: &Pool, sink: &mut Sink) -> Result<i32, CustomError> {
+    let mut client = pool.get().await?;
+    // Not an RLS transaction, the outbox covers every team.
+    let transaction = client.transaction().await?;
+
+    let claimed = queries::audit_outbox::claim()
+        .bind(&transaction, &BATCH_SIZE)
+        .all()
+        .await?;
+
+    if claimed.is_empty() {
+        return Ok(0);
+    }
+
+    let ids: Vec<i32> = claimed.iter().map(|entry| entry.outbox_id).collect();
+
+    let events: Vec<AuditEvent> = claimed
+        .into_iter()
+        .map(|entry| AuditEvent {
+            id: entry.id,
+            organisation_id: entry.organisation_id,
+            sequence: entry.sequence,
+            created_at: entry.created_at,
+            user_id: entry.user_id,
+            service_account_id: entry.service_account_id,
+            access_type: entry.access_type,
+            action: entry.action,
+            description: entry.description,
+            ip_address: entry.ip_address,
+            user_agent: entry.user_agent,
+            vault_id: entry.vault_id,
+            secret_id: entry.secret_id,
+            environment_id: entry.environment_id,
+            target_user_id: entry.target_user_id,
+            target_service_account_id: entry.target_service_account_id,
+            hash: entry.hash,
+        })
+        .collect();
+
+    let forwarded = match sink.send(&events).await {
+        Ok(()) => {
+            queries::audit_outbox::forwarded()
+                .bind(&transaction, &ids.as_ref())
+                .one()
+                .await?
+        }
+        Err(e) => {
+            tracing::error!("could not send {} audit entries: {}", ids.len(), e);
+            queries::audit_outbox::failed()
+                .bind(&transaction, &ids.as_ref(), &e.to_string().as_ref())
+                .one()
+                .await?;
+            0
+        }
+    };
+
+    transaction.commit().await?;
+
+    Ok(forwarded)
+}
+
+// Without an audit sink there's nobody to forward to.
+async fn discard(pool: &Pool) -> Result<i32, CustomError> {
+    let mut client = pool.get().await?;
+    let transaction = client.transaction().await?;
+
+    let discarded = queries::audit_outbox::discard()
+        .bind(&transaction)
+        .one()
+        .await?;
+
+    transaction.commit().await?;
+
+    Ok(discarded)
+}
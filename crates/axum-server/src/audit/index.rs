@@ -1,7 +1,9 @@
+use super::filter::Filter;
 use crate::authentication::Authentication;
 use crate::errors::CustomError;
+use crate::rbac::{Authorize, ViewAudit};
 use axum::{
-    extract::{Extension, Path},
+    extract::{Extension, Path, Query},
     response::Html,
 };
 use db::queries;
@@ -10,34 +12,75 @@ use db::Pool;
 pub async fn index(
     Path(organisation_id): Path<i32>,
     current_user: Authentication,
+    _: Authorize<ViewAudit>,
     Extension(pool): Extension<Pool>,
+    Query(filter): Query<Filter>,
 ) -> Result<Html<String>, CustomError> {
+    for date in [filter.get_from(), filter.get_to()].into_iter().flatten() {
+        if !super::export::is_date(date) {
+            return Err(CustomError::InvalidInput(
+                "Dates should be in the format YYYY-MM-DD".to_string(),
+            ));
+        }
+    }
+
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
 
     let team_users = queries::organisations::get_users()
-        .bind(&transaction, &organisation_id)
+        .bind(&*transaction, &organisation_id)
+        .all()
+        .await?;
+
+    let vaults = queries::vaults::get_all()
+        .bind(&*transaction, &current_user.user_id, &organisation_id)
         .all()
         .await?;
 
-    let audits = queries::audit::audit()
+    // One more than a page so we know if there's another page.
+    let mut audits = queries::audit::audit()
         .bind(
-            &transaction,
-            &None,
-            &None,
-            &None,
-            &None,
+            &*transaction,
+            &filter.get_before(),
+            &filter.get_after(),
+            &filter.convert_to_action(),
+            &filter.convert_to_access_type(),
+            &filter.get_user(),
+            &filter.get_vault(),
+            &filter.get_target(),
+            &filter.get_from(),
+            &filter.get_to(),
+            &filter.get_search(),
             &organisation_id,
             &(super::PAGE_SIZE + 1),
         )
         .all()
         .await?;
 
+    let more = audits.len() > super::PAGE_SIZE as usize;
+    audits.truncate(super::PAGE_SIZE as usize);
+    // Going back a page they come oldest first.
+    if filter.get_after().is_some() {
+        audits.reverse();
+    }
+
+    let (prev_page_url, next_page_url) = filter.pages(organisation_id, &audits, more);
+
+    let retention = queries::organisations::audit_retention()
+        .bind(&*transaction, &organisation_id)
+        .one()
+        .await?;
+
     Ok(Html(ui_components::audit::index::index(
         organisation_id,
         team_users,
+        vaults,
         audits,
+        filter.to_ui(),
+        prev_page_url,
+        next_page_url,
+        retention,
     )))
 }
@@ -1,5 +1,14 @@
+#[cfg(test)]
+mod coverage;
+mod event;
+mod export;
 mod filter;
+pub mod forwarder;
 mod index;
+pub mod retention;
+pub mod verify;
+
+pub use event::{client_ip, Event, Source};
 
 pub const PAGE_SIZE: i64 = 10;
 
@@ -11,5 +20,16 @@ use axum::{
 pub fn routes() -> Router {
     Router::new()
         .route("/app/team/:organisation_id/audit", get(index::index))
-        .route("/app/team/:organisation_id/audit", post(filter::filter))
+        .route(
+            "/app/team/:organisation_id/audit/verify",
+            post(verify::verify),
+        )
+        .route(
+            "/app/team/:organisation_id/audit/export",
+            get(export::export),
+        )
+        .route(
+            "/app/team/:organisation_id/audit/retention",
+            post(retention::set_retention),
+        )
 }
@@ -0,0 +1,110 @@
+use crate::audit::Source;
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use crate::rbac::{Authorize, ManageTeam};
+use axum::{
+    extract::{Extension, Form, Path},
+    response::IntoResponse,
+};
+use db::queries;
+use db::types::public::{AuditAccessType, AuditAction, AuditRetention};
+use db::Pool;
+use serde::Deserialize;
+use std::time::Duration;
+
+// How often we look for audit entries that have passed their retention period.
+const EXPIRE_EVERY: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Deserialize, Default, Debug)]
+pub struct Retention {
+    pub days: u32,
+    pub retention: u32,
+}
+
+impl Retention {
+    pub fn get_days(&self) -> Option<i32> {
+        match self.days {
+            0 => None,
+            n => Some(n as i32),
+        }
+    }
+
+    pub fn convert_to_retention(&self) -> AuditRetention {
+        match self.retention {
+            2 => AuditRetention::Purge,
+            _ => AuditRetention::Archive,
+        }
+    }
+}
+
+pub async fn set_retention(
+    Path(organisation_id): Path<i32>,
+    current_user: Authentication,
+    source: Source,
+    _: Authorize<ManageTeam>,
+    Extension(pool): Extension<Pool>,
+    Form(retention): Form<Retention>,
+) -> Result<impl IntoResponse, CustomError> {
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    let days = retention.get_days();
+    let audit_retention = retention.convert_to_retention();
+
+    queries::organisations::set_audit_retention()
+        .bind(&*transaction, &days, &audit_retention, &organisation_id)
+        .await?;
+
+    let description = match (days, audit_retention) {
+        (None, _) => "Set the audit trail to be kept forever".to_string(),
+        (Some(days), AuditRetention::Archive) => {
+            format!("Set audit entries to be archived after {} days", days)
+        }
+        (Some(days), AuditRetention::Purge) => {
+            format!("Set audit entries to be purged after {} days", days)
+        }
+    };
+
+    crate::audit::Event::new(
+        AuditAction::ChangeAuditRetention,
+        AuditAccessType::Web,
+        description,
+    )
+    .by_user(current_user.user_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
+
+    transaction.commit().await?;
+
+    crate::layout::redirect_and_snackbar(
+        &ui_components::routes::audit::index_route(organisation_id),
+        "Audit Retention Updated",
+    )
+}
+
+// Runs in the background for as long as the server does.
+pub async fn expire_audit_trails(pool: Pool) {
+    loop {
+        match expire(&pool).await {
+            Ok(0) => {}
+            Ok(expired) => tracing::info!("expired {} audit entries", expired),
+            Err(e) => tracing::error!("could not expire audit entries: {}", e),
+        }
+        tokio::time::sleep(EXPIRE_EVERY).await;
+    }
+}
+
+async fn expire(pool: &Pool) -> Result<i32, CustomError> {
+    let mut client = pool.get().await?;
+    // Not an RLS transaction, expire_audit_trail covers every team.
+    let transaction = client.transaction().await?;
+
+    let expired = queries::audit::expire().bind(&transaction).one().await?;
+
+    transaction.commit().await?;
+
+    Ok(expired)
+}
@@ -0,0 +1,83 @@
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use crate::rbac::{Authorize, ViewAudit};
+use axum::{
+    extract::{Extension, Path},
+    response::IntoResponse,
+};
+use db::queries;
+use db::Pool;
+use std::env;
+
+pub async fn verify(
+    Path(organisation_id): Path<i32>,
+    current_user: Authentication,
+    _: Authorize<ViewAudit>,
+    Extension(pool): Extension<Pool>,
+) -> Result<impl IntoResponse, CustomError> {
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    let problems = queries::audit::verify()
+        .bind(&*transaction, &organisation_id)
+        .all()
+        .await?;
+
+    let url = ui_components::routes::audit::index_route(organisation_id);
+
+    if problems.is_empty() {
+        return crate::layout::redirect_and_snackbar(&url, "Audit Trail Verified");
+    }
+
+    for problem in problems {
+        tracing::error!(
+            "Audit trail for team {} entry {}: {}",
+            organisation_id,
+            problem.audit_sequence,
+            problem.problem
+        );
+    }
+
+    crate::layout::redirect_and_snackbar(&url, "Audit Trail Has Been Tampered With")
+}
+
+// The admin command, run with the server binary i.e. ./axum-server verify-audit
+// in the server container (not the cloak CLI). Checks every team so it needs a
+// DATABASE_URL that can see all of the audit trail, the readonly user can.
+pub async fn verify_all_teams() -> Result<bool, CustomError> {
+    let database_url = env::var("DATABASE_URL")
+        .map_err(|_| CustomError::FaultySetup("DATABASE_URL not set".to_string()))?;
+    let pool = db::create_pool(&database_url);
+    let mut client = pool.get().await?;
+    // Not an RLS transaction, we're not acting for a user here.
+    let transaction = client.transaction().await?;
+
+    let organisations = queries::audit::organisations()
+        .bind(&transaction)
+        .all()
+        .await?;
+
+    let mut verified = true;
+    for organisation_id in organisations {
+        let problems = queries::audit::verify()
+            .bind(&transaction, &organisation_id)
+            .all()
+            .await?;
+
+        if problems.is_empty() {
+            println!("Team {}: verified", organisation_id);
+        }
+
+        for problem in problems {
+            verified = false;
+            println!(
+                "Team {}: entry {}: {}",
+                organisation_id, problem.audit_sequence, problem.problem
+            );
+        }
+    }
+
+    Ok(verified)
+}
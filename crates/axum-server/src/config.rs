@@ -40,6 +40,40 @@ impl SmtpConfig {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct AuditSinkConfig {
+    // i.e. udp://siem:514, tls://siem:6514 or https://siem/collect
+    pub url: String,
+    // PEM for a collector with a private CA.
+    pub ca_certificates: Option<Vec<u8>>,
+    // Bearer token for HTTP collectors.
+    pub token: Option<String>,
+}
+
+impl AuditSinkConfig {
+    pub fn new() -> Option<AuditSinkConfig> {
+        let url = env::var("AUDIT_SINK_URL").ok()?;
+
+        let ca_certificates = env::var("AUDIT_SINK_CA_FILE")
+            .ok()
+            .map(|path| std::fs::read(path).expect("AUDIT_SINK_CA_FILE can't be read"));
+
+        Some(AuditSinkConfig {
+            url,
+            ca_certificates,
+            token: env::var("AUDIT_SINK_TOKEN").ok(),
+        })
+    }
+
+    pub fn options(&self) -> audit_sink::Options {
+        audit_sink::Options {
+            ca_certificates: self.ca_certificates.clone(),
+            token: self.token.clone(),
+            hostname: env::var("HOSTNAME").ok(),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Config {
     pub port: u16,
@@ -47,6 +81,11 @@ pub struct Config {
     pub app_database_url: String,
     // Configure SMTP for email.
     pub smtp_config: Option<SmtpConfig>,
+    // Forward the audit trail to a SIEM.
+    pub audit_sink_config: Option<AuditSinkConfig>,
+    // How many proxies in front of us add to X-Forwarded-For, 0 if
+    // clients connect to us directly.
+    pub trusted_proxies: usize,
 }
 
 impl Config {
@@ -59,10 +98,22 @@ impl Config {
 
         let app_database_url = env::var("APP_DATABASE_URL").expect("APP_DATABASE_URL not set");
 
+        // Envoy sits in front of us.
+        let trusted_proxies: usize = if env::var("TRUSTED_PROXIES").is_ok() {
+            env::var("TRUSTED_PROXIES")
+                .unwrap()
+                .parse::<usize>()
+                .unwrap()
+        } else {
+            1
+        };
+
         Config {
             port,
             app_database_url,
             smtp_config: SmtpConfig::new(),
+            audit_sink_config: AuditSinkConfig::new(),
+            trusted_proxies,
         }
     }
 }
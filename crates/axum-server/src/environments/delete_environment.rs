@@ -0,0 +1,83 @@
+use crate::audit::Source;
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use axum::{
+    extract::{Extension, Form, Path},
+    response::IntoResponse,
+};
+use db::queries;
+use db::types::public::{AuditAccessType, AuditAction, VaultRole};
+use db::Pool;
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate, Default, Debug)]
+pub struct DeleteEnvironment {
+    pub environment_id: i32,
+    pub name: String,
+}
+
+pub async fn delete(
+    Path((organisation_id, vault_id)): Path<(i32, i32)>,
+    current_user: Authentication,
+    source: Source,
+    Form(delete_environment): Form<DeleteEnvironment>,
+    Extension(pool): Extension<Pool>,
+) -> Result<impl IntoResponse, CustomError> {
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    // Only vault Admins can change the environments.
+    crate::rbac::require_vault_role(
+        &transaction,
+        current_user.user_id,
+        vault_id,
+        VaultRole::Admin,
+    )
+    .await?;
+
+    // Blow up if the environment isn't in this vault or we can't see it
+    let environment = queries::environments::get()
+        .bind(&*transaction, &delete_environment.environment_id, &vault_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    let url = ui_components::routes::environments::index_route(vault_id, organisation_id);
+
+    if environment.name != delete_environment.name {
+        return crate::layout::redirect_and_snackbar(&url, "Name did not match");
+    }
+
+    // Service accounts connected to the environment are disconnected and
+    // lose their copies of the secrets.
+    queries::environments::delete_service_account_secrets()
+        .bind(&*transaction, &environment.id)
+        .await?;
+    queries::environments::disconnect_service_accounts()
+        .bind(&*transaction, &environment.id)
+        .await?;
+
+    // The database removes the secrets and users_environments rows.
+    queries::environments::delete()
+        .bind(&*transaction, &environment.id, &vault_id)
+        .await?;
+
+    // The environment has gone so it's only in the description.
+    crate::audit::Event::new(
+        AuditAction::DeleteEnvironment,
+        AuditAccessType::Web,
+        format!("Environment {} deleted", environment.name),
+    )
+    .by_user(current_user.user_id)
+    .vault(vault_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
+
+    transaction.commit().await?;
+
+    crate::layout::redirect_and_snackbar(&url, "Environment Deleted")
+}
@@ -0,0 +1,43 @@
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use axum::{
+    extract::{Extension, Path},
+    response::Html,
+};
+use db::queries;
+use db::Pool;
+
+pub async fn index(
+    Path((team_id, vault_id)): Path<(i32, i32)>,
+    Extension(pool): Extension<Pool>,
+    current_user: Authentication,
+) -> Result<Html<String>, CustomError> {
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    let team = queries::organisations::organisation()
+        .bind(&*transaction, &team_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    // Blow up if the user doesn't have access to the vault
+    let user_vault = queries::user_vaults::get()
+        .bind(&*transaction, &current_user.user_id, &vault_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    let environments = queries::environments::get_all()
+        .bind(&*transaction, &user_vault.vault_id)
+        .all()
+        .await?;
+
+    Ok(Html(ui_components::environments::index::index(
+        team.id,
+        user_vault.vault_id,
+        environments,
+    )))
+}
@@ -0,0 +1,29 @@
+mod delete_environment;
+mod index;
+mod new_environment;
+mod rename_environment;
+
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+pub fn routes() -> Router {
+    Router::new()
+        .route(
+            "/app/team/:organisation_id/vault/:id/environments",
+            get(index::index),
+        )
+        .route(
+            "/app/team/:organisation_id/vault/:id/environments/new",
+            post(new_environment::new),
+        )
+        .route(
+            "/app/team/:organisation_id/vault/:id/environments/rename",
+            post(rename_environment::rename),
+        )
+        .route(
+            "/app/team/:organisation_id/vault/:id/environments/delete",
+            post(delete_environment::delete),
+        )
+}
@@ -0,0 +1,77 @@
+use crate::audit::Source;
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use axum::{
+    extract::{Extension, Form, Path},
+    response::IntoResponse,
+};
+use db::queries;
+use db::types::public::{AuditAccessType, AuditAction, VaultRole};
+use db::Pool;
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate, Default, Debug)]
+pub struct NewEnvironment {
+    #[validate(length(min = 1, message = "The name is mandatory"))]
+    pub name: String,
+}
+
+pub async fn new(
+    Path((organisation_id, vault_id)): Path<(i32, i32)>,
+    current_user: Authentication,
+    source: Source,
+    Form(new_environment): Form<NewEnvironment>,
+    Extension(pool): Extension<Pool>,
+) -> Result<impl IntoResponse, CustomError> {
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    // Only vault Admins can change the environments.
+    crate::rbac::require_vault_role(
+        &transaction,
+        current_user.user_id,
+        vault_id,
+        VaultRole::Admin,
+    )
+    .await?;
+
+    // Blow up if the user doesn't have access to the vault
+    queries::user_vaults::get()
+        .bind(&*transaction, &current_user.user_id, &vault_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    // A trigger gives us access to the new environment.
+    queries::environments::insert()
+        .bind(&*transaction, &vault_id, &new_environment.name.trim())
+        .await?;
+
+    let environment = queries::environments::get_by_name()
+        .bind(&*transaction, &new_environment.name.trim(), &vault_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    crate::audit::Event::new(
+        AuditAction::CreateEnvironment,
+        AuditAccessType::Web,
+        format!("Environment {} created", new_environment.name.trim()),
+    )
+    .by_user(current_user.user_id)
+    .vault(vault_id)
+    .environment(environment.id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
+
+    transaction.commit().await?;
+
+    crate::layout::redirect_and_snackbar(
+        &ui_components::routes::environments::index_route(vault_id, organisation_id),
+        "Environment Created",
+    )
+}
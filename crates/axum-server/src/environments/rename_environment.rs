@@ -0,0 +1,80 @@
+use crate::audit::Source;
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use axum::{
+    extract::{Extension, Form, Path},
+    response::IntoResponse,
+};
+use db::queries;
+use db::types::public::{AuditAccessType, AuditAction, VaultRole};
+use db::Pool;
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate, Default, Debug)]
+pub struct RenameEnvironment {
+    pub environment_id: i32,
+    #[validate(length(min = 1, message = "The name is mandatory"))]
+    pub name: String,
+}
+
+pub async fn rename(
+    Path((organisation_id, vault_id)): Path<(i32, i32)>,
+    current_user: Authentication,
+    source: Source,
+    Form(rename_environment): Form<RenameEnvironment>,
+    Extension(pool): Extension<Pool>,
+) -> Result<impl IntoResponse, CustomError> {
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    // Only vault Admins can change the environments.
+    crate::rbac::require_vault_role(
+        &transaction,
+        current_user.user_id,
+        vault_id,
+        VaultRole::Admin,
+    )
+    .await?;
+
+    // Blow up if the environment isn't in this vault or we can't see it
+    let environment = queries::environments::get()
+        .bind(&*transaction, &rename_environment.environment_id, &vault_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    queries::environments::rename()
+        .bind(
+            &*transaction,
+            &rename_environment.name.trim(),
+            &environment.id,
+            &vault_id,
+        )
+        .await?;
+
+    crate::audit::Event::new(
+        AuditAction::RenameEnvironment,
+        AuditAccessType::Web,
+        format!(
+            "Environment {} renamed to {}",
+            environment.name,
+            rename_environment.name.trim()
+        ),
+    )
+    .by_user(current_user.user_id)
+    .vault(vault_id)
+    .environment(environment.id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
+
+    transaction.commit().await?;
+
+    crate::layout::redirect_and_snackbar(
+        &ui_components::routes::environments::index_route(vault_id, organisation_id),
+        "Environment Renamed",
+    )
+}
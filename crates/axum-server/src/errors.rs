@@ -1,7 +1,8 @@
 use axum::{
     http::StatusCode,
-    response::{IntoResponse, Response},
+    response::{Html, IntoResponse, Response},
 };
+use db::SqlState;
 use std::fmt;
 use tonic::{Code, Status};
 
@@ -11,6 +12,18 @@ pub enum CustomError {
     Database(String),
     InvalidInput(String),
     Unauthorized(String),
+    NotFound(String),
+    PermissionDenied(String),
+    Conflict(String),
+}
+
+impl CustomError {
+    // For a lookup that found no row, use .opt() and turn None into this.
+    // With RLS a row that belongs to someone else looks exactly the same as
+    // a missing one.
+    pub fn not_found() -> CustomError {
+        CustomError::NotFound("We couldn't find what you were looking for".to_string())
+    }
 }
 
 // Allow the use of "{}" format specifier
@@ -24,33 +37,71 @@ impl fmt::Display for CustomError {
             }
             CustomError::InvalidInput(ref cause) => write!(f, "Invalid Input: {}", cause),
             CustomError::Unauthorized(ref cause) => write!(f, "Invalid Request: {}", cause),
+            CustomError::NotFound(ref cause) => write!(f, "Not Found: {}", cause),
+            CustomError::PermissionDenied(ref cause) => write!(f, "Permission Denied: {}", cause),
+            CustomError::Conflict(ref cause) => write!(f, "Conflict: {}", cause),
         }
     }
 }
 
+// Database and setup errors can contain table names, SQL and connection
+// details, so we log them and only tell the caller something went wrong.
+const INTERNAL_ERROR: &str = "Something went wrong, please try again later";
+
 // For gRPC we raise a custom error and it gets converted to a gRPC status code.
 impl From<CustomError> for Status {
     fn from(error: CustomError) -> Status {
         match error {
-            CustomError::Database(cause) => Status::new(Code::Internal, cause),
-            CustomError::FaultySetup(cause) => Status::new(Code::Internal, cause),
-            CustomError::InvalidInput(cause) => Status::new(Code::Internal, cause),
-            CustomError::Unauthorized(cause) => Status::new(Code::Internal, cause),
+            CustomError::Database(cause) => {
+                tracing::error!("Database Error: {}", cause);
+                Status::new(Code::Internal, INTERNAL_ERROR)
+            }
+            CustomError::FaultySetup(cause) => {
+                tracing::error!("Setup Error: {}", cause);
+                Status::new(Code::Internal, INTERNAL_ERROR)
+            }
+            CustomError::InvalidInput(cause) => Status::new(Code::InvalidArgument, cause),
+            CustomError::Unauthorized(cause) => Status::new(Code::Unauthenticated, cause),
+            CustomError::NotFound(cause) => Status::new(Code::NotFound, cause),
+            CustomError::PermissionDenied(cause) => Status::new(Code::PermissionDenied, cause),
+            // Someone else got there first, the caller can re-read and retry.
+            CustomError::Conflict(cause) => Status::new(Code::Aborted, cause),
         }
     }
 }
 
-// So that errors get printed to the browser?
+// So that errors get printed to the browser
 impl IntoResponse for CustomError {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
-            CustomError::Database(message) => (StatusCode::UNPROCESSABLE_ENTITY, message),
-            CustomError::FaultySetup(message) => (StatusCode::UNPROCESSABLE_ENTITY, message),
-            CustomError::InvalidInput(message) => (StatusCode::UNPROCESSABLE_ENTITY, message),
-            CustomError::Unauthorized(message) => (StatusCode::UNPROCESSABLE_ENTITY, message),
+            CustomError::Database(message) => {
+                tracing::error!("Database Error: {}", message);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    INTERNAL_ERROR.to_string(),
+                )
+            }
+            CustomError::FaultySetup(message) => {
+                tracing::error!("Setup Error: {}", message);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    INTERNAL_ERROR.to_string(),
+                )
+            }
+            CustomError::InvalidInput(message) => (StatusCode::BAD_REQUEST, message),
+            CustomError::Unauthorized(message) => (StatusCode::UNAUTHORIZED, message),
+            CustomError::NotFound(message) => (StatusCode::NOT_FOUND, message),
+            CustomError::PermissionDenied(message) => (StatusCode::FORBIDDEN, message),
+            CustomError::Conflict(message) => (StatusCode::CONFLICT, message),
         };
 
-        format!("status = {}, message = {}", status, error_message).into_response()
+        let page = ui_components::error_page::error_page(
+            status.as_u16(),
+            status.canonical_reason().unwrap_or("Error"),
+            &error_message,
+        );
+
+        (status, Html(page)).into_response()
     }
 }
 
@@ -63,6 +114,26 @@ impl From<axum::http::uri::InvalidUri> for CustomError {
 
 impl From<db::TokioPostgresError> for CustomError {
     fn from(err: db::TokioPostgresError) -> CustomError {
+        if let Some(code) = err.code() {
+            // Row level security rejects an INSERT or UPDATE with this code.
+            if code == &SqlState::INSUFFICIENT_PRIVILEGE {
+                return CustomError::PermissionDenied(
+                    "You don't have access to this resource".to_string(),
+                );
+            }
+            if code == &SqlState::UNIQUE_VIOLATION {
+                return CustomError::InvalidInput("That already exists".to_string());
+            }
+            if code == &SqlState::FOREIGN_KEY_VIOLATION
+                || code == &SqlState::NOT_NULL_VIOLATION
+                || code == &SqlState::CHECK_VIOLATION
+            {
+                return CustomError::InvalidInput(
+                    "The request refers to something that doesn't exist or is incomplete"
+                        .to_string(),
+                );
+            }
+        }
         CustomError::Database(err.to_string())
     }
 }
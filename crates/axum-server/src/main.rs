@@ -1,13 +1,16 @@
+mod api_keys;
 mod api_service;
 mod audit;
 mod authentication;
 mod config;
 mod email;
+mod environments;
 mod errors;
 mod hybrid;
 mod layout;
 mod members;
 mod profile;
+mod rbac;
 mod registration_handler;
 mod rls;
 mod secrets;
@@ -29,6 +32,18 @@ async fn main() {
     }
     tracing_subscriber::fmt::init();
 
+    // Admin commands, otherwise we run the server.
+    if std::env::args().nth(1).as_deref() == Some("verify-audit") {
+        match audit::verify::verify_all_teams().await {
+            Ok(true) => std::process::exit(0),
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                tracing::error!("could not verify the audit trail: {}", e);
+                std::process::exit(2)
+            }
+        }
+    }
+
     let config = config::Config::new();
     let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
 
@@ -37,22 +52,50 @@ async fn main() {
 
     let axum_make_service = axum::Router::new()
         .route("/static/*path", get(static_files::static_path))
+        .merge(api_keys::routes())
         .merge(audit::routes())
         .merge(vaults::routes())
         .merge(secrets::routes())
         .merge(team::routes())
         .merge(members::routes())
+        .merge(environments::routes())
         .merge(profile::routes())
         .merge(service_accounts::routes())
         .merge(registration_handler::routes())
         .layer(TraceLayer::new_for_http())
-        .layer(Extension(config))
+        .layer(Extension(config.clone()))
         .layer(Extension(pool.clone()))
         .into_make_service();
 
+    // Postgres tells us when service account secrets change so we can
+    // push them to anyone watching.
+    let (service_account_changes, _) = tokio::sync::broadcast::channel(100);
+    tokio::spawn(listen_for_service_account_changes(
+        config.app_database_url.clone(),
+        service_account_changes.clone(),
+    ));
+
+    // Apply each team's audit retention period.
+    tokio::spawn(audit::retention::expire_audit_trails(pool.clone()));
+
+    // Retire service account keys that have passed their grace period.
+    tokio::spawn(service_accounts::rotate_key::retire_previous_keys(
+        pool.clone(),
+    ));
+
+    // Send the audit trail on to a SIEM if there is one.
+    tokio::spawn(audit::forwarder::forward_audit_events(
+        pool.clone(),
+        config.audit_sink_config.clone(),
+    ));
+
     let grpc_service = tonic::transport::Server::builder()
         .add_service(grpc_api::vault::vault_server::VaultServer::new(
-            api_service::VaultService { pool },
+            api_service::VaultService {
+                pool,
+                config,
+                service_account_changes,
+            },
         ))
         .into_service();
 
@@ -66,6 +109,29 @@ async fn main() {
     }
 }
 
+async fn listen_for_service_account_changes(
+    database_url: String,
+    sender: tokio::sync::broadcast::Sender<i32>,
+) {
+    loop {
+        match db::listen(&database_url, "service_account_secrets").await {
+            Ok(mut notifications) => {
+                while let Some(payload) = notifications.next().await {
+                    if let Ok(service_account_id) = payload.parse::<i32>() {
+                        // An error just means nobody is watching right now.
+                        let _ = sender.send(service_account_id);
+                    }
+                }
+                tracing::error!("lost connection listening for service account changes");
+            }
+            Err(e) => {
+                tracing::error!("could not listen for service account changes: {}", e);
+            }
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+    }
+}
+
 pub fn render<F>(f: F) -> Html<&'static str>
 where
     F: FnOnce(&mut Vec<u8>) -> Result<(), std::io::Error>,
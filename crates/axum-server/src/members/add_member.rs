@@ -1,3 +1,4 @@
+use crate::audit::Source;
 use crate::authentication::Authentication;
 use crate::errors::CustomError;
 use axum::{
@@ -5,6 +6,7 @@ use axum::{
     response::{IntoResponse, Redirect},
 };
 use db::queries;
+use db::types::public::{AuditAccessType, AuditAction, VaultRole};
 use db::Pool;
 use serde::Deserialize;
 use validator::Validate;
@@ -17,18 +19,34 @@ pub struct AddMember {
     pub ecdh_public_key: String,
     // Comma separated list of environemnt id's
     pub environments: String,
+    // Viewer, Editor or Admin
+    pub role: String,
+    // The version of the vault key that was wrapped.
+    pub key_version: i32,
 }
 
 pub async fn add(
     Path((organisation_id, vault_id)): Path<(i32, i32)>,
     current_user: Authentication,
+    source: Source,
     Form(add_member): Form<AddMember>,
     Extension(pool): Extension<Pool>,
 ) -> Result<impl IntoResponse, CustomError> {
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    // Only vault Admins can add members.
+    crate::rbac::require_vault_role(
+        &transaction,
+        current_user.user_id,
+        vault_id,
+        VaultRole::Admin,
+    )
+    .await?;
+
+    let role = crate::rbac::parse_vault_role(&add_member.role)?;
 
     // The environments we have selected for the ser come in as a comma
     // separated list of ids.
@@ -41,27 +59,44 @@ pub async fn add(
 
     // Do an IDOR check, does this user have access to the vault. This will
     // blow up if we don't
-    queries::vaults::get()
-        .bind(&transaction, &vault_id, &current_user.user_id)
-        .one()
-        .await?;
+    let vault = queries::vaults::get()
+        .bind(&*transaction, &vault_id, &current_user.user_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    crate::vaults::ensure_key_version(&transaction, vault_id, add_member.key_version).await?;
 
     queries::user_vaults::insert()
         .bind(
-            &transaction,
+            &*transaction,
             &add_member.user_id,
             &vault_id,
             &add_member.ecdh_public_key.as_ref(),
             &add_member.wrapped_vault_key.as_ref(),
+            &role,
+            &add_member.key_version,
         )
         .await?;
 
     for env in envs {
         queries::environments::connect_environment_to_user()
-            .bind(&transaction, &add_member.user_id, &env)
+            .bind(&*transaction, &add_member.user_id, &env)
             .await?;
     }
 
+    crate::audit::Event::new(
+        AuditAction::AddMember,
+        AuditAccessType::Web,
+        format!("User {} added to vault {}", add_member.user_id, vault.name),
+    )
+    .by_user(current_user.user_id)
+    .vault(vault_id)
+    .target_user(add_member.user_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
+
     transaction.commit().await?;
 
     Ok(Redirect::to(&ui_components::routes::members::member_route(
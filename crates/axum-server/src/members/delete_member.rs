@@ -1,3 +1,4 @@
+use crate::audit::Source;
 use crate::authentication::Authentication;
 use crate::errors::CustomError;
 use axum::{
@@ -5,6 +6,7 @@ use axum::{
     response::IntoResponse,
 };
 use db::queries;
+use db::types::public::{AuditAccessType, AuditAction, VaultRole};
 use db::Pool;
 use serde::Deserialize;
 use validator::Validate;
@@ -18,26 +20,57 @@ pub struct DeleteMember {
 pub async fn delete(
     Path(params): Path<(i32, i32)>,
     current_user: Authentication,
+    source: Source,
     Form(delete_member): Form<DeleteMember>,
     Extension(pool): Extension<Pool>,
 ) -> Result<impl IntoResponse, CustomError> {
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    // Anyone can take themselves out of a vault.
+    if delete_member.user_id != current_user.user_id {
+        crate::rbac::require_vault_role(
+            &transaction,
+            current_user.user_id,
+            delete_member.vault_id,
+            VaultRole::Admin,
+        )
+        .await?;
+    }
+
     queries::user_vaults::remove_user_from_vault()
         .bind(
-            &transaction,
+            &*transaction,
             &delete_member.vault_id,
             &delete_member.user_id,
             &current_user.user_id,
         )
         .await?;
 
+    crate::rbac::ensure_vault_administrator(&transaction, delete_member.vault_id).await?;
+
     let team = queries::organisations::organisation()
-        .bind(&transaction, &params.0)
-        .one()
-        .await?;
+        .bind(&*transaction, &params.0)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    crate::audit::Event::new(
+        AuditAction::DeleteMember,
+        AuditAccessType::Web,
+        format!(
+            "User {} removed from vault with ID {}",
+            delete_member.user_id, delete_member.vault_id
+        ),
+    )
+    .by_user(current_user.user_id)
+    .vault(delete_member.vault_id)
+    .target_user(delete_member.user_id)
+    .source(&source)
+    .insert(&transaction, team.id)
+    .await?;
 
     // If we remove ourself, redirect to vaults page.
     let url = if delete_member.user_id == current_user.user_id {
@@ -5,7 +5,9 @@ use axum::{
     response::Html,
 };
 use db::queries;
+use db::types::public::VaultRole;
 use db::Pool;
+use ui_components::members::rotate_key::KeyRotation;
 
 pub async fn index(
     Path((team_id, vault_id)): Path<(i32, i32)>,
@@ -14,45 +16,86 @@ pub async fn index(
 ) -> Result<Html<String>, CustomError> {
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
 
     let team = queries::organisations::organisation()
-        .bind(&transaction, &team_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &team_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     // Blow up if the user doesn't have access to the vault
     queries::user_vaults::get()
-        .bind(&transaction, &current_user.user_id, &vault_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &current_user.user_id, &vault_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     let members = queries::user_vaults::get_users()
-        .bind(&transaction, &vault_id)
+        .bind(&*transaction, &vault_id)
         .all()
         .await?;
 
     let non_members = queries::user_vaults::get_non_members()
-        .bind(&transaction, &team_id, &vault_id)
+        .bind(&*transaction, &team_id, &vault_id)
         .all()
         .await?;
 
     let user_vault = queries::user_vaults::get()
-        .bind(&transaction, &current_user.user_id, &vault_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &current_user.user_id, &vault_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     let environments = queries::environments::get_all()
-        .bind(&transaction, &user_vault.vault_id)
+        .bind(&*transaction, &user_vault.vault_id)
         .all()
         .await?;
 
+    let can_manage_members = members
+        .iter()
+        .any(|member| member.user_id == current_user.user_id && member.role == VaultRole::Admin);
+
+    let vault = queries::vaults::get()
+        .bind(&*transaction, &vault_id, &current_user.user_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    let mut rotation = KeyRotation {
+        key_version: vault.key_version,
+        needed: vault.key_rotation_needed,
+        ..Default::default()
+    };
+
+    // Only an Admin can rotate the key, so only they need the ciphertext.
+    if can_manage_members {
+        rotation.hidden_environments = queries::environments::hidden_environments()
+            .bind(&*transaction, &vault_id)
+            .one()
+            .await?;
+        rotation.member_keys = queries::user_vaults::get_for_rotation()
+            .bind(&*transaction, &vault_id, &team_id)
+            .all()
+            .await?;
+        rotation.secrets = queries::secrets::get_for_rotation()
+            .bind(&*transaction, &vault_id)
+            .all()
+            .await?;
+        rotation.versions = queries::secrets::get_versions_for_rotation()
+            .bind(&*transaction, &vault_id)
+            .all()
+            .await?;
+    }
+
     Ok(Html(ui_components::members::index::index(
         team.id,
         user_vault,
         environments,
         members,
         non_members,
+        can_manage_members,
+        rotation,
     )))
 }
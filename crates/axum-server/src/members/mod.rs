@@ -1,6 +1,9 @@
 mod add_member;
 mod delete_member;
 mod index;
+mod rotate_key;
+mod set_environments;
+mod set_role;
 
 use axum::{
     routing::{get, post},
@@ -21,4 +24,16 @@ pub fn routes() -> Router {
             "/app/team/:organisation_id/vault/:id/members/delete",
             post(delete_member::delete),
         )
+        .route(
+            "/app/team/:organisation_id/vault/:id/members/environments",
+            post(set_environments::set_environments),
+        )
+        .route(
+            "/app/team/:organisation_id/vault/:id/members/set_role",
+            post(set_role::set_role),
+        )
+        .route(
+            "/app/team/:organisation_id/vault/:id/members/rotate_key",
+            post(rotate_key::rotate),
+        )
 }
@@ -0,0 +1,192 @@
+use crate::audit::Source;
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use axum::{
+    extract::{Extension, Form, Path},
+    response::IntoResponse,
+};
+use db::queries;
+use db::types::public::{AuditAccessType, AuditAction, VaultRole};
+use db::Pool;
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate, Default, Debug)]
+pub struct RotateKey {
+    // The version of the key the browser decrypted with.
+    pub key_version: i32,
+    // JSON lists built by the browser, see WrappedKey and Reencrypted.
+    pub members: String,
+    pub secrets: String,
+    pub versions: String,
+}
+
+// The new vault key wrapped for one member.
+#[derive(Deserialize, Debug)]
+pub struct WrappedKey {
+    pub user_id: i32,
+    pub ecdh_public_key: String,
+    pub encrypted_vault_key: String,
+}
+
+// A secret, or a version of one, encrypted with the new vault key.
+#[derive(Deserialize, Debug)]
+pub struct Reencrypted {
+    pub id: i32,
+    pub name: String,
+    pub secret: String,
+}
+
+fn parse<T: serde::de::DeserializeOwned>(json: &str) -> Result<Vec<T>, CustomError> {
+    serde_json::from_str(json)
+        .map_err(|_| CustomError::InvalidInput("Rotated key not valid".to_string()))
+}
+
+pub async fn rotate(
+    Path((organisation_id, vault_id)): Path<(i32, i32)>,
+    current_user: Authentication,
+    source: Source,
+    Extension(pool): Extension<Pool>,
+    Form(rotate_key): Form<RotateKey>,
+) -> Result<impl IntoResponse, CustomError> {
+    let members: Vec<WrappedKey> = parse(&rotate_key.members)?;
+    let secrets: Vec<Reencrypted> = parse(&rotate_key.secrets)?;
+    let versions: Vec<Reencrypted> = parse(&rotate_key.versions)?;
+
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    crate::rbac::require_vault_role(
+        &transaction,
+        current_user.user_id,
+        vault_id,
+        VaultRole::Admin,
+    )
+    .await?;
+
+    // Secrets in an environment they can't see would stay on the old key.
+    let hidden_environments = queries::environments::hidden_environments()
+        .bind(&*transaction, &vault_id)
+        .one()
+        .await?;
+
+    if hidden_environments != 0 {
+        return Err(CustomError::PermissionDenied(
+            "You need access to every environment in the vault to rotate its key".to_string(),
+        ));
+    }
+
+    let vault = queries::vaults::get()
+        .bind(&*transaction, &vault_id, &current_user.user_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    let key_version = queries::vaults::start_key_rotation()
+        .bind(&*transaction, &vault_id, &rotate_key.key_version)
+        .opt()
+        .await?
+        .ok_or_else(|| {
+            CustomError::Conflict(
+                "The key has been rotated by someone else, reload the page and try again"
+                    .to_string(),
+            )
+        })?;
+
+    for member in &members {
+        let updated = queries::user_vaults::rewrap()
+            .bind(
+                &*transaction,
+                &member.encrypted_vault_key.as_ref(),
+                &member.ecdh_public_key.as_ref(),
+                &key_version,
+                &member.user_id,
+                &vault_id,
+            )
+            .await?;
+        if updated != 1 {
+            return Err(CustomError::InvalidInput(format!(
+                "User {} is not a member of this vault",
+                member.user_id
+            )));
+        }
+    }
+
+    for secret in &secrets {
+        let updated = queries::secrets::reencrypt()
+            .bind(
+                &*transaction,
+                &secret.name.as_ref(),
+                &secret.secret.as_ref(),
+                &key_version,
+                &secret.id,
+                &vault_id,
+            )
+            .await?;
+        if updated != 1 {
+            return Err(CustomError::InvalidInput(format!(
+                "Secret {} is not in this vault",
+                secret.id
+            )));
+        }
+    }
+
+    for version in &versions {
+        let updated = queries::secrets::reencrypt_version()
+            .bind(
+                &*transaction,
+                &version.name.as_ref(),
+                &version.secret.as_ref(),
+                &key_version,
+                &version.id,
+                &vault_id,
+            )
+            .await?;
+        if updated != 1 {
+            return Err(CustomError::InvalidInput(format!(
+                "Secret version {} is not in this vault",
+                version.id
+            )));
+        }
+    }
+
+    // Anything still on the old key would be unreadable, so nothing changes
+    // unless the browser sent all of it.
+    let stale = queries::vaults::finish_key_rotation()
+        .bind(&*transaction, &vault_id, &key_version)
+        .one()
+        .await?;
+
+    if stale != 0 {
+        return Err(CustomError::Conflict(
+            "The vault has changed since the page loaded, reload the page and try again"
+                .to_string(),
+        ));
+    }
+
+    crate::audit::Event::new(
+        AuditAction::RotateVaultKey,
+        AuditAccessType::Web,
+        format!(
+            "Key of vault {} rotated to version {}, {} secrets and {} members",
+            vault.name,
+            key_version,
+            secrets.len(),
+            members.len()
+        ),
+    )
+    .by_user(current_user.user_id)
+    .vault(vault_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
+
+    transaction.commit().await?;
+
+    crate::layout::redirect_and_snackbar(
+        &ui_components::routes::members::member_route(vault_id, organisation_id),
+        "Vault Key Rotated",
+    )
+}
@@ -0,0 +1,125 @@
+use crate::audit::Source;
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use axum::{
+    extract::{Extension, Form, Path},
+    response::IntoResponse,
+};
+use db::queries;
+use db::types::public::{AuditAccessType, AuditAction, VaultRole};
+use db::Pool;
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate, Default, Debug)]
+pub struct SetEnvironments {
+    pub user_id: i32,
+    // Comma separated list of environemnt id's
+    pub environments: String,
+}
+
+pub async fn set_environments(
+    Path((organisation_id, vault_id)): Path<(i32, i32)>,
+    current_user: Authentication,
+    source: Source,
+    Form(set_environments): Form<SetEnvironments>,
+    Extension(pool): Extension<Pool>,
+) -> Result<impl IntoResponse, CustomError> {
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    // Only vault Admins can change who sees which environment.
+    crate::rbac::require_vault_role(
+        &transaction,
+        current_user.user_id,
+        vault_id,
+        VaultRole::Admin,
+    )
+    .await?;
+
+    let envs: Vec<i32> = set_environments
+        .environments
+        .split(',')
+        .map(|e| e.parse::<i32>().unwrap_or(-1))
+        .filter(|e| *e != -1)
+        .collect();
+
+    // Do an IDOR check, does this user have access to the vault. This will
+    // blow up if we don't
+    let vault = queries::vaults::get()
+        .bind(&*transaction, &vault_id, &current_user.user_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    // Blow up if the user isn't a member of the vault
+    queries::user_vaults::get()
+        .bind(&*transaction, &set_environments.user_id, &vault_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    let member = queries::users::user()
+        .bind(&*transaction, &set_environments.user_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    // We can only hand out or take away the environments we can see ourselves.
+    let environments = queries::environments::get_all()
+        .bind(&*transaction, &vault_id)
+        .all()
+        .await?;
+
+    let granted = queries::environments::get_for_user()
+        .bind(&*transaction, &vault_id, &set_environments.user_id)
+        .all()
+        .await?;
+
+    for env in environments {
+        let has_access = granted.iter().any(|g| g.id == env.id);
+        let wants_access = envs.contains(&env.id);
+
+        let description = if wants_access && !has_access {
+            queries::environments::connect_environment_to_user()
+                .bind(&*transaction, &member.id, &env.id)
+                .await?;
+            format!(
+                "Gave {} access to {} in the {} vault",
+                member.email, env.name, vault.name
+            )
+        } else if !wants_access && has_access {
+            queries::environments::disconnect_environment_from_user()
+                .bind(&*transaction, &member.id, &env.id)
+                .await?;
+            format!(
+                "Removed access to {} in the {} vault from {}",
+                env.name, vault.name, member.email
+            )
+        } else {
+            continue;
+        };
+
+        crate::audit::Event::new(
+            AuditAction::ChangeEnvironmentAccess,
+            AuditAccessType::Web,
+            description,
+        )
+        .by_user(current_user.user_id)
+        .vault(vault_id)
+        .environment(env.id)
+        .target_user(member.id)
+        .source(&source)
+        .insert(&transaction, organisation_id)
+        .await?;
+    }
+
+    transaction.commit().await?;
+
+    crate::layout::redirect_and_snackbar(
+        &ui_components::routes::members::member_route(vault_id, organisation_id),
+        "Environments Updated",
+    )
+}
@@ -0,0 +1,77 @@
+use crate::audit::Source;
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use axum::{
+    extract::{Extension, Form, Path},
+    response::IntoResponse,
+};
+use db::queries;
+use db::types::public::{AuditAccessType, AuditAction, VaultRole};
+use db::Pool;
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate, Default, Debug)]
+pub struct SetRole {
+    pub user_id: i32,
+    // Viewer, Editor or Admin
+    pub role: String,
+}
+
+pub async fn set_role(
+    Path((organisation_id, vault_id)): Path<(i32, i32)>,
+    current_user: Authentication,
+    source: Source,
+    Form(set_role): Form<SetRole>,
+    Extension(pool): Extension<Pool>,
+) -> Result<impl IntoResponse, CustomError> {
+    let role = crate::rbac::parse_vault_role(&set_role.role)?;
+
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    crate::rbac::require_vault_role(
+        &transaction,
+        current_user.user_id,
+        vault_id,
+        VaultRole::Admin,
+    )
+    .await?;
+
+    let vault = queries::vaults::get()
+        .bind(&*transaction, &vault_id, &current_user.user_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    queries::user_vaults::set_role()
+        .bind(&*transaction, &role, &set_role.user_id, &vault_id)
+        .await?;
+
+    // Nothing is changed if that was the last Admin.
+    crate::rbac::ensure_vault_administrator(&transaction, vault_id).await?;
+
+    crate::audit::Event::new(
+        AuditAction::ChangeRole,
+        AuditAccessType::Web,
+        format!(
+            "Role of {} in vault {} changed to {:?}",
+            set_role.user_id, vault.name, role
+        ),
+    )
+    .by_user(current_user.user_id)
+    .vault(vault_id)
+    .target_user(set_role.user_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
+
+    transaction.commit().await?;
+
+    crate::layout::redirect_and_snackbar(
+        &ui_components::routes::members::member_route(vault_id, organisation_id),
+        "Role Changed",
+    )
+}
@@ -14,18 +14,20 @@ pub async fn index(
 ) -> Result<Html<String>, CustomError> {
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
 
     let team = queries::organisations::organisation()
-        .bind(&transaction, &organisation_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     let user = queries::users::user()
-        .bind(&transaction, &current_user.user_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &current_user.user_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     Ok(Html(ui_components::profile::profile(user, team.id)))
 }
@@ -11,14 +11,14 @@ pub async fn index(
     Path(team_id): Path<i32>,
 ) -> Result<Html<String>, CustomError> {
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
 
     let user = queries::users::user()
-        .bind(&transaction, &current_user.user_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &current_user.user_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     Ok(Html(ui_components::profile_popup::profile_popup(
         user, team_id,
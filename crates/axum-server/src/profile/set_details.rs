@@ -1,3 +1,4 @@
+use crate::audit::Source;
 use crate::authentication::Authentication;
 use crate::errors::CustomError;
 use axum::{
@@ -5,6 +6,7 @@ use axum::{
     response::IntoResponse,
 };
 use db::queries;
+use db::types::public::{AuditAccessType, AuditAction};
 use db::Pool;
 use serde::Deserialize;
 use validator::Validate;
@@ -20,23 +22,38 @@ pub struct SetDetails {
 pub async fn set_details(
     Path(organisation_id): Path<i32>,
     current_user: Authentication,
+    source: Source,
     Extension(pool): Extension<Pool>,
     Form(set_name): Form<SetDetails>,
 ) -> Result<impl IntoResponse, CustomError> {
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
 
     queries::users::set_name()
         .bind(
-            &transaction,
+            &*transaction,
             &set_name.first_name.as_ref(),
             &set_name.last_name.as_ref(),
             &current_user.user_id,
         )
         .await?;
 
+    crate::audit::Event::new(
+        AuditAction::UpdateProfile,
+        AuditAccessType::Web,
+        format!(
+            "Name changed to {} {}",
+            set_name.first_name, set_name.last_name
+        ),
+    )
+    .by_user(current_user.user_id)
+    .target_user(current_user.user_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
+
     transaction.commit().await?;
 
     crate::layout::redirect_and_snackbar(
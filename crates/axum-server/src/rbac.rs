@@ -0,0 +1,331 @@
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use axum::{
+    async_trait,
+    extract::{Extension, FromRequest, Path, RequestParts},
+    response::{IntoResponse, Response},
+};
+use db::queries;
+use db::types::public::{Permission, Role, VaultRole};
+use db::{Pool, Transaction};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+// A permission a route can ask for with Authorize.
+pub trait Required {
+    const PERMISSION: Permission;
+}
+
+pub struct ManageTeam;
+impl Required for ManageTeam {
+    const PERMISSION: Permission = Permission::ManageTeam;
+}
+
+pub struct InviteMembers;
+impl Required for InviteMembers {
+    const PERMISSION: Permission = Permission::InviteMembers;
+}
+
+pub struct CreateVault;
+impl Required for CreateVault {
+    const PERMISSION: Permission = Permission::CreateVault;
+}
+
+pub struct DeleteVault;
+impl Required for DeleteVault {
+    const PERMISSION: Permission = Permission::DeleteVault;
+}
+
+pub struct ManageServiceAccounts;
+impl Required for ManageServiceAccounts {
+    const PERMISSION: Permission = Permission::ManageServiceAccounts;
+}
+
+pub struct ViewAudit;
+impl Required for ViewAudit {
+    const PERMISSION: Permission = Permission::ViewAudit;
+}
+
+// Add `_: Authorize<ManageTeam>` to a handler and the request is rejected
+// unless the user has that permission in the team from the path.
+pub struct Authorize<P: Required>(PhantomData<P>);
+
+#[async_trait]
+impl<B, P> FromRequest<B> for Authorize<P>
+where
+    B: Send,
+    P: Required + Send,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let current_user = Authentication::from_request(req).await?;
+
+        let Path(params) = Path::<HashMap<String, String>>::from_request(req)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        let organisation_id = params
+            .get("organisation_id")
+            .and_then(|id| id.parse::<i32>().ok())
+            .ok_or_else(|| {
+                CustomError::InvalidInput("The route has no team".to_string()).into_response()
+            })?;
+
+        let Extension(pool) = Extension::<Pool>::from_request(req)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        // Only reads, so it's fine for the transaction to roll back when dropped.
+        let mut client = pool
+            .get()
+            .await
+            .map_err(|e| CustomError::from(e).into_response())?;
+        let transaction = super::rls::RlsTransaction::for_user(&mut client, &current_user)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        require(
+            &transaction,
+            current_user.user_id,
+            organisation_id,
+            P::PERMISSION,
+        )
+        .await
+        .map_err(IntoResponse::into_response)?;
+
+        Ok(Authorize(PhantomData))
+    }
+}
+
+// For the API where there are no extractors. Checks in the caller's own
+// transaction so it sees the same data as the rest of the request.
+pub async fn require(
+    transaction: &Transaction<'_>,
+    user_id: i32,
+    organisation_id: i32,
+    permission: Permission,
+) -> Result<(), CustomError> {
+    let permissions = queries::rbac::permissions()
+        .bind(transaction, &user_id, &organisation_id)
+        .all()
+        .await?;
+
+    if permissions.contains(&permission) {
+        Ok(())
+    } else {
+        Err(CustomError::PermissionDenied(format!(
+            "You need the {:?} permission to do that",
+            permission
+        )))
+    }
+}
+
+// The role picked when inviting someone. Either one of the built in roles
+// or the id of one of the team's own roles.
+pub fn parse_role(role: &str) -> Result<(Vec<Role>, Option<i32>), CustomError> {
+    match role {
+        "Administrator" => Ok((vec![Role::Administrator, Role::Collaborator], None)),
+        "Collaborator" | "" => Ok((vec![Role::Collaborator], None)),
+        team_role => match team_role.parse::<i32>() {
+            Ok(team_role_id) => Ok((vec![], Some(team_role_id))),
+            Err(_) => Err(CustomError::InvalidInput(format!(
+                "{} isn't a role",
+                team_role
+            ))),
+        },
+    }
+}
+
+// Inviting someone can't hand out more than the inviter has. Only those who
+// manage the team can make Administrators, and a team role can't carry a
+// permission the inviter doesn't have themselves.
+pub async fn ensure_can_grant(
+    transaction: &Transaction<'_>,
+    user_id: i32,
+    organisation_id: i32,
+    roles: &[Role],
+    team_role_id: Option<i32>,
+) -> Result<(), CustomError> {
+    let permissions = queries::rbac::permissions()
+        .bind(transaction, &user_id, &organisation_id)
+        .all()
+        .await?;
+
+    if roles.contains(&Role::Administrator) && !permissions.contains(&Permission::ManageTeam) {
+        return Err(CustomError::PermissionDenied(
+            "You need the ManageTeam permission to invite an Administrator".to_string(),
+        ));
+    }
+
+    if let Some(team_role_id) = team_role_id {
+        let team_role = queries::rbac::team_role()
+            .bind(transaction, &team_role_id, &organisation_id)
+            .opt()
+            .await?
+            .ok_or_else(CustomError::not_found)?;
+
+        if let Some(missing) = team_role
+            .permissions
+            .iter()
+            .find(|permission| !permissions.contains(permission))
+        {
+            return Err(CustomError::PermissionDenied(format!(
+                "You can't give the {} role as you don't have the {:?} permission",
+                team_role.name, missing
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+// A team always needs an Administrator to manage it. Call this after
+// changing who's in a team or what they can do, before committing.
+pub async fn ensure_administrator(
+    transaction: &Transaction<'_>,
+    organisation_id: i32,
+) -> Result<(), CustomError> {
+    let administrators = queries::organisations::count_administrators()
+        .bind(transaction, &organisation_id)
+        .one()
+        .await?;
+
+    if administrators == 0 {
+        return Err(CustomError::InvalidInput(
+            "The team needs at least one Administrator".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+// Whoever owns a team can only be removed from it, or lose their role, once
+// they've handed it over to someone else.
+pub async fn ensure_not_owner(
+    transaction: &Transaction<'_>,
+    user_id: i32,
+    organisation_id: i32,
+) -> Result<(), CustomError> {
+    let team = queries::organisations::organisation()
+        .bind(transaction, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    if team.created_by_user_id == user_id {
+        return Err(CustomError::InvalidInput(
+            "The owner of a team has to transfer it to someone else first".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+// API requests name a team as well as the vault they're about. The vault
+// decides the team, the request only has to agree with it, otherwise a
+// permission in one team could be used on another team's vault. Returns the
+// team to check permissions against and audit in.
+pub async fn vault_team(
+    transaction: &Transaction<'_>,
+    user_id: i32,
+    vault_id: i32,
+    organisation_id: i32,
+) -> Result<i32, CustomError> {
+    let vault = queries::vaults::get()
+        .bind(transaction, &vault_id, &user_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    if vault.organisation_id != organisation_id {
+        return Err(CustomError::NotFound(
+            "Vault is not in this team".to_string(),
+        ));
+    }
+
+    Ok(vault.organisation_id)
+}
+
+// The same as vault_team for service accounts.
+pub async fn service_account_team(
+    transaction: &Transaction<'_>,
+    service_account_id: i32,
+    organisation_id: i32,
+) -> Result<i32, CustomError> {
+    let service_account = queries::service_accounts::get_dangerous()
+        .bind(transaction, &service_account_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    if service_account.organisation_id != organisation_id {
+        return Err(CustomError::NotFound(
+            "Service account is not in this team".to_string(),
+        ));
+    }
+
+    Ok(service_account.organisation_id)
+}
+
+// The role picked when adding someone to a vault.
+pub fn parse_vault_role(role: &str) -> Result<VaultRole, CustomError> {
+    match role {
+        "Admin" => Ok(VaultRole::Admin),
+        "Editor" => Ok(VaultRole::Editor),
+        "Viewer" | "" => Ok(VaultRole::Viewer),
+        vault_role => Err(CustomError::InvalidInput(format!(
+            "{} isn't a vault role",
+            vault_role
+        ))),
+    }
+}
+
+// Each vault role can do everything the ones before it can.
+fn vault_role_rank(role: VaultRole) -> u8 {
+    match role {
+        VaultRole::Viewer => 0,
+        VaultRole::Editor => 1,
+        VaultRole::Admin => 2,
+    }
+}
+
+// Like require but for what a member can do inside a vault. Errors if the
+// user isn't a member at all.
+pub async fn require_vault_role(
+    transaction: &Transaction<'_>,
+    user_id: i32,
+    vault_id: i32,
+    needed: VaultRole,
+) -> Result<(), CustomError> {
+    let role = queries::user_vaults::role()
+        .bind(transaction, &user_id, &vault_id)
+        .opt()
+        .await?;
+
+    match role {
+        Some(role) if vault_role_rank(role) >= vault_role_rank(needed) => Ok(()),
+        _ => Err(CustomError::PermissionDenied(format!(
+            "You need to be a vault {:?} to do that",
+            needed
+        ))),
+    }
+}
+
+// The vault equivalent of ensure_administrator.
+pub async fn ensure_vault_administrator(
+    transaction: &Transaction<'_>,
+    vault_id: i32,
+) -> Result<(), CustomError> {
+    let orphaned = queries::user_vaults::without_administrator()
+        .bind(transaction, &vault_id)
+        .one()
+        .await?;
+
+    if orphaned {
+        return Err(CustomError::InvalidInput(
+            "The vault needs at least one Admin".to_string(),
+        ));
+    }
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use crate::audit::Source;
 use crate::{authentication::Authentication, errors::CustomError};
 use axum::{
     extract::Extension,
@@ -7,6 +8,7 @@ use axum::{
 };
 use db::queries;
 use db::types;
+use db::types::public::{AuditAccessType, AuditAction};
 use db::Pool;
 
 pub static INDEX: &str = "/app/post_registration";
@@ -19,15 +21,15 @@ pub fn routes() -> Router {
 // the organisation table. If not, then create one.
 pub async fn post_registration(
     current_user: Authentication,
+    source: Source,
     Extension(pool): Extension<Pool>,
 ) -> Result<impl IntoResponse, CustomError> {
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction = super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
 
     let org = queries::organisations::get_primary_organisation()
-        .bind(&transaction, &current_user.user_id)
+        .bind(&*transaction, &current_user.user_id)
         .one()
         .await;
 
@@ -37,7 +39,7 @@ pub async fn post_registration(
         )))
     } else {
         let inserted_org_id = queries::organisations::insert_organisation()
-            .bind(&transaction)
+            .bind(&*transaction)
             .one()
             .await?;
 
@@ -48,13 +50,24 @@ pub async fn post_registration(
 
         queries::organisations::add_user_to_organisation()
             .bind(
-                &transaction,
+                &*transaction,
                 &current_user.user_id,
                 &inserted_org_id,
                 &roles.as_ref(),
+                &None,
             )
             .await?;
 
+        crate::audit::Event::new(
+            AuditAction::CreateTeam,
+            AuditAccessType::Web,
+            "Team created on registration".to_string(),
+        )
+        .by_user(current_user.user_id)
+        .source(&source)
+        .insert(&transaction, inserted_org_id)
+        .await?;
+
         transaction.commit().await?;
 
         Ok(Redirect::to(&ui_components::routes::vaults::index_route(
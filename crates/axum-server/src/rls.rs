@@ -1,37 +1,69 @@
 use crate::authentication::Authentication;
 use crate::errors::CustomError;
-use db::Transaction;
+use db::{Object, Transaction};
+use std::ops::Deref;
 
-// A helper function for setting the RLS user which is used by all the policies.
-pub async fn set_row_level_security_user(
-    transaction: &Transaction<'_>,
-    current_user: &Authentication,
-) -> Result<(), CustomError> {
-    transaction
-        .query(
-            &format!(
-                "SET LOCAL row_level_security.user_id = {}",
-                current_user.user_id
-            ),
-            &[],
+// A transaction with the row level security identity already set, so a
+// handler can't touch the database without saying who it's acting for.
+// Use &*transaction where a query wants the underlying transaction.
+pub struct RlsTransaction<'a> {
+    transaction: Transaction<'a>,
+}
+
+impl<'a> RlsTransaction<'a> {
+    // Sets the RLS user which is used by all the policies.
+    pub async fn for_user(
+        client: &'a mut Object,
+        current_user: &Authentication,
+    ) -> Result<RlsTransaction<'a>, CustomError> {
+        let transaction = client.transaction().await?;
+        set_config(
+            &transaction,
+            "row_level_security.user_id",
+            &current_user.user_id.to_string(),
         )
         .await?;
+        Ok(RlsTransaction { transaction })
+    }
 
-    Ok(())
+    // Sets the RLS ECDH public key which is used to access service account secrets.
+    pub async fn for_service_account(
+        client: &'a mut Object,
+        ecdh_public_key: &str,
+    ) -> Result<RlsTransaction<'a>, CustomError> {
+        let transaction = client.transaction().await?;
+        set_config(
+            &transaction,
+            "row_level_security.ecdh_public_key",
+            ecdh_public_key,
+        )
+        .await?;
+        Ok(RlsTransaction { transaction })
+    }
+
+    pub async fn commit(self) -> Result<(), CustomError> {
+        self.transaction.commit().await?;
+        Ok(())
+    }
 }
-// A helper function for setting the RLS ECDH public key which is used to access service account secrets.
-pub async fn set_row_level_security_ecdh_public_key(
+
+impl<'a> Deref for RlsTransaction<'a> {
+    type Target = Transaction<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.transaction
+    }
+}
+
+// SET LOCAL can't take parameters, set_config can. The last argument
+// makes it local to the transaction.
+async fn set_config(
     transaction: &Transaction<'_>,
-    ecdh_public_key: &str,
+    name: &str,
+    value: &str,
 ) -> Result<(), CustomError> {
     transaction
-        .query(
-            &format!(
-                "SET LOCAL row_level_security.ecdh_public_key = '{}'",
-                ecdh_public_key
-            ),
-            &[],
-        )
+        .query("SELECT set_config($1, $2, true)", &[&name, &value])
         .await?;
 
     Ok(())
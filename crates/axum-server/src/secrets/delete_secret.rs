@@ -1,3 +1,4 @@
+use crate::audit::Source;
 use crate::authentication::Authentication;
 use crate::errors::CustomError;
 use axum::{
@@ -5,7 +6,7 @@ use axum::{
     response::IntoResponse,
 };
 use db::queries;
-use db::types::public::{AuditAccessType, AuditAction};
+use db::types::public::{AuditAccessType, AuditAction, VaultRole};
 use db::Pool;
 use serde::Deserialize;
 use validator::Validate;
@@ -18,42 +19,56 @@ pub struct DeleteSecret {
 pub async fn delete(
     Path((organisation_id, vault_id)): Path<(i32, i32)>,
     current_user: Authentication,
+    source: Source,
     Form(delete_secret): Form<DeleteSecret>,
     Extension(pool): Extension<Pool>,
 ) -> Result<impl IntoResponse, CustomError> {
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    // Viewers can only read secrets.
+    crate::rbac::require_vault_role(
+        &transaction,
+        current_user.user_id,
+        vault_id,
+        VaultRole::Editor,
+    )
+    .await?;
 
     let team = queries::organisations::organisation()
-        .bind(&transaction, &organisation_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     let secret = queries::secrets::get()
-        .bind(&transaction, &delete_secret.secret_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &delete_secret.secret_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     queries::secrets::delete_secret()
-        .bind(&transaction, &delete_secret.secret_id)
+        .bind(&*transaction, &delete_secret.secret_id)
         .await?;
 
-    queries::audit::insert()
-        .bind(
-            &transaction,
-            &current_user.user_id,
-            &organisation_id,
-            &AuditAction::DeleteSecret,
-            &AuditAccessType::Web,
-            &format!("Secret deleted from Vault with ID {}", vault_id).as_ref(),
-        )
-        .await?;
+    crate::audit::Event::new(
+        AuditAction::DeleteSecret,
+        AuditAccessType::Web,
+        format!("Secret deleted from Vault with ID {}", vault_id),
+    )
+    .by_user(current_user.user_id)
+    .vault(vault_id)
+    .secret(delete_secret.secret_id)
+    .environment(secret.environment_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
 
     queries::secrets::delete_service_account()
         .bind(
-            &transaction,
+            &*transaction,
             &secret.name_blind_index.as_ref(),
             &secret.vault_id,
         )
@@ -1,3 +1,4 @@
+use crate::audit::Source;
 use crate::authentication::Authentication;
 use crate::errors::CustomError;
 use axum::{
@@ -12,29 +13,37 @@ pub async fn index(
     Path((team_id, vault_id)): Path<(i32, i32)>,
     Extension(pool): Extension<Pool>,
     current_user: Authentication,
+    source: Source,
 ) -> Result<Html<String>, CustomError> {
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
 
     let team = queries::organisations::organisation()
-        .bind(&transaction, &team_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &team_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     let secrets = queries::secrets::get_all()
-        .bind(&transaction, &vault_id)
+        .bind(&*transaction, &vault_id)
         .all()
         .await?;
 
     let user_vault = queries::user_vaults::get()
-        .bind(&transaction, &current_user.user_id, &vault_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &current_user.user_id, &vault_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     let environments = queries::environments::get_all()
-        .bind(&transaction, &vault_id)
+        .bind(&*transaction, &vault_id)
+        .all()
+        .await?;
+
+    let versions = queries::secrets::get_versions()
+        .bind(&*transaction, &vault_id)
         .all()
         .await?;
 
@@ -44,24 +53,26 @@ pub async fn index(
             user_vault,
             environments,
             secrets,
+            versions,
         )))
     } else {
-        queries::audit::insert()
-            .bind(
-                &transaction,
-                &current_user.user_id,
-                &team.id,
-                &AuditAction::AccessSecrets,
-                &AuditAccessType::Web,
-                &format!("Secrets  accesed from vault {}", &user_vault.vault_id).as_ref(),
-            )
-            .await?;
+        crate::audit::Event::new(
+            AuditAction::AccessSecrets,
+            AuditAccessType::Web,
+            format!("Secrets  accesed from vault {}", &user_vault.vault_id),
+        )
+        .by_user(current_user.user_id)
+        .vault(user_vault.vault_id)
+        .source(&source)
+        .insert(&transaction, team.id)
+        .await?;
 
         Ok(Html(ui_components::secrets::index::index(
             team.id,
             user_vault,
             environments,
             secrets,
+            versions,
         )))
     }
 }
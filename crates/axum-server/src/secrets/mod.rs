@@ -1,11 +1,17 @@
 mod delete_secret;
 mod index;
 mod new_secret;
+mod rollback_secret;
+mod update_secret;
 
+use crate::errors::CustomError;
 use axum::{
     routing::{get, post},
     Router,
 };
+use db::queries;
+use db::Transaction;
+use serde::Deserialize;
 
 pub fn routes() -> Router {
     Router::new()
@@ -21,4 +27,101 @@ pub fn routes() -> Router {
             "/app/team/:organisation_id/vault/:id/secrets/delete",
             post(delete_secret::delete),
         )
+        .route(
+            "/app/team/:organisation_id/vault/:id/secrets/rollback",
+            post(rollback_secret::rollback),
+        )
+        .route(
+            "/app/team/:organisation_id/vault/:id/secrets/update",
+            post(update_secret::update),
+        )
+}
+
+// A secret re-encrypted by the browser for one service account.
+#[derive(Deserialize, Debug)]
+pub struct ServiceAccountSecretCopy {
+    pub service_account_id: i32,
+    // The service account key the copy is for, None for its current key.
+    pub service_account_ecdh_public_key: Option<String>,
+    pub public_ecdh_key: String,
+    pub encrypted_name: String,
+    pub encrypted_secret_value: String,
+}
+
+// When a secret changes, the copies held by service accounts have to change
+// with it. The server can't re-encrypt them so the browser sends a copy for
+// every service account connected to the secret's environment, and we swap
+// them in the same transaction as the secret itself. A service account whose
+// key is being rotated needs a copy for each key.
+pub async fn replace_service_account_secrets(
+    transaction: &Transaction<'_>,
+    vault_id: i32,
+    environment_id: i32,
+    name_blind_index: &str,
+    copies: &[ServiceAccountSecretCopy],
+) -> Result<(), CustomError> {
+    let service_accounts = queries::service_accounts::get_by_vault()
+        .bind(transaction, &vault_id)
+        .all()
+        .await?;
+
+    let mut expected: Vec<(i32, &str)> = Vec::new();
+    for sa in service_accounts
+        .iter()
+        .filter(|sa| sa.environment_id == Some(environment_id))
+    {
+        expected.push((sa.id, &sa.ecdh_public_key));
+        if let Some(previous_ecdh_public_key) = &sa.previous_ecdh_public_key {
+            expected.push((sa.id, previous_ecdh_public_key));
+        }
+    }
+    expected.sort_unstable();
+
+    let current_key = |service_account_id: i32| {
+        service_accounts
+            .iter()
+            .find(|sa| sa.id == service_account_id)
+            .map(|sa| sa.ecdh_public_key.as_str())
+            .unwrap_or_default()
+    };
+
+    let mut received: Vec<(i32, &str)> = copies
+        .iter()
+        .map(|c| {
+            (
+                c.service_account_id,
+                c.service_account_ecdh_public_key
+                    .as_deref()
+                    .unwrap_or_else(|| current_key(c.service_account_id)),
+            )
+        })
+        .collect();
+    received.sort_unstable();
+
+    if expected != received {
+        return Err(CustomError::InvalidInput(
+            "Every service account connected to the environment needs a copy of the secret"
+                .to_string(),
+        ));
+    }
+
+    queries::secrets::delete_service_account()
+        .bind(transaction, &name_blind_index, &vault_id)
+        .await?;
+
+    for copy in copies {
+        queries::service_account_secrets::insert()
+            .bind(
+                transaction,
+                &copy.service_account_id,
+                &copy.encrypted_name.as_ref(),
+                &name_blind_index,
+                &copy.encrypted_secret_value.as_ref(),
+                &copy.public_ecdh_key.as_ref(),
+                &copy.service_account_ecdh_public_key.as_deref(),
+            )
+            .await?;
+    }
+
+    Ok(())
 }
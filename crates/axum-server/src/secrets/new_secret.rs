@@ -1,3 +1,4 @@
+use crate::audit::Source;
 use crate::authentication::Authentication;
 use crate::errors::CustomError;
 use axum::{
@@ -5,7 +6,7 @@ use axum::{
     response::{IntoResponse, Redirect},
 };
 use db::queries;
-use db::types::public::{AuditAccessType, AuditAction};
+use db::types::public::{AuditAccessType, AuditAction, VaultRole};
 use db::Pool;
 use serde::Deserialize;
 use validator::Validate;
@@ -19,51 +20,64 @@ pub struct NewSecret {
     pub name_blind_index: String,
     #[validate(length(min = 1, message = "The secret is mandatory"))]
     pub secret: String,
+    // The version of the vault key the browser encrypted with.
+    pub key_version: i32,
 }
 
 pub async fn new(
     Path((organisation_id, id)): Path<(i32, i32)>,
     current_user: Authentication,
+    source: Source,
     Form(new_secret): Form<NewSecret>,
     Extension(pool): Extension<Pool>,
 ) -> Result<impl IntoResponse, CustomError> {
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    // Viewers can only read secrets.
+    crate::rbac::require_vault_role(&transaction, current_user.user_id, id, VaultRole::Editor)
+        .await?;
 
     // This will blow up if the user doesn't have access to the vault
     queries::vaults::get()
-        .bind(&transaction, &id, &current_user.user_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &id, &current_user.user_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    crate::vaults::ensure_key_version(&transaction, id, new_secret.key_version).await?;
 
     queries::secrets::insert()
         .bind(
-            &transaction,
+            &*transaction,
             &id,
             &new_secret.name.as_ref(),
             &new_secret.name_blind_index.as_ref(),
             &new_secret.secret.as_ref(),
             &new_secret.environment_id,
+            &new_secret.key_version,
         )
         .await?;
 
-    queries::audit::insert()
-        .bind(
-            &transaction,
-            &current_user.user_id,
-            &organisation_id,
-            &AuditAction::AddSecret,
-            &AuditAccessType::Web,
-            &format!("Secret created for Vault with ID {}", id).as_ref(),
-        )
-        .await?;
+    crate::audit::Event::new(
+        AuditAction::AddSecret,
+        AuditAccessType::Web,
+        format!("Secret created for Vault with ID {}", id),
+    )
+    .by_user(current_user.user_id)
+    .vault(id)
+    .environment(new_secret.environment_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
 
     let team = queries::organisations::organisation()
-        .bind(&transaction, &organisation_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     transaction.commit().await?;
 
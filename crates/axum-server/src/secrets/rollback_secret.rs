@@ -0,0 +1,112 @@
+use crate::audit::Source;
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use axum::{
+    extract::{Extension, Form, Path},
+    response::IntoResponse,
+};
+use db::queries;
+use db::types::public::{AuditAccessType, AuditAction, VaultRole};
+use db::Pool;
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate, Default, Debug)]
+pub struct RollbackSecret {
+    pub secret_id: i32,
+    pub version_id: i32,
+    // JSON list of ServiceAccountSecretCopy, built by the browser.
+    pub service_account_secrets: String,
+}
+
+pub async fn rollback(
+    Path((organisation_id, vault_id)): Path<(i32, i32)>,
+    current_user: Authentication,
+    source: Source,
+    Form(rollback_secret): Form<RollbackSecret>,
+    Extension(pool): Extension<Pool>,
+) -> Result<impl IntoResponse, CustomError> {
+    let copies: Vec<super::ServiceAccountSecretCopy> =
+        serde_json::from_str(&rollback_secret.service_account_secrets).map_err(|_| {
+            CustomError::InvalidInput("Service account secrets not valid".to_string())
+        })?;
+
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    // Viewers can only read secrets.
+    crate::rbac::require_vault_role(
+        &transaction,
+        current_user.user_id,
+        vault_id,
+        VaultRole::Editor,
+    )
+    .await?;
+
+    let team = queries::organisations::organisation()
+        .bind(&*transaction, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    let secret = queries::secrets::get()
+        .bind(&*transaction, &rollback_secret.secret_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    if secret.vault_id != vault_id {
+        return Err(CustomError::NotFound(
+            "Secret is not in this vault".to_string(),
+        ));
+    }
+
+    let version = queries::secrets::get_version()
+        .bind(
+            &*transaction,
+            &rollback_secret.version_id,
+            &rollback_secret.secret_id,
+        )
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    // This also records the restored ciphertext as the newest version.
+    queries::secrets::restore_version()
+        .bind(&*transaction, &version.id, &secret.id)
+        .await?;
+
+    super::replace_service_account_secrets(
+        &transaction,
+        secret.vault_id,
+        secret.environment_id,
+        &secret.name_blind_index,
+        &copies,
+    )
+    .await?;
+
+    crate::audit::Event::new(
+        AuditAction::RollbackSecret,
+        AuditAccessType::Web,
+        format!(
+            "Secret {} in Vault with ID {} rolled back to version {}",
+            secret.id, vault_id, version.version
+        ),
+    )
+    .by_user(current_user.user_id)
+    .vault(vault_id)
+    .secret(secret.id)
+    .environment(secret.environment_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
+
+    transaction.commit().await?;
+
+    crate::layout::redirect_and_snackbar(
+        &ui_components::routes::secrets::index_route(vault_id, team.id),
+        "Secret Rolled Back",
+    )
+}
@@ -0,0 +1,118 @@
+use crate::audit::Source;
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use axum::{
+    extract::{Extension, Form, Path},
+    response::IntoResponse,
+};
+use db::queries;
+use db::types::public::{AuditAccessType, AuditAction, VaultRole};
+use db::Pool;
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate, Default, Debug)]
+pub struct UpdateSecret {
+    pub secret_id: i32,
+    #[validate(length(min = 1, message = "The secret is mandatory"))]
+    pub secret: String,
+    // When the secret was last changed, as the editor saw it.
+    pub updated_at: String,
+    // The version of the vault key the browser encrypted with.
+    pub key_version: i32,
+    // JSON list of ServiceAccountSecretCopy, built by the browser.
+    pub service_account_secrets: String,
+}
+
+pub async fn update(
+    Path((organisation_id, vault_id)): Path<(i32, i32)>,
+    current_user: Authentication,
+    source: Source,
+    Form(update_secret): Form<UpdateSecret>,
+    Extension(pool): Extension<Pool>,
+) -> Result<impl IntoResponse, CustomError> {
+    let copies: Vec<super::ServiceAccountSecretCopy> =
+        serde_json::from_str(&update_secret.service_account_secrets).map_err(|_| {
+            CustomError::InvalidInput("Service account secrets not valid".to_string())
+        })?;
+
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    // Viewers can only read secrets.
+    crate::rbac::require_vault_role(
+        &transaction,
+        current_user.user_id,
+        vault_id,
+        VaultRole::Editor,
+    )
+    .await?;
+
+    let team = queries::organisations::organisation()
+        .bind(&*transaction, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    let secret = queries::secrets::get()
+        .bind(&*transaction, &update_secret.secret_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    if secret.vault_id != vault_id {
+        return Err(CustomError::NotFound(
+            "Secret is not in this vault".to_string(),
+        ));
+    }
+
+    crate::vaults::ensure_key_version(&transaction, vault_id, update_secret.key_version).await?;
+
+    let updated = queries::secrets::update_secret()
+        .bind(
+            &*transaction,
+            &update_secret.secret.as_ref(),
+            &update_secret.key_version,
+            &secret.id,
+            &update_secret.updated_at.as_ref(),
+        )
+        .await?;
+
+    if updated == 0 {
+        return Err(CustomError::Conflict(
+            "The secret has been changed by someone else, reload the page and try again"
+                .to_string(),
+        ));
+    }
+
+    super::replace_service_account_secrets(
+        &transaction,
+        secret.vault_id,
+        secret.environment_id,
+        &secret.name_blind_index,
+        &copies,
+    )
+    .await?;
+
+    crate::audit::Event::new(
+        AuditAction::UpdateSecret,
+        AuditAccessType::Web,
+        format!("Secret {} updated in Vault with ID {}", secret.id, vault_id),
+    )
+    .by_user(current_user.user_id)
+    .vault(vault_id)
+    .secret(secret.id)
+    .environment(secret.environment_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
+
+    transaction.commit().await?;
+
+    crate::layout::redirect_and_snackbar(
+        &ui_components::routes::secrets::index_route(vault_id, team.id),
+        "Secret Updated",
+    )
+}
@@ -1,5 +1,7 @@
+use crate::audit::Source;
 use crate::authentication::Authentication;
 use crate::errors::CustomError;
+use crate::rbac::{Authorize, ManageServiceAccounts};
 use axum::extract::Path;
 use axum::{
     extract::{Extension, Form},
@@ -21,22 +23,25 @@ pub struct ConnectServiceAccount {
 pub async fn connect(
     Path(organisation_id): Path<i32>,
     current_user: Authentication,
+    source: Source,
+    _: Authorize<ManageServiceAccounts>,
     Form(connect_form): Form<ConnectServiceAccount>,
     Extension(pool): Extension<Pool>,
 ) -> Result<impl IntoResponse, CustomError> {
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
 
     let team = queries::organisations::organisation()
-        .bind(&transaction, &organisation_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     queries::service_accounts::connect()
         .bind(
-            &transaction,
+            &*transaction,
             &connect_form.vault_id,
             &connect_form.environment_id,
             &connect_form.service_account_id,
@@ -45,20 +50,21 @@ pub async fn connect(
         )
         .await?;
 
-    queries::audit::insert()
-        .bind(
-            &transaction,
-            &current_user.user_id,
-            &organisation_id,
-            &AuditAction::ConnectServiceAccount,
-            &AuditAccessType::Web,
-            &format!(
-                "Service account {} connected",
-                &connect_form.service_account_id
-            )
-            .as_ref(),
-        )
-        .await?;
+    crate::audit::Event::new(
+        AuditAction::ConnectServiceAccount,
+        AuditAccessType::Web,
+        format!(
+            "Service account {} connected",
+            &connect_form.service_account_id
+        ),
+    )
+    .by_user(current_user.user_id)
+    .vault(connect_form.vault_id)
+    .environment(connect_form.environment_id)
+    .target_service_account(connect_form.service_account_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
 
     transaction.commit().await?;
 
@@ -1,5 +1,7 @@
+use crate::audit::Source;
 use crate::authentication::Authentication;
 use crate::errors::CustomError;
+use crate::rbac::{Authorize, ManageServiceAccounts};
 use axum::{
     extract::{Extension, Form, Path},
     response::{IntoResponse, Redirect},
@@ -18,41 +20,43 @@ pub struct DeleteServiceAccount {
 pub async fn delete(
     Path(organisation_id): Path<i32>,
     current_user: Authentication,
+    source: Source,
+    _: Authorize<ManageServiceAccounts>,
     Form(idor_delete_service_account): Form<DeleteServiceAccount>,
     Extension(pool): Extension<Pool>,
 ) -> Result<impl IntoResponse, CustomError> {
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
 
     let team = queries::organisations::organisation()
-        .bind(&transaction, &organisation_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     queries::service_accounts::delete_service_account()
         .bind(
-            &transaction,
+            &*transaction,
             &idor_delete_service_account.service_account_id,
             &organisation_id,
         )
         .await?;
 
-    queries::audit::insert()
-        .bind(
-            &transaction,
-            &current_user.user_id,
-            &organisation_id,
-            &AuditAction::ConnectServiceAccount,
-            &AuditAccessType::Web,
-            &format!(
-                "Service account {} deleted",
-                idor_delete_service_account.service_account_id
-            )
-            .as_ref(),
-        )
-        .await?;
+    crate::audit::Event::new(
+        AuditAction::DeleteServiceAccount,
+        AuditAccessType::Web,
+        format!(
+            "Service account {} deleted",
+            idor_delete_service_account.service_account_id
+        ),
+    )
+    .by_user(current_user.user_id)
+    .target_service_account(idor_delete_service_account.service_account_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
 
     transaction.commit().await?;
 
@@ -14,21 +14,22 @@ pub async fn index(
 ) -> Result<Html<String>, CustomError> {
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
 
     let team = queries::organisations::organisation()
-        .bind(&transaction, &organisation_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     let service_accounts = queries::service_accounts::get_all()
-        .bind(&transaction, &organisation_id)
+        .bind(&*transaction, &organisation_id)
         .all()
         .await?;
 
     let environments_and_vaults = queries::environments::get_environments_and_vaults()
-        .bind(&transaction)
+        .bind(&*transaction)
         .all()
         .await?;
 
@@ -2,6 +2,7 @@ mod connect_account;
 mod delete;
 mod index;
 mod new_account;
+pub mod rotate_key;
 
 use axum::{
     routing::{get, post},
@@ -26,4 +27,8 @@ pub fn routes() -> Router {
             "/app/team/:organisation_id/service_accounts/delete",
             post(delete::delete),
         )
+        .route(
+            "/app/team/:organisation_id/service_accounts/rotate_key",
+            post(rotate_key::rotate),
+        )
 }
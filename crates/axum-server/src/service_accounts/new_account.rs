@@ -1,5 +1,7 @@
+use crate::audit::Source;
 use crate::authentication::Authentication;
 use crate::errors::CustomError;
+use crate::rbac::{Authorize, ManageServiceAccounts};
 use axum::{
     extract::{Extension, Form, Path},
     response::{IntoResponse, Redirect},
@@ -23,22 +25,25 @@ pub struct NewServiceAccount {
 pub async fn new(
     Path(organisation_id): Path<i32>,
     current_user: Authentication,
+    source: Source,
+    _: Authorize<ManageServiceAccounts>,
     Form(new_service_account): Form<NewServiceAccount>,
     Extension(pool): Extension<Pool>,
 ) -> Result<impl IntoResponse, CustomError> {
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
 
     let team = queries::organisations::organisation()
-        .bind(&transaction, &organisation_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     queries::service_accounts::insert()
         .bind(
-            &transaction,
+            &*transaction,
             &organisation_id,
             &new_service_account.name.as_ref(),
             &new_service_account.public_key.as_ref(),
@@ -46,16 +51,15 @@ pub async fn new(
         )
         .await?;
 
-    queries::audit::insert()
-        .bind(
-            &transaction,
-            &current_user.user_id,
-            &organisation_id,
-            &AuditAction::NewServiceAccount,
-            &AuditAccessType::Web,
-            &String::from("Service account created").as_ref(),
-        )
-        .await?;
+    crate::audit::Event::new(
+        AuditAction::NewServiceAccount,
+        AuditAccessType::Web,
+        String::from("Service account created"),
+    )
+    .by_user(current_user.user_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
 
     transaction.commit().await?;
 
@@ -0,0 +1,246 @@
+use crate::audit::Source;
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use crate::rbac::{Authorize, ManageServiceAccounts};
+use axum::{
+    extract::{Extension, Form, Path},
+    response::{IntoResponse, Redirect},
+};
+use db::queries;
+use db::types::public::{AuditAccessType, AuditAction, VaultRole};
+use db::Pool;
+use serde::Deserialize;
+use std::time::Duration;
+use validator::Validate;
+
+// The longest the previous key can keep working, 30 days.
+const MAX_GRACE_HOURS: u32 = 720;
+
+// How often we look for previous keys that have passed their grace period.
+const RETIRE_EVERY: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Deserialize, Validate, Default, Debug)]
+pub struct RotateKey {
+    pub service_account_id: i32,
+    #[validate(length(min = 1, message = "Where did the new key go?"))]
+    pub public_key: String,
+    #[validate(length(min = 1, message = "Where did the new key go?"))]
+    pub encrypted_private_key: String,
+    // How long the previous key keeps working, 0 retires it straight away.
+    pub grace_hours: u32,
+    // The ephemeral key the secrets were encrypted with, empty if the
+    // service account isn't connected to a vault.
+    pub public_ecdh_key: String,
+    // JSON list built by the browser, see RotatedSecret.
+    pub secrets: String,
+}
+
+// A secret encrypted for the new key of the service account.
+#[derive(Deserialize, Debug)]
+pub struct RotatedSecret {
+    pub name_blind_index: String,
+    pub encrypted_name: String,
+    pub encrypted_secret_value: String,
+}
+
+pub async fn rotate(
+    Path(organisation_id): Path<i32>,
+    current_user: Authentication,
+    source: Source,
+    _: Authorize<ManageServiceAccounts>,
+    Extension(pool): Extension<Pool>,
+    Form(rotate_key): Form<RotateKey>,
+) -> Result<impl IntoResponse, CustomError> {
+    if rotate_key.public_key.is_empty() || rotate_key.encrypted_private_key.is_empty() {
+        return Err(CustomError::InvalidInput(
+            "Where did the new key go?".to_string(),
+        ));
+    }
+
+    if rotate_key.grace_hours > MAX_GRACE_HOURS {
+        return Err(CustomError::InvalidInput(format!(
+            "The previous key can keep working for at most {} hours",
+            MAX_GRACE_HOURS
+        )));
+    }
+
+    let secrets: Vec<RotatedSecret> = if rotate_key.secrets.is_empty() {
+        Default::default()
+    } else {
+        serde_json::from_str(&rotate_key.secrets)
+            .map_err(|_| CustomError::InvalidInput("Rotated secrets not valid".to_string()))?
+    };
+
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    let service_account = queries::service_accounts::get_dangerous()
+        .bind(&*transaction, &rotate_key.service_account_id)
+        .opt()
+        .await?
+        .filter(|service_account| service_account.organisation_id == organisation_id)
+        .ok_or_else(|| CustomError::NotFound("Service account not found".to_string()))?;
+
+    // The new key needs a copy of every secret in the environment the
+    // service account is connected to, or it would lose access to some.
+    // Writing those copies is the same as sharing secrets, so Editors only.
+    if let (Some(vault_id), Some(environment_id)) =
+        (service_account.vault_id, service_account.environment_id)
+    {
+        crate::rbac::require_vault_role(
+            &transaction,
+            current_user.user_id,
+            vault_id,
+            VaultRole::Editor,
+        )
+        .await?;
+
+        let mut expected = queries::secrets::get_blind_indexes()
+            .bind(&*transaction, &vault_id, &environment_id)
+            .all()
+            .await?;
+        expected.sort();
+
+        let mut received: Vec<String> = secrets
+            .iter()
+            .map(|secret| secret.name_blind_index.clone())
+            .collect();
+        received.sort();
+
+        if expected != received {
+            return Err(CustomError::Conflict(
+                "The secrets have changed since the page loaded, reload the page and try again"
+                    .to_string(),
+            ));
+        }
+    }
+
+    let rotated = queries::service_accounts::rotate_key()
+        .bind(
+            &*transaction,
+            &(rotate_key.grace_hours as i32),
+            &rotate_key.public_key.as_ref(),
+            &rotate_key.encrypted_private_key.as_ref(),
+            &service_account.id,
+            &organisation_id,
+        )
+        .opt()
+        .await?
+        .ok_or_else(|| {
+            CustomError::Conflict(
+                "The previous key of this service account is still being retired".to_string(),
+            )
+        })?;
+
+    for secret in &secrets {
+        queries::service_account_secrets::insert()
+            .bind(
+                &*transaction,
+                &service_account.id,
+                &secret.encrypted_name.as_ref(),
+                &secret.name_blind_index.as_ref(),
+                &secret.encrypted_secret_value.as_ref(),
+                &rotate_key.public_ecdh_key.as_ref(),
+                &Some(rotate_key.public_key.as_str()),
+            )
+            .await?;
+    }
+
+    let event = crate::audit::Event::new(
+        AuditAction::RotateServiceAccountKey,
+        AuditAccessType::Web,
+        format!(
+            "Key of service account {} rotated, {} secrets copied to the new key, \
+            previous key valid until {}",
+            service_account.name,
+            secrets.len(),
+            rotated.previous_key_expires_at
+        ),
+    )
+    .by_user(current_user.user_id)
+    .target_service_account(service_account.id);
+    connected_to(event, &service_account)
+        .source(&source)
+        .insert(&transaction, organisation_id)
+        .await?;
+
+    // No grace period, so the previous key goes now rather than
+    // waiting for retire_previous_keys.
+    if rotate_key.grace_hours == 0 {
+        queries::service_account_secrets::delete_for_key()
+            .bind(
+                &*transaction,
+                &service_account.id,
+                &rotated.previous_ecdh_public_key.as_ref(),
+            )
+            .await?;
+
+        queries::service_accounts::retire_previous_key()
+            .bind(&*transaction, &service_account.id, &organisation_id)
+            .await?;
+
+        let event = crate::audit::Event::new(
+            AuditAction::RetireServiceAccountKey,
+            AuditAccessType::Web,
+            format!(
+                "Previous key of service account {} retired",
+                service_account.name
+            ),
+        )
+        .by_user(current_user.user_id)
+        .target_service_account(service_account.id);
+        connected_to(event, &service_account)
+            .source(&source)
+            .insert(&transaction, organisation_id)
+            .await?;
+    }
+
+    transaction.commit().await?;
+
+    Ok(Redirect::to(
+        &ui_components::routes::service_accounts::index_route(organisation_id),
+    ))
+}
+
+// Record which vault and environment the service account is connected to, if any.
+fn connected_to(
+    mut event: crate::audit::Event,
+    service_account: &queries::service_accounts::GetDangerous,
+) -> crate::audit::Event {
+    if let Some(vault_id) = service_account.vault_id {
+        event = event.vault(vault_id);
+    }
+    if let Some(environment_id) = service_account.environment_id {
+        event = event.environment(environment_id);
+    }
+    event
+}
+
+// Runs in the background for as long as the server does.
+pub async fn retire_previous_keys(pool: Pool) {
+    loop {
+        match retire(&pool).await {
+            Ok(0) => {}
+            Ok(retired) => tracing::info!("retired {} service account keys", retired),
+            Err(e) => tracing::error!("could not retire service account keys: {}", e),
+        }
+        tokio::time::sleep(RETIRE_EVERY).await;
+    }
+}
+
+async fn retire(pool: &Pool) -> Result<i32, CustomError> {
+    let mut client = pool.get().await?;
+    // Not an RLS transaction, retire_service_account_keys covers every team.
+    let transaction = client.transaction().await?;
+
+    let retired = queries::service_accounts::retire_expired_keys()
+        .bind(&transaction)
+        .one()
+        .await?;
+
+    transaction.commit().await?;
+
+    Ok(retired)
+}
@@ -1,3 +1,4 @@
+use crate::audit::Source;
 use crate::authentication::Authentication;
 use crate::errors::CustomError;
 use axum::{
@@ -5,6 +6,7 @@ use axum::{
     response::{IntoResponse, Redirect},
 };
 use db::queries;
+use db::types::public::{AuditAccessType, AuditAction};
 use db::Pool;
 use serde::Deserialize;
 use sha2::{Digest, Sha256};
@@ -19,10 +21,12 @@ pub async fn invite(
     Path(invite): Path<Invite>,
     Extension(pool): Extension<Pool>,
     current_user: Authentication,
+    source: Source,
 ) -> Result<impl IntoResponse, CustomError> {
     let team_id = accept_invitation(
         &pool,
         &current_user,
+        &source,
         &invite.invite_selector,
         &invite.invite_validator,
     )
@@ -36,6 +40,7 @@ pub async fn invite(
 pub async fn accept_invitation(
     pool: &Pool,
     current_user: &Authentication,
+    source: &Source,
     invitation_selector: &str,
     invitation_verifier: &str,
 ) -> Result<i32, CustomError> {
@@ -47,33 +52,37 @@ pub async fn accept_invitation(
 
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, current_user).await?;
 
     let invitation = queries::invitations::get_invitation()
-        .bind(&transaction, &invitation_selector)
-        .one()
-        .await?;
+        .bind(&*transaction, &invitation_selector)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     if invitation.invitation_verifier_hash == invitation_verifier_hash_base64 {
         let user = queries::users::user()
-            .bind(&transaction, &current_user.user_id)
-            .one()
-            .await?;
+            .bind(&*transaction, &current_user.user_id)
+            .opt()
+            .await?
+            .ok_or_else(CustomError::not_found)?;
 
         // Make sure the user accepting the invitation is the user that we emailed
         if user.email == invitation.email {
             let user = queries::users::get_by_email()
-                .bind(&transaction, &user.email.as_ref())
-                .one()
-                .await?;
+                .bind(&*transaction, &user.email.as_ref())
+                .opt()
+                .await?
+                .ok_or_else(CustomError::not_found)?;
 
             queries::organisations::add_user_to_organisation()
                 .bind(
-                    &transaction,
+                    &*transaction,
                     &user.id,
                     &invitation.organisation_id,
                     &invitation.roles.as_ref(),
+                    &invitation.team_role_id,
                 )
                 .await?;
 
@@ -81,7 +90,7 @@ pub async fn accept_invitation(
             if (None, None) == (user.first_name, user.last_name) {
                 queries::users::set_name()
                     .bind(
-                        &transaction,
+                        &*transaction,
                         &invitation.first_name.as_ref(),
                         &invitation.last_name.as_ref(),
                         &current_user.user_id,
@@ -91,11 +100,21 @@ pub async fn accept_invitation(
 
             queries::invitations::delete_invitation()
                 .bind(
-                    &transaction,
+                    &*transaction,
                     &invitation.email.as_ref(),
                     &invitation.organisation_id,
                 )
                 .await?;
+
+            crate::audit::Event::new(
+                AuditAction::AcceptInvite,
+                AuditAccessType::Web,
+                format!("{} accepted an invitation", invitation.email),
+            )
+            .by_user(current_user.user_id)
+            .source(source)
+            .insert(&transaction, invitation.organisation_id)
+            .await?;
         }
     }
 
@@ -1,13 +1,14 @@
+use crate::audit::Source;
 use crate::authentication::Authentication;
 use crate::errors::CustomError;
+use crate::rbac::{Authorize, InviteMembers};
 use axum::{
     extract::{Extension, Form, Path},
     response::IntoResponse,
 };
 use db::queries;
-use db::types;
 use db::types::public::{AuditAccessType, AuditAction};
-use db::Pool;
+use db::{Pool, Transaction};
 use lettre::Message;
 use rand::Rng;
 use serde::Deserialize;
@@ -22,22 +23,71 @@ pub struct NewInvite {
     pub first_name: String,
     #[validate(length(min = 1, message = "The last name is mandatory"))]
     pub last_name: String,
-    pub admin: Option<String>,
+    // Administrator, Collaborator or the id of one of the team's roles.
+    pub role: String,
 }
 
 pub async fn create_invite(
     Path(organisation_id): Path<i32>,
     current_user: Authentication,
+    source: Source,
+    _: Authorize<InviteMembers>,
     Extension(pool): Extension<Pool>,
     Extension(config): Extension<crate::config::Config>,
     Form(new_invite): Form<NewInvite>,
-    authentication: Authentication,
 ) -> Result<impl IntoResponse, CustomError> {
-    let invite_hash = create(&pool, &authentication, &new_invite, organisation_id).await?;
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    let (invitation_verifier_base64, invitation_selector_base64) = create(
+        &transaction,
+        current_user.user_id,
+        &new_invite,
+        organisation_id,
+    )
+    .await?;
+
+    crate::audit::Event::new(
+        AuditAction::CreateInvite,
+        AuditAccessType::Web,
+        format!("{} invited", &new_invite.email),
+    )
+    .by_user(current_user.user_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
 
-    let invitation_verifier_base64 = invite_hash.0;
-    let invitation_selector_base64 = invite_hash.1;
+    let team = queries::organisations::organisation()
+        .bind(&*transaction, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    transaction.commit().await?;
 
+    // Only once the invitation is saved, or the link wouldn't work.
+    send_invite_email(
+        &config,
+        &new_invite.email,
+        &invitation_selector_base64,
+        &invitation_verifier_base64,
+    );
+
+    crate::layout::redirect_and_snackbar(
+        &ui_components::routes::team::index_route(team.id),
+        "Invitation Created",
+    )
+}
+
+// If SMTP is configured email the invitee a link to accept the invite.
+pub fn send_invite_email(
+    config: &crate::config::Config,
+    email: &str,
+    invitation_selector_base64: &str,
+    invitation_verifier_base64: &str,
+) {
     if let Some(smtp_config) = &config.smtp_config {
         let url = format!(
             "{}/app/invite/{}/{}",
@@ -55,52 +105,23 @@ pub async fn create_invite(
 
         let email = Message::builder()
             .from(smtp_config.from_email.clone())
-            .to(new_invite.email.parse().unwrap())
+            .to(email.parse().unwrap())
             .subject("You are invited to a Cloak Team")
             .body(body)
             .unwrap();
 
-        crate::email::send_email(&config, email)
+        crate::email::send_email(config, email)
     }
-
-    // Create a transaction and setup RLS
-    let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
-
-    queries::audit::insert()
-        .bind(
-            &transaction,
-            &current_user.user_id,
-            &organisation_id,
-            &AuditAction::CreateInvite,
-            &AuditAccessType::Web,
-            &format!("{} invited", &new_invite.email).as_ref(),
-        )
-        .await?;
-
-    let team = queries::organisations::organisation()
-        .bind(&transaction, &organisation_id)
-        .one()
-        .await?;
-
-    crate::layout::redirect_and_snackbar(
-        &ui_components::routes::team::index_route(team.id),
-        "Invitation Created",
-    )
 }
 
+// Saves the invitation in the caller's transaction so it's committed along
+// with the audit event. Returns the verifier and selector for the link.
 pub async fn create(
-    pool: &Pool,
-    current_user: &Authentication,
+    transaction: &Transaction<'_>,
+    user_id: i32,
     new_invite: &NewInvite,
     organisation_id: i32,
 ) -> Result<(String, String), CustomError> {
-    // Create a transaction and setup RLS
-    let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, current_user).await?;
-
     let invitation_selector = rand::thread_rng().gen::<[u8; 6]>();
     let invitation_selector_base64 =
         base64::encode_config(invitation_selector, base64::URL_SAFE_NO_PAD);
@@ -111,18 +132,14 @@ pub async fn create(
     let invitation_verifier_base64 =
         base64::encode_config(invitation_verifier, base64::URL_SAFE_NO_PAD);
 
-    let roles = if new_invite.admin.is_some() {
-        vec![
-            types::public::Role::Administrator,
-            types::public::Role::Collaborator,
-        ]
-    } else {
-        vec![types::public::Role::Collaborator]
-    };
+    let (roles, team_role_id) = crate::rbac::parse_role(&new_invite.role)?;
+
+    crate::rbac::ensure_can_grant(transaction, user_id, organisation_id, &roles, team_role_id)
+        .await?;
 
     queries::invitations::insert_invitation()
         .bind(
-            &transaction,
+            transaction,
             &organisation_id,
             &new_invite.email.as_ref(),
             &new_invite.first_name.as_ref(),
@@ -130,10 +147,9 @@ pub async fn create(
             &invitation_selector_base64.as_ref(),
             &invitation_verifier_hash_base64.as_ref(),
             &roles.as_ref(),
+            &team_role_id,
         )
         .await?;
 
-    transaction.commit().await?;
-
     Ok((invitation_verifier_base64, invitation_selector_base64))
 }
@@ -1,7 +1,9 @@
+use crate::audit::Source;
 use crate::authentication::Authentication;
 use crate::errors::CustomError;
+use crate::rbac::{Authorize, ManageTeam};
 use axum::{
-    extract::{Extension, Form},
+    extract::{Extension, Form, Path},
     response::IntoResponse,
 };
 use db::queries;
@@ -12,38 +14,41 @@ use validator::Validate;
 
 #[derive(Deserialize, Validate, Default, Debug)]
 pub struct DeleteMember {
-    pub organisation_id: i32,
     pub user_id: i32,
 }
 
 pub async fn delete(
+    Path(organisation_id): Path<i32>,
     current_user: Authentication,
+    source: Source,
+    _: Authorize<ManageTeam>,
     Extension(pool): Extension<Pool>,
     Form(delete_member): Form<DeleteMember>,
 ) -> Result<impl IntoResponse, CustomError> {
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    crate::rbac::ensure_not_owner(&transaction, delete_member.user_id, organisation_id).await?;
 
     queries::organisations::remove_user()
-        .bind(
-            &transaction,
-            &delete_member.user_id,
-            &delete_member.organisation_id,
-        )
+        .bind(&*transaction, &delete_member.user_id, &organisation_id)
         .await?;
 
-    queries::audit::insert()
-        .bind(
-            &transaction,
-            &current_user.user_id,
-            &delete_member.organisation_id,
-            &AuditAction::CreateInvite,
-            &AuditAccessType::Web,
-            &format!("{} removed from team", &delete_member.user_id).as_ref(),
-        )
-        .await?;
+    // Nobody is removed if that was the last Administrator.
+    crate::rbac::ensure_administrator(&transaction, organisation_id).await?;
+
+    crate::audit::Event::new(
+        AuditAction::RemoveTeamMember,
+        AuditAccessType::Web,
+        format!("{} removed from team", &delete_member.user_id),
+    )
+    .by_user(current_user.user_id)
+    .target_user(delete_member.user_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
 
     transaction.commit().await?;
 
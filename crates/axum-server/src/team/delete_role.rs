@@ -0,0 +1,60 @@
+use crate::audit::Source;
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use crate::rbac::{Authorize, ManageTeam};
+use axum::{
+    extract::{Extension, Form, Path},
+    response::IntoResponse,
+};
+use db::queries;
+use db::types::public::{AuditAccessType, AuditAction};
+use db::Pool;
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate, Default, Debug)]
+pub struct DeleteRole {
+    pub team_role_id: i32,
+}
+
+pub async fn delete_role(
+    Path(organisation_id): Path<i32>,
+    current_user: Authentication,
+    source: Source,
+    _: Authorize<ManageTeam>,
+    Extension(pool): Extension<Pool>,
+    Form(delete_role): Form<DeleteRole>,
+) -> Result<impl IntoResponse, CustomError> {
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    // Nothing is deleted if a member or an invitation still has the role.
+    let name = queries::rbac::delete_team_role()
+        .bind(&*transaction, &delete_role.team_role_id, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(|| {
+            CustomError::InvalidInput(
+                "Give everyone with this role another role before deleting it".to_string(),
+            )
+        })?;
+
+    crate::audit::Event::new(
+        AuditAction::DeleteRole,
+        AuditAccessType::Web,
+        format!("Role {} deleted", name),
+    )
+    .by_user(current_user.user_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
+
+    transaction.commit().await?;
+
+    crate::layout::redirect_and_snackbar(
+        &ui_components::routes::team::index_route(organisation_id),
+        "Role Deleted",
+    )
+}
@@ -0,0 +1,98 @@
+use crate::audit::Source;
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use crate::rbac::{Authorize, ManageTeam};
+use axum::{
+    extract::{Extension, Form, Path},
+    response::IntoResponse,
+};
+use db::queries;
+use db::types::public::{AuditAccessType, AuditAction};
+use db::Pool;
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate, Default, Debug)]
+pub struct DeleteTeam {
+    // Typed in to confirm which team is going.
+    pub name: String,
+}
+
+// The audit trail goes with the team, so this is recorded in one of the
+// owner's other teams if they have one.
+pub async fn delete_team(
+    Path(organisation_id): Path<i32>,
+    current_user: Authentication,
+    source: Source,
+    _: Authorize<ManageTeam>,
+    Extension(pool): Extension<Pool>,
+    Form(delete_team): Form<DeleteTeam>,
+) -> Result<impl IntoResponse, CustomError> {
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    let team = queries::organisations::organisation()
+        .bind(&*transaction, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    let name = match team.name {
+        Some(name) if name == delete_team.name.trim() => name,
+        Some(_) => {
+            return Err(CustomError::InvalidInput(
+                "The name doesn't match the team".to_string(),
+            ))
+        }
+        None => {
+            return Err(CustomError::InvalidInput(
+                "Give the team a name before deleting it".to_string(),
+            ))
+        }
+    };
+
+    let remaining_team = queries::organisations::get_teams()
+        .bind(&*transaction, &current_user.user_id)
+        .all()
+        .await?
+        .into_iter()
+        .find(|team| team.id != organisation_id);
+
+    if let Some(remaining_team) = &remaining_team {
+        crate::audit::Event::new(
+            AuditAction::DeleteTeam,
+            AuditAccessType::Web,
+            format!("Team {} ({}) deleted", name, organisation_id),
+        )
+        .by_user(current_user.user_id)
+        .source(&source)
+        .insert(&transaction, remaining_team.id)
+        .await?;
+    }
+
+    // Takes the vaults, service accounts and audit trail with it.
+    let deleted = queries::organisations::delete_organisation()
+        .bind(&*transaction, &organisation_id)
+        .one()
+        .await?;
+
+    if deleted == 0 {
+        return Err(CustomError::PermissionDenied(
+            "Only the owner can delete a team".to_string(),
+        ));
+    }
+
+    transaction.commit().await?;
+
+    if remaining_team.is_none() {
+        tracing::info!(
+            "Team {} deleted by user {}",
+            organisation_id,
+            current_user.user_id
+        );
+    }
+
+    crate::layout::redirect_and_snackbar(crate::registration_handler::INDEX, "Team Deleted")
+}
@@ -15,35 +15,41 @@ pub async fn index(
 ) -> Result<Html<String>, CustomError> {
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
 
     let team = queries::organisations::organisation()
-        .bind(&transaction, &organisation_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     let users = queries::organisations::get_users()
-        .bind(&transaction, &organisation_id)
+        .bind(&*transaction, &organisation_id)
         .all()
         .await?;
 
     let permissions: Vec<types::public::Permission> = queries::rbac::permissions()
-        .bind(&transaction, &current_user.user_id, &organisation_id)
+        .bind(&*transaction, &current_user.user_id, &organisation_id)
         .all()
         .await?;
 
-    let can_manage_team = permissions
-        .iter()
-        .any(|p| p == &types::public::Permission::ManageTeam);
+    let can_manage_team = permissions.contains(&types::public::Permission::ManageTeam);
+    let can_invite_members = permissions.contains(&types::public::Permission::InviteMembers);
 
-    let user = queries::users::user()
-        .bind(&transaction, &current_user.user_id)
-        .one()
+    let team_roles = queries::rbac::team_roles()
+        .bind(&*transaction, &organisation_id)
+        .all()
         .await?;
 
+    let user = queries::users::user()
+        .bind(&*transaction, &current_user.user_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
     let invites = queries::invitations::get_all()
-        .bind(&transaction, &organisation_id)
+        .bind(&*transaction, &organisation_id)
         .all()
         .await?;
 
@@ -52,6 +58,8 @@ pub async fn index(
         users,
         team,
         user,
+        team_roles,
         can_manage_team,
+        can_invite_members,
     )))
 }
@@ -0,0 +1,66 @@
+use crate::audit::Source;
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use axum::{
+    extract::{Extension, Path},
+    response::IntoResponse,
+};
+use db::queries;
+use db::types::public::{AuditAccessType, AuditAction};
+use db::Pool;
+
+pub async fn leave(
+    Path(organisation_id): Path<i32>,
+    current_user: Authentication,
+    source: Source,
+    Extension(pool): Extension<Pool>,
+) -> Result<impl IntoResponse, CustomError> {
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    crate::rbac::ensure_not_owner(&transaction, current_user.user_id, organisation_id).await?;
+
+    // While we're still a member and allowed to write to the team's trail.
+    crate::audit::Event::new(
+        AuditAction::LeaveTeam,
+        AuditAccessType::Web,
+        "Left the team".to_string(),
+    )
+    .by_user(current_user.user_id)
+    .target_user(current_user.user_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
+
+    let vaults = queries::vaults::get_all()
+        .bind(&*transaction, &current_user.user_id, &organisation_id)
+        .all()
+        .await?;
+
+    // Don't leave access to the team's vaults behind.
+    queries::user_vaults::leave_environments()
+        .bind(&*transaction, &current_user.user_id, &organisation_id)
+        .await?;
+
+    queries::user_vaults::leave_vaults()
+        .bind(&*transaction, &current_user.user_id, &organisation_id)
+        .await?;
+
+    // Nobody leaves if they were the last Admin of one of the vaults.
+    for vault in vaults {
+        crate::rbac::ensure_vault_administrator(&transaction, vault.id).await?;
+    }
+
+    queries::organisations::remove_user()
+        .bind(&*transaction, &current_user.user_id, &organisation_id)
+        .await?;
+
+    // Nobody leaves if they were the last Administrator.
+    crate::rbac::ensure_administrator(&transaction, organisation_id).await?;
+
+    transaction.commit().await?;
+
+    crate::layout::redirect_and_snackbar(crate::registration_handler::INDEX, "Left Team")
+}
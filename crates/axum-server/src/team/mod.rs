@@ -1,11 +1,17 @@
 mod accept_invite;
-mod create_invite;
+pub mod create_invite;
 mod delete_member;
+mod delete_role;
+mod delete_team;
 mod index;
+mod leave_team;
+mod new_role;
 mod new_team;
 mod set_name;
+mod set_role;
 mod switch;
 mod teams_popup;
+mod transfer;
 
 use axum::{
     routing::{get, post},
@@ -36,5 +42,26 @@ pub fn routes() -> Router {
             "/app/team/:organisation_id/set_name",
             post(set_name::set_name),
         )
+        .route(
+            "/app/team/:organisation_id/set_role",
+            post(set_role::set_role),
+        )
+        .route(
+            "/app/team/:organisation_id/transfer",
+            post(transfer::transfer),
+        )
+        .route(
+            "/app/team/:organisation_id/delete_team",
+            post(delete_team::delete_team),
+        )
+        .route("/app/team/:organisation_id/leave", post(leave_team::leave))
         .route("/app/team/:organisation_id/new", post(new_team::new_team))
+        .route(
+            "/app/team/:organisation_id/roles/new",
+            post(new_role::new_role),
+        )
+        .route(
+            "/app/team/:organisation_id/roles/delete",
+            post(delete_role::delete_role),
+        )
 }
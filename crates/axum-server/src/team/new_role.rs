@@ -0,0 +1,92 @@
+use crate::audit::Source;
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use crate::rbac::{Authorize, ManageTeam};
+use axum::{
+    extract::{Extension, Form, Path},
+    response::IntoResponse,
+};
+use db::queries;
+use db::types::public::{AuditAccessType, AuditAction, Permission};
+use db::Pool;
+use serde::Deserialize;
+use validator::Validate;
+
+// A checkbox for each permission, they're only sent when ticked.
+#[derive(Deserialize, Validate, Default, Debug)]
+pub struct NewRole {
+    #[validate(length(min = 1, message = "The name is mandatory"))]
+    pub name: String,
+    pub manage_team: Option<String>,
+    pub invite_members: Option<String>,
+    pub create_vault: Option<String>,
+    pub delete_vault: Option<String>,
+    pub manage_service_accounts: Option<String>,
+    pub view_audit: Option<String>,
+}
+
+impl NewRole {
+    fn permissions(&self) -> Vec<Permission> {
+        [
+            (&self.manage_team, Permission::ManageTeam),
+            (&self.invite_members, Permission::InviteMembers),
+            (&self.create_vault, Permission::CreateVault),
+            (&self.delete_vault, Permission::DeleteVault),
+            (
+                &self.manage_service_accounts,
+                Permission::ManageServiceAccounts,
+            ),
+            (&self.view_audit, Permission::ViewAudit),
+        ]
+        .into_iter()
+        .filter(|(ticked, _)| ticked.is_some())
+        .map(|(_, permission)| permission)
+        .collect()
+    }
+}
+
+pub async fn new_role(
+    Path(organisation_id): Path<i32>,
+    current_user: Authentication,
+    source: Source,
+    _: Authorize<ManageTeam>,
+    Extension(pool): Extension<Pool>,
+    Form(new_role): Form<NewRole>,
+) -> Result<impl IntoResponse, CustomError> {
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    let permissions = new_role.permissions();
+
+    queries::rbac::insert_team_role()
+        .bind(
+            &*transaction,
+            &organisation_id,
+            &new_role.name.trim(),
+            &permissions.as_ref(),
+        )
+        .await?;
+
+    crate::audit::Event::new(
+        AuditAction::CreateRole,
+        AuditAccessType::Web,
+        format!(
+            "Role {} created with {:?}",
+            new_role.name.trim(),
+            permissions
+        ),
+    )
+    .by_user(current_user.user_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
+
+    transaction.commit().await?;
+
+    crate::layout::redirect_and_snackbar(
+        &ui_components::routes::team::index_route(organisation_id),
+        "Role Created",
+    )
+}
@@ -1,3 +1,4 @@
+use crate::audit::Source;
 use crate::authentication::Authentication;
 use crate::errors::CustomError;
 use axum::{
@@ -6,6 +7,7 @@ use axum::{
 };
 use db::queries;
 use db::types;
+use db::types::public::{AuditAccessType, AuditAction};
 use db::Pool;
 use serde::Deserialize;
 use validator::Validate;
@@ -19,16 +21,17 @@ pub struct NewTeam {
 pub async fn new_team(
     Path(organisation_id): Path<i32>,
     current_user: Authentication,
+    source: Source,
     Form(new_team): Form<NewTeam>,
     Extension(pool): Extension<Pool>,
 ) -> Result<impl IntoResponse, CustomError> {
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
 
     let org_id = queries::organisations::insert_organisation()
-        .bind(&transaction)
+        .bind(&*transaction)
         .one()
         .await?;
 
@@ -39,17 +42,28 @@ pub async fn new_team(
 
     queries::organisations::add_user_to_organisation()
         .bind(
-            &transaction,
+            &*transaction,
             &current_user.user_id,
             &org_id,
             &roles.as_ref(),
+            &None,
         )
         .await?;
 
     queries::organisations::set_name()
-        .bind(&transaction, &new_team.name.as_ref(), &org_id)
+        .bind(&*transaction, &new_team.name.as_ref(), &org_id)
         .await?;
 
+    crate::audit::Event::new(
+        AuditAction::CreateTeam,
+        AuditAccessType::Web,
+        format!("Team {} created", new_team.name),
+    )
+    .by_user(current_user.user_id)
+    .source(&source)
+    .insert(&transaction, org_id)
+    .await?;
+
     transaction.commit().await?;
 
     crate::layout::redirect_and_snackbar(
@@ -1,10 +1,13 @@
+use crate::audit::Source;
 use crate::authentication::Authentication;
 use crate::errors::CustomError;
+use crate::rbac::{Authorize, ManageTeam};
 use axum::{
     extract::{Extension, Form, Path},
     response::IntoResponse,
 };
 use db::queries;
+use db::types::public::{AuditAccessType, AuditAction};
 use db::Pool;
 use serde::Deserialize;
 use validator::Validate;
@@ -18,18 +21,30 @@ pub struct SetName {
 pub async fn set_name(
     Path(organisation_id): Path<i32>,
     current_user: Authentication,
+    source: Source,
+    _: Authorize<ManageTeam>,
     Extension(pool): Extension<Pool>,
     Form(set_name): Form<SetName>,
 ) -> Result<impl IntoResponse, CustomError> {
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
 
     queries::organisations::set_name()
-        .bind(&transaction, &set_name.name.as_ref(), &organisation_id)
+        .bind(&*transaction, &set_name.name.as_ref(), &organisation_id)
         .await?;
 
+    crate::audit::Event::new(
+        AuditAction::RenameTeam,
+        AuditAccessType::Web,
+        format!("Team renamed to {}", set_name.name),
+    )
+    .by_user(current_user.user_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
+
     transaction.commit().await?;
 
     crate::layout::redirect_and_snackbar(
@@ -0,0 +1,75 @@
+use crate::audit::Source;
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use crate::rbac::{Authorize, ManageTeam};
+use axum::{
+    extract::{Extension, Form, Path},
+    response::IntoResponse,
+};
+use db::queries;
+use db::types::public::{AuditAccessType, AuditAction};
+use db::Pool;
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate, Default, Debug)]
+pub struct SetRole {
+    pub user_id: i32,
+    // Administrator, Collaborator or the id of one of the team's roles.
+    pub role: String,
+}
+
+pub async fn set_role(
+    Path(organisation_id): Path<i32>,
+    current_user: Authentication,
+    source: Source,
+    _: Authorize<ManageTeam>,
+    Extension(pool): Extension<Pool>,
+    Form(set_role): Form<SetRole>,
+) -> Result<impl IntoResponse, CustomError> {
+    let (roles, team_role_id) = crate::rbac::parse_role(&set_role.role)?;
+
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    crate::rbac::ensure_not_owner(&transaction, set_role.user_id, organisation_id).await?;
+
+    let updated = queries::organisations::set_roles()
+        .bind(
+            &*transaction,
+            &roles.as_ref(),
+            &team_role_id,
+            &set_role.user_id,
+            &organisation_id,
+        )
+        .await?;
+
+    if updated == 0 {
+        return Err(CustomError::NotFound(
+            "User is not a member of this team".to_string(),
+        ));
+    }
+
+    // Nothing is changed if that was the last Administrator.
+    crate::rbac::ensure_administrator(&transaction, organisation_id).await?;
+
+    crate::audit::Event::new(
+        AuditAction::ChangeRole,
+        AuditAccessType::Web,
+        format!("Role of {} changed to {}", set_role.user_id, set_role.role),
+    )
+    .by_user(current_user.user_id)
+    .target_user(set_role.user_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
+
+    transaction.commit().await?;
+
+    crate::layout::redirect_and_snackbar(
+        &ui_components::routes::team::index_route(organisation_id),
+        "Role Changed",
+    )
+}
@@ -11,19 +11,19 @@ pub async fn index(
     Extension(pool): Extension<Pool>,
 ) -> Result<Html<String>, CustomError> {
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
 
     let teams = queries::organisations::get_teams()
-        .bind(&transaction, &current_user.user_id)
+        .bind(&*transaction, &current_user.user_id)
         .all()
         .await?;
 
     let team = queries::organisations::organisation()
-        .bind(&transaction, &organisation_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     Ok(Html(ui_components::team_members::team_popup::team_popup(
         teams, team,
@@ -0,0 +1,62 @@
+use crate::audit::Source;
+use crate::authentication::Authentication;
+use crate::errors::CustomError;
+use crate::rbac::{Authorize, ManageTeam};
+use axum::{
+    extract::{Extension, Form, Path},
+    response::IntoResponse,
+};
+use db::queries;
+use db::types::public::{AuditAccessType, AuditAction};
+use db::Pool;
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate, Default, Debug)]
+pub struct Transfer {
+    pub user_id: i32,
+}
+
+pub async fn transfer(
+    Path(organisation_id): Path<i32>,
+    current_user: Authentication,
+    source: Source,
+    _: Authorize<ManageTeam>,
+    Extension(pool): Extension<Pool>,
+    Form(transfer): Form<Transfer>,
+) -> Result<impl IntoResponse, CustomError> {
+    // Create a transaction and setup RLS
+    let mut client = pool.get().await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    // Only the owner can hand the team over and only to another Administrator.
+    let transferred = queries::organisations::transfer_ownership()
+        .bind(&*transaction, &transfer.user_id, &organisation_id)
+        .opt()
+        .await?;
+
+    if transferred.is_none() {
+        return Err(CustomError::PermissionDenied(
+            "Only the owner can transfer a team and only to an Administrator".to_string(),
+        ));
+    }
+
+    crate::audit::Event::new(
+        AuditAction::TransferTeam,
+        AuditAccessType::Web,
+        format!("Team transferred to {}", transfer.user_id),
+    )
+    .by_user(current_user.user_id)
+    .target_user(transfer.user_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
+
+    transaction.commit().await?;
+
+    crate::layout::redirect_and_snackbar(
+        &ui_components::routes::team::index_route(organisation_id),
+        "Team Transferred",
+    )
+}
@@ -1,11 +1,13 @@
+use crate::audit::Source;
 use crate::authentication::Authentication;
 use crate::errors::CustomError;
+use crate::rbac::{self, Authorize};
 use axum::{
     extract::{Extension, Form, Path},
     response::IntoResponse,
 };
 use db::queries;
-use db::types::public::{AuditAccessType, AuditAction};
+use db::types::public::{AuditAccessType, AuditAction, VaultRole};
 use db::Pool;
 use serde::Deserialize;
 use validator::Validate;
@@ -19,47 +21,67 @@ pub struct DeleteVault {
 pub async fn delete(
     Path(organisation_id): Path<i32>,
     current_user: Authentication,
+    source: Source,
+    _: Authorize<rbac::DeleteVault>,
     Form(idor_delete_vault): Form<DeleteVault>,
     Extension(pool): Extension<Pool>,
 ) -> Result<impl IntoResponse, CustomError> {
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
+
+    // As well as the team permission, only the vault's Admins can delete it.
+    crate::rbac::require_vault_role(
+        &transaction,
+        current_user.user_id,
+        idor_delete_vault.vault_id,
+        VaultRole::Admin,
+    )
+    .await?;
 
     let team = queries::organisations::organisation()
-        .bind(&transaction, &organisation_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     let vault = queries::vaults::get()
         .bind(
-            &transaction,
+            &*transaction,
             &idor_delete_vault.vault_id,
             &current_user.user_id,
         )
-        .one()
-        .await?;
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    // The DeleteVault permission was checked against the team in the URL.
+    if vault.organisation_id != organisation_id {
+        return Err(CustomError::NotFound(
+            "Vault is not in this team".to_string(),
+        ));
+    }
 
     if vault.name == idor_delete_vault.name {
         queries::vaults::delete()
             .bind(
-                &transaction,
+                &*transaction,
                 &idor_delete_vault.vault_id,
                 &current_user.user_id,
             )
             .await?;
 
-        queries::audit::insert()
-            .bind(
-                &transaction,
-                &current_user.user_id,
-                &organisation_id,
-                &AuditAction::DeleteVault,
-                &AuditAccessType::Web,
-                &format!("{} vault deleted", vault.name).as_ref(),
-            )
-            .await?;
+        crate::audit::Event::new(
+            AuditAction::DeleteVault,
+            AuditAccessType::Web,
+            format!("{} vault deleted", vault.name),
+        )
+        .by_user(current_user.user_id)
+        .vault(idor_delete_vault.vault_id)
+        .source(&source)
+        .insert(&transaction, organisation_id)
+        .await?;
 
         transaction.commit().await?;
     } else {
@@ -15,16 +15,17 @@ pub async fn index(
 ) -> Result<Html<String>, CustomError> {
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
 
     let team = queries::organisations::organisation()
-        .bind(&transaction, &organisation_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     let vaults = queries::vaults::get_all()
-        .bind(&transaction, &current_user.user_id, &organisation_id)
+        .bind(&*transaction, &current_user.user_id, &organisation_id)
         .all()
         .await?;
 
@@ -32,12 +33,12 @@ pub async fn index(
 
     for vault in vaults {
         let user_count = queries::vaults::user_vault_count()
-            .bind(&transaction, &vault.id)
+            .bind(&*transaction, &vault.id)
             .one()
             .await?;
 
         let secret_count = queries::vaults::secrets_count()
-            .bind(&transaction, &vault.id)
+            .bind(&*transaction, &vault.id)
             .one()
             .await?;
 
@@ -49,6 +50,7 @@ pub async fn index(
             href: "".to_string(),
             created_at: vault.created_at,
             updated_at: vault.updated_at,
+            key_rotation_needed: vault.key_rotation_needed,
         });
     }
 
@@ -2,10 +2,13 @@ mod delete_vault;
 mod index;
 mod new_vault;
 
+use crate::errors::CustomError;
 use axum::{
     routing::{get, post},
     Router,
 };
+use db::queries;
+use db::Transaction;
 
 pub fn routes() -> Router {
     Router::new()
@@ -16,3 +19,28 @@ pub fn routes() -> Router {
             post(delete_vault::delete),
         )
 }
+
+// Secrets and members are encrypted by the browser with the vault key it
+// holds. Once the key has been rotated nobody can decrypt anything written with
+// the old one, so the write is turned down and the caller has to reload. The
+// vault stays locked until the transaction ends so a rotation can't start in
+// between.
+pub async fn ensure_key_version(
+    transaction: &Transaction<'_>,
+    vault_id: i32,
+    key_version: i32,
+) -> Result<(), CustomError> {
+    let current = queries::vaults::lock_key_version()
+        .bind(transaction, &vault_id)
+        .one()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
+
+    if current != key_version {
+        return Err(CustomError::Conflict(
+            "The vault key has been rotated, reload the page and try again".to_string(),
+        ));
+    }
+
+    Ok(())
+}
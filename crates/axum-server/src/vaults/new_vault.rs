@@ -1,5 +1,7 @@
+use crate::audit::Source;
 use crate::authentication::Authentication;
 use crate::errors::CustomError;
+use crate::rbac::{Authorize, CreateVault};
 use axum::{
     extract::{Extension, Form, Path},
     response::IntoResponse,
@@ -23,27 +25,30 @@ pub struct NewVault {
 pub async fn new(
     Path(organisation_id): Path<i32>,
     current_user: Authentication,
+    source: Source,
+    _: Authorize<CreateVault>,
     Form(new_vault): Form<NewVault>,
     Extension(pool): Extension<Pool>,
 ) -> Result<impl IntoResponse, CustomError> {
     // Create a transaction and setup RLS
     let mut client = pool.get().await?;
-    let transaction = client.transaction().await?;
-    super::super::rls::set_row_level_security_user(&transaction, &current_user).await?;
+    let transaction =
+        super::super::rls::RlsTransaction::for_user(&mut client, &current_user).await?;
 
     let team = queries::organisations::organisation()
-        .bind(&transaction, &organisation_id)
-        .one()
-        .await?;
+        .bind(&*transaction, &organisation_id)
+        .opt()
+        .await?
+        .ok_or_else(CustomError::not_found)?;
 
     let vault_id = queries::vaults::insert()
-        .bind(&transaction, &organisation_id, &new_vault.name.as_ref())
+        .bind(&*transaction, &organisation_id, &new_vault.name.as_ref())
         .one()
         .await?;
 
     queries::vaults::insert_user_vaults()
         .bind(
-            &transaction,
+            &*transaction,
             &current_user.user_id,
             &vault_id,
             &new_vault.public_key.as_ref(),
@@ -51,26 +56,20 @@ pub async fn new(
         )
         .await?;
 
-    queries::audit::insert()
-        .bind(
-            &transaction,
-            &current_user.user_id,
-            &organisation_id,
-            &AuditAction::CreateVault,
-            &AuditAccessType::Web,
-            &format!("{} vault created", &new_vault.name).as_ref(),
-        )
-        .await?;
+    crate::audit::Event::new(
+        AuditAction::CreateVault,
+        AuditAccessType::Web,
+        format!("{} vault created", &new_vault.name),
+    )
+    .by_user(current_user.user_id)
+    .vault(vault_id)
+    .source(&source)
+    .insert(&transaction, organisation_id)
+    .await?;
 
-    let envs = queries::environments::setup_environments()
-        .bind(&transaction, &vault_id)
-        .all()
+    queries::environments::setup_environments()
+        .bind(&*transaction, &vault_id)
         .await?;
-    for env in envs {
-        queries::environments::connect_environment_to_user()
-            .bind(&transaction, &current_user.user_id, &env.id)
-            .await?;
-    }
 
     transaction.commit().await?;
 
@@ -44,6 +44,10 @@ async fn audit_filter(driver: &WebDriver, email: &str) -> WebDriverResult<()> {
 
     assert_eq!(table_cell.text().await?, email);
 
+    // The filter is kept in the URL so it can be shared.
+    let url = driver.current_url().await?.to_string();
+    assert!(url.contains("user="));
+
     Ok(())
 }
 
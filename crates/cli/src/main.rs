@@ -8,17 +8,20 @@ mod config;
 use clap::{Parser, Subcommand};
 use cli_table::WithTitle;
 use p256::ecdh::SharedSecret;
+use p256::ecdsa::{signature::Signer, Signature, SigningKey};
 use p256::{elliptic_curve::ecdh, pkcs8::DecodePublicKey, PublicKey};
 use std::collections::HashMap;
 use std::env;
 use std::ffi::OsString;
-use std::process::{Command, Stdio};
+use std::process::{Child, Command, Stdio};
 
 use aes_gcm::aead::{generic_array::GenericArray, Aead, Payload};
 use aes_gcm::Aes256Gcm;
 use aes_gcm::KeyInit;
 use cli_table::{print_stdout, Table};
 use dotenv::dotenv;
+use grpc_api::vault::vault_client::VaultClient;
+use tonic::transport::{ClientTlsConfig, Endpoint};
 
 /// A fictional versioning CLI
 #[derive(Parser)]
@@ -46,6 +49,12 @@ pub enum Commands {
     Info,
     Secrets,
     Env,
+    /// Keeps running and prints the secrets whenever they change. If a program
+    /// is given after -- it's restarted with the new secrets instead.
+    Watch {
+        #[clap(last = true)]
+        command: Vec<OsString>,
+    },
 }
 
 #[tokio::main]
@@ -58,28 +67,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // cargo run -- --api-host-url=http://envoy:7100 run printenv
     // cargo run -- info
     // cargo run -- --api-host-url=http://envoy:7100 secrets
+    // cargo run -- --api-host-url=http://envoy:7100 watch -- printenv
     match &args.command {
         Commands::Run(args) => {
             let env_vars_to_inject = get_secrets(&config).await?;
 
-            let filtered_env: HashMap<String, String> = env::vars()
-                .filter(|&(ref k, _)| k != "ECDH_PRIVATE_KEY")
-                .collect();
-
-            let filtered_env: HashMap<String, String> =
-                filtered_env.into_iter().chain(env_vars_to_inject).collect();
-
-            let cmd_args = insert_secrets(&args[1..], &filtered_env).await;
-
-            println!("Calling out to {:?} with {:?}", &args[0], &cmd_args);
-
-            let mut child = Command::new(&args[0])
-                .args(&cmd_args)
-                .stdin(Stdio::null())
-                .stdout(Stdio::inherit())
-                .envs(&filtered_env)
-                .spawn()
-                .expect("Failed to run command");
+            let mut child = spawn_with_secrets(&args[0], &args[1..], env_vars_to_inject).await?;
 
             child.wait().expect("failed to wait on child");
         }
@@ -100,6 +93,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 println!("{}={}", name, value);
             }
         }
+        Commands::Watch { command } => {
+            watch(&args.api_host_url, &config, command).await?;
+        }
     }
 
     Ok(())
@@ -136,10 +132,22 @@ async fn insert_secrets(cmd_args: &[OsString], secrets: &HashMap<String, String>
 async fn get_secrets(
     config: &config::Config,
 ) -> Result<HashMap<String, String>, Box<dyn std::error::Error>> {
+    // Prove we hold the private key by signing a challenge from the server.
+    let challenge = config
+        .client
+        .get_service_account_challenge(vault::GetServiceAccountChallengeRequest {
+            ecdh_public_key: config.public_key_der_base64.clone(),
+        })
+        .await?;
+
+    let signature = sign_challenge(config, &challenge.nonce);
+
     let response = config
         .client
         .get_service_account(vault::GetServiceAccountRequest {
             ecdh_public_key: config.public_key_der_base64.clone(),
+            nonce: challenge.nonce,
+            signature,
         })
         .await?;
 
@@ -147,20 +155,139 @@ async fn get_secrets(
 
     let mut env_vars_to_inject: HashMap<String, String> = Default::default();
     for secret in response.secrets {
-        let key_der = base64::decode(secret.ecdh_public_key).unwrap();
+        let (name, value) = decrypt_name_and_value(
+            config,
+            &aad,
+            secret.ecdh_public_key,
+            secret.encrypted_name,
+            secret.encrypted_secret_value,
+        )?;
+        env_vars_to_inject.insert(name, value);
+    }
+    Ok(env_vars_to_inject)
+}
+
+// grpc-web doesn't support streaming, so watching uses native gRPC.
+async fn watch(
+    api_host_url: &str,
+    config: &config::Config,
+    command: &[OsString],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut endpoint = Endpoint::from_shared(api_host_url.to_string())?;
+    if api_host_url.starts_with("https") {
+        endpoint = endpoint.tls_config(ClientTlsConfig::new())?;
+    }
+    let mut client = VaultClient::new(endpoint.connect().await?);
 
-        let public_key = PublicKey::from_public_key_der(&key_der).unwrap();
+    let challenge = client
+        .get_service_account_challenge(grpc_api::vault::GetServiceAccountChallengeRequest {
+            ecdh_public_key: config.public_key_der_base64.clone(),
+        })
+        .await?
+        .into_inner();
+
+    let signature = sign_challenge(config, &challenge.nonce);
+
+    let mut snapshots = client
+        .watch_service_account(grpc_api::vault::GetServiceAccountRequest {
+            ecdh_public_key: config.public_key_der_base64.clone(),
+            nonce: challenge.nonce,
+            signature,
+        })
+        .await?
+        .into_inner();
+
+    let mut child: Option<Child> = None;
+
+    // The first snapshot arrives straight away, then one per change.
+    while let Some(snapshot) = snapshots.message().await? {
+        let aad = transform_u32_to_array_of_u8(snapshot.service_account_id);
+
+        let mut secrets: HashMap<String, String> = Default::default();
+        for secret in snapshot.secrets {
+            let (name, value) = decrypt_name_and_value(
+                config,
+                &aad,
+                secret.ecdh_public_key,
+                secret.encrypted_name,
+                secret.encrypted_secret_value,
+            )?;
+            secrets.insert(name, value);
+        }
 
-        let shared_secret = ecdh::diffie_hellman(
-            config.secret_key.to_nonzero_scalar(),
-            public_key.as_affine(),
-        );
+        if let Some((program, program_args)) = command.split_first() {
+            if let Some(mut running) = child.take() {
+                // It may have already exited, in which case there's nothing to kill.
+                let _ = running.kill();
+                running.wait()?;
+            }
+            child = Some(spawn_with_secrets(program, program_args, secrets).await?);
+        } else {
+            for (name, value) in secrets.into_iter() {
+                println!("{}={}", name, value);
+            }
+            println!();
+        }
+    }
 
-        let plaintext_name = decrypt_secret(secret.encrypted_name, &aad, &shared_secret)?;
-        let plaintext_value = decrypt_secret(secret.encrypted_secret_value, &aad, &shared_secret)?;
-        env_vars_to_inject.insert(plaintext_name, plaintext_value);
+    if let Some(mut running) = child.take() {
+        let _ = running.kill();
+        running.wait()?;
     }
-    Ok(env_vars_to_inject)
+
+    Ok(())
+}
+
+async fn spawn_with_secrets(
+    program: &OsString,
+    args: &[OsString],
+    secrets: HashMap<String, String>,
+) -> Result<Child, Box<dyn std::error::Error>> {
+    let filtered_env: HashMap<String, String> = env::vars()
+        .filter(|&(ref k, _)| k != "ECDH_PRIVATE_KEY")
+        .collect();
+
+    let filtered_env: HashMap<String, String> = filtered_env.into_iter().chain(secrets).collect();
+
+    let cmd_args = insert_secrets(args, &filtered_env).await;
+
+    println!("Calling out to {:?} with {:?}", program, &cmd_args);
+
+    let child = Command::new(program)
+        .args(&cmd_args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::inherit())
+        .envs(&filtered_env)
+        .spawn()?;
+
+    Ok(child)
+}
+
+fn sign_challenge(config: &config::Config, nonce: &str) -> String {
+    let signing_key = SigningKey::from(&config.secret_key);
+    let signature: Signature = signing_key.sign(nonce.as_bytes());
+    base64::encode(signature.to_der())
+}
+
+fn decrypt_name_and_value(
+    config: &config::Config,
+    aad: &[u8; 4],
+    ecdh_public_key: String,
+    encrypted_name: String,
+    encrypted_secret_value: String,
+) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let key_der = base64::decode(ecdh_public_key).unwrap();
+
+    let public_key = PublicKey::from_public_key_der(&key_der).unwrap();
+
+    let shared_secret = ecdh::diffie_hellman(
+        config.secret_key.to_nonzero_scalar(),
+        public_key.as_affine(),
+    );
+
+    let plaintext_name = decrypt_secret(encrypted_name, aad, &shared_secret)?;
+    let plaintext_value = decrypt_secret(encrypted_secret_value, aad, &shared_secret)?;
+    Ok((plaintext_name, plaintext_value))
 }
 
 fn decrypt_secret(
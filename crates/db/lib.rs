@@ -1,40 +1,35 @@
+mod notifications;
+
 use std::str::FromStr;
 
 pub use cornucopia_async::Params;
-pub use deadpool_postgres::{Pool, PoolError, Transaction};
+pub use deadpool_postgres::{Object, Pool, PoolError, Transaction};
+pub use notifications::{listen, Notifications};
+pub use tokio_postgres::error::SqlState;
 pub use tokio_postgres::Error as TokioPostgresError;
 
-pub use queries::audit::Audit;
+pub use queries::api_keys::ApiKey;
+pub use queries::audit::{Audit, AuditExport};
 pub use queries::environments::{Environment, EnvironmentsAndVault};
 pub use queries::invitations::Invitation;
 pub use queries::organisations::GetTeams as Team;
 pub use queries::organisations::GetUsers as Member;
-pub use queries::organisations::Organisation;
-pub use queries::secrets::Secret;
+pub use queries::organisations::{AuditRetentionSettings, Organisation};
+pub use queries::rbac::TeamRole;
+pub use queries::secrets::{Ciphertext, Secret, SecretVersion};
 pub use queries::service_accounts::ServiceAccount;
-pub use queries::user_vaults::{NonMember, UserVault, VaultMember};
+pub use queries::user_vaults::{MemberKey, NonMember, UserVault, VaultMember};
 pub use queries::users::User;
 pub use queries::vaults::Vault;
-pub use types::public::{AuditAccessType, AuditAction, Role};
+pub use types::public::{
+    AuditAccessType, AuditAction, AuditRetention, Permission, Role, VaultRole,
+};
 
 pub fn create_pool(database_url: &str) -> deadpool_postgres::Pool {
     let config = tokio_postgres::Config::from_str(database_url).unwrap();
 
     let manager = if database_url.contains("sslmode=require") {
-        let mut root_store = rustls::RootCertStore::empty();
-        root_store.add_server_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.0.iter().map(|ta| {
-            rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(
-                ta.subject,
-                ta.spki,
-                ta.name_constraints,
-            )
-        }));
-
-        let tls_config = rustls::ClientConfig::builder()
-            .with_safe_defaults()
-            .with_root_certificates(root_store)
-            .with_no_client_auth();
-        let tls = tokio_postgres_rustls::MakeRustlsConnect::new(tls_config);
+        let tls = tokio_postgres_rustls::MakeRustlsConnect::new(tls_config());
         deadpool_postgres::Manager::new(config, tls)
     } else {
         deadpool_postgres::Manager::new(config, tokio_postgres::NoTls)
@@ -43,4 +38,20 @@ pub fn create_pool(database_url: &str) -> deadpool_postgres::Pool {
     deadpool_postgres::Pool::builder(manager).build().unwrap()
 }
 
+fn tls_config() -> rustls::ClientConfig {
+    let mut root_store = rustls::RootCertStore::empty();
+    root_store.add_server_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.0.iter().map(|ta| {
+        rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(
+            ta.subject,
+            ta.spki,
+            ta.name_constraints,
+        )
+    }));
+
+    rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(root_store)
+        .with_no_client_auth()
+}
+
 include!(concat!(env!("OUT_DIR"), "/cornucopia.rs"));
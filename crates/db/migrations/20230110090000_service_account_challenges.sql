@@ -0,0 +1,45 @@
+-- migrate:up
+CREATE TABLE service_account_challenges (
+    id SERIAL PRIMARY KEY,
+    service_account_id INT NOT NULL,
+    nonce VARCHAR NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_service_account
+        FOREIGN KEY(service_account_id)
+        REFERENCES service_accounts(id)
+        ON DELETE CASCADE
+);
+
+COMMENT ON TABLE service_account_challenges IS 'Single use challenges the CLI signs to prove it holds a service accounts private key.';
+COMMENT ON COLUMN service_account_challenges.service_account_id IS 'The service account the challenge was issued to.';
+COMMENT ON COLUMN service_account_challenges.nonce IS 'A random base64 encoded value that the CLI signs with the service account key.';
+COMMENT ON COLUMN service_account_challenges.created_at IS 'Challenges expire a short time after they are issued.';
+
+-- Give access to the application user
+GRANT SELECT, INSERT, DELETE ON service_account_challenges TO application;
+GRANT USAGE, SELECT ON service_account_challenges_id_seq TO application;
+
+-- Give access to the readonly user
+GRANT SELECT ON service_account_challenges TO readonly;
+GRANT SELECT ON service_account_challenges_id_seq TO readonly;
+
+ALTER TABLE service_account_challenges ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY readonly_policy ON service_account_challenges FOR SELECT TO readonly USING (true);
+
+CREATE POLICY multi_tenancy_policy ON service_account_challenges FOR ALL TO application
+USING (
+    service_account_id IN (
+        SELECT id
+        FROM service_accounts
+        WHERE ecdh_public_key = current_ecdh_public_key())
+);
+
+COMMENT ON POLICY multi_tenancy_policy ON service_account_challenges IS
+    'Challenges are only visible to the service account they were issued to.';
+
+-- migrate:down
+DROP POLICY multi_tenancy_policy ON service_account_challenges;
+DROP POLICY readonly_policy ON service_account_challenges;
+DROP TABLE service_account_challenges;
@@ -0,0 +1,23 @@
+-- migrate:up
+
+-- Service accounts access the system without a user, so an audit row
+-- is attributed to either a user or a service account.
+ALTER TABLE audit_trail ALTER COLUMN user_id DROP NOT NULL;
+ALTER TABLE audit_trail ADD COLUMN service_account_id INT;
+ALTER TABLE audit_trail ADD COLUMN ip_address VARCHAR;
+ALTER TABLE audit_trail ADD COLUMN user_agent VARCHAR;
+
+ALTER TABLE audit_trail ADD CONSTRAINT audit_trail_actor
+    CHECK (user_id IS NOT NULL OR service_account_id IS NOT NULL);
+
+COMMENT ON COLUMN audit_trail.user_id IS 'The user that accessed the system, NULL if a service account did.';
+COMMENT ON COLUMN audit_trail.service_account_id IS 'The service account that accessed the system. No foreign key so the audit outlives the account.';
+COMMENT ON COLUMN audit_trail.ip_address IS 'The source IP address of the request if we know it.';
+COMMENT ON COLUMN audit_trail.user_agent IS 'The user agent of the request if we know it.';
+
+-- migrate:down
+ALTER TABLE audit_trail DROP CONSTRAINT audit_trail_actor;
+ALTER TABLE audit_trail DROP COLUMN user_agent;
+ALTER TABLE audit_trail DROP COLUMN ip_address;
+ALTER TABLE audit_trail DROP COLUMN service_account_id;
+ALTER TABLE audit_trail ALTER COLUMN user_id SET NOT NULL;
@@ -0,0 +1,76 @@
+-- migrate:up
+CREATE TABLE api_keys (
+    id SERIAL PRIMARY KEY,
+    user_id INT NOT NULL,
+    organisation_id INT NOT NULL,
+    name VARCHAR NOT NULL,
+    api_key_hash VARCHAR NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_user
+        FOREIGN KEY(user_id)
+        REFERENCES users(id)
+        ON DELETE CASCADE,
+
+    CONSTRAINT fk_organisation
+        FOREIGN KEY(organisation_id)
+        REFERENCES organisations(id)
+        ON DELETE CASCADE
+);
+
+COMMENT ON TABLE api_keys IS 'Keys that let automation call the API on behalf of a user.';
+COMMENT ON COLUMN api_keys.user_id IS 'The user the API acts as when this key is used.';
+COMMENT ON COLUMN api_keys.organisation_id IS 'The team the key was created in.';
+COMMENT ON COLUMN api_keys.name IS 'A name so the user can remember what the key is for.';
+COMMENT ON COLUMN api_keys.api_key_hash IS 'A SHA256 hash of the key. We never store the key itself.';
+COMMENT ON COLUMN api_keys.expires_at IS 'The key stops working after this time. NULL if it never expires.';
+
+-- Give access to the application user
+GRANT SELECT, INSERT, DELETE ON api_keys TO application;
+GRANT USAGE, SELECT ON api_keys_id_seq TO application;
+
+-- Give access to the readonly user
+GRANT SELECT ON api_keys TO readonly;
+GRANT SELECT ON api_keys_id_seq TO readonly;
+
+ALTER TABLE api_keys ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY readonly_policy ON api_keys FOR SELECT TO readonly USING (true);
+
+CREATE POLICY multi_tenancy_policy ON api_keys FOR ALL TO application
+USING (
+    user_id = current_app_user()
+);
+
+COMMENT ON POLICY multi_tenancy_policy ON api_keys IS
+    'A user can only see and revoke their own keys.';
+
+-- We don't know the user until we have looked up the key, so this
+-- bypasses RLS but only ever returns the user for a valid key. A key only
+-- works in the team it was created in.
+CREATE FUNCTION get_user_for_api_key(hash TEXT, org INTEGER) RETURNS INTEGER AS
+$$
+    SELECT
+        user_id
+    FROM
+        api_keys
+    WHERE
+        api_key_hash = hash
+    AND
+        organisation_id = org
+    AND
+        (expires_at IS NULL OR expires_at > NOW())
+$$ LANGUAGE SQL SECURITY DEFINER SET search_path = public;
+COMMENT ON FUNCTION get_user_for_api_key IS
+    'The user for an API key hash in a team, NULL if the key is unknown, expired or for another team.';
+
+ALTER TYPE audit_action ADD VALUE 'CreateApiKey';
+ALTER TYPE audit_action ADD VALUE 'RevokeApiKey';
+
+-- migrate:down
+-- Postgres can't remove values from an enum, so CreateApiKey and RevokeApiKey stay.
+DROP FUNCTION get_user_for_api_key;
+DROP POLICY multi_tenancy_policy ON api_keys;
+DROP POLICY readonly_policy ON api_keys;
+DROP TABLE api_keys;
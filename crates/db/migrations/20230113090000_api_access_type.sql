@@ -0,0 +1,7 @@
+-- migrate:up
+
+-- Calls to the management API, usually from platform tooling with an API key.
+ALTER TYPE audit_access_type ADD VALUE 'API';
+
+-- migrate:down
+-- Postgres can't remove values from an enum, so API stays.
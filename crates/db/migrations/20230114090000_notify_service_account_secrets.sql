@@ -0,0 +1,26 @@
+-- migrate:up
+
+-- Let anyone listening know which service account has new secrets. Postgres
+-- folds identical notifications within a transaction, so a bulk insert
+-- only notifies once per service account.
+CREATE FUNCTION notify_service_account_secrets() RETURNS TRIGGER AS
+$$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM pg_notify('service_account_secrets', OLD.service_account_id::text);
+    ELSE
+        PERFORM pg_notify('service_account_secrets', NEW.service_account_id::text);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+COMMENT ON FUNCTION notify_service_account_secrets IS
+    'Sends the service account id on the service_account_secrets channel.';
+
+CREATE TRIGGER notify_service_account_secrets
+    AFTER INSERT OR UPDATE OR DELETE ON service_account_secrets
+    FOR EACH ROW EXECUTE FUNCTION notify_service_account_secrets();
+
+-- migrate:down
+DROP TRIGGER notify_service_account_secrets ON service_account_secrets;
+DROP FUNCTION notify_service_account_secrets;
@@ -0,0 +1,88 @@
+-- migrate:up
+CREATE TABLE secret_versions (
+    id SERIAL PRIMARY KEY,
+    secret_id INT NOT NULL,
+    version INT NOT NULL,
+    name VARCHAR NOT NULL,
+    secret VARCHAR NOT NULL,
+    created_by_user_id INT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    UNIQUE (secret_id, version),
+
+    CONSTRAINT fk_secret
+        FOREIGN KEY(secret_id)
+        REFERENCES secrets(id)
+        ON DELETE CASCADE,
+
+    CONSTRAINT fk_user
+        FOREIGN KEY(created_by_user_id)
+        REFERENCES users(id)
+        ON DELETE SET NULL
+);
+
+COMMENT ON TABLE secret_versions IS 'Every value a secret has had. The newest version is the one in the secrets table.';
+COMMENT ON COLUMN secret_versions.version IS 'Starts at 1 and goes up by one for each change to the secret.';
+COMMENT ON COLUMN secret_versions.name IS 'The name of the secret encrypted with the vault key';
+COMMENT ON COLUMN secret_versions.secret IS 'The value of the secret encrypted with the vault key';
+COMMENT ON COLUMN secret_versions.created_by_user_id IS 'The user that made the change, NULL if we do not know.';
+
+-- Give access to the application user. Versions are history so they can't be changed.
+GRANT SELECT, INSERT ON secret_versions TO application;
+GRANT USAGE, SELECT ON secret_versions_id_seq TO application;
+
+-- Give access to the readonly user
+GRANT SELECT ON secret_versions TO readonly;
+GRANT SELECT ON secret_versions_id_seq TO readonly;
+
+ALTER TABLE secret_versions ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY readonly_policy ON secret_versions FOR SELECT TO readonly USING (true);
+
+CREATE POLICY multi_tenancy_policy ON secret_versions FOR ALL TO application
+USING (
+    secret_id IN (SELECT id FROM secrets)
+);
+
+COMMENT ON POLICY multi_tenancy_policy ON secret_versions IS
+    'A user can see the history of any secret they can see.';
+
+-- Existing secrets start their history at version 1.
+INSERT INTO secret_versions (secret_id, version, name, secret, created_at)
+SELECT id, 1, name, secret, updated_at FROM secrets;
+
+-- Record a version whenever a secret is created or its ciphertext changes,
+-- so nothing that writes to secrets can forget to.
+CREATE FUNCTION record_secret_version() RETURNS TRIGGER AS
+$$
+BEGIN
+    INSERT INTO secret_versions (secret_id, version, name, secret, created_by_user_id)
+    SELECT
+        NEW.id,
+        COALESCE(MAX(version), 0) + 1,
+        NEW.name,
+        NEW.secret,
+        -- Not set when the database is changed outside of the application.
+        NULLIF(current_setting('row_level_security.user_id', true), '')::integer
+    FROM
+        secret_versions
+    WHERE
+        secret_id = NEW.id;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER record_secret_version
+    AFTER INSERT OR UPDATE OF name, secret ON secrets
+    FOR EACH ROW
+    EXECUTE FUNCTION record_secret_version();
+
+ALTER TYPE audit_action ADD VALUE 'RollbackSecret';
+
+-- migrate:down
+-- Postgres can't remove values from an enum, so RollbackSecret stays.
+DROP TRIGGER record_secret_version ON secrets;
+DROP FUNCTION record_secret_version;
+DROP POLICY multi_tenancy_policy ON secret_versions;
+DROP POLICY readonly_policy ON secret_versions;
+DROP TABLE secret_versions;
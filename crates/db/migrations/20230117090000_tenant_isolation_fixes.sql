@@ -0,0 +1,72 @@
+-- migrate:up
+
+-- The sub select used vault_id from the outer row as vaults has no such
+-- column, so any visible vault let a user connect themselves to any vault.
+DROP POLICY multi_tenancy_policy ON users_vaults;
+CREATE POLICY multi_tenancy_policy ON users_vaults FOR ALL TO application
+USING (
+    vault_id IN (SELECT id FROM vaults)
+    AND
+    user_id IN (SELECT get_users_for_app_user())
+);
+
+-- Same problem, service_account_id came from the outer row so any service
+-- account in our orgs opened up the secrets of every service account.
+DROP POLICY multi_tenancy_policy ON service_account_secrets;
+CREATE POLICY multi_tenancy_policy ON service_account_secrets FOR ALL TO application
+USING (
+    service_account_id IN (
+        SELECT id
+        FROM service_accounts
+        WHERE organisation_id IN (SELECT get_orgs_for_app_user()))
+);
+
+-- users_environments was never locked down.
+ALTER TABLE users_environments ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY readonly_policy ON users_environments FOR SELECT TO readonly USING (true);
+
+CREATE POLICY multi_tenancy_policy ON users_environments FOR ALL TO application
+USING (
+    environment_id IN (SELECT id FROM environments)
+    AND
+    user_id IN (SELECT get_users_for_app_user())
+);
+
+COMMENT ON POLICY multi_tenancy_policy ON users_environments IS
+    'A user can see who has access to the environments of their vaults.';
+
+-- Without a check a user could create a key against a team they are not in.
+ALTER POLICY multi_tenancy_policy ON api_keys
+WITH CHECK (
+    user_id = current_app_user()
+    AND
+    organisation_id IN (SELECT get_orgs_for_app_user())
+);
+
+-- migrate:down
+ALTER POLICY multi_tenancy_policy ON api_keys
+WITH CHECK (
+    user_id = current_app_user()
+);
+
+DROP POLICY multi_tenancy_policy ON users_environments;
+DROP POLICY readonly_policy ON users_environments;
+ALTER TABLE users_environments DISABLE ROW LEVEL SECURITY;
+
+DROP POLICY multi_tenancy_policy ON service_account_secrets;
+CREATE POLICY multi_tenancy_policy ON service_account_secrets FOR ALL TO application
+USING (
+    service_account_id IN (
+        SELECT service_account_id
+        FROM service_accounts
+        WHERE organisation_id IN (SELECT get_orgs_for_app_user()))
+);
+
+DROP POLICY multi_tenancy_policy ON users_vaults;
+CREATE POLICY multi_tenancy_policy ON users_vaults FOR ALL TO application
+USING (
+    vault_id IN (SELECT vault_id FROM vaults)
+    AND
+    user_id IN (SELECT get_users_for_app_user())
+);
@@ -0,0 +1,85 @@
+-- migrate:up
+
+-- The environments policy can't look at users_environments directly as
+-- the users_environments policy looks at environments.
+CREATE FUNCTION get_environments_for_app_user() RETURNS setof integer AS
+$$
+    SELECT
+        environment_id
+    FROM
+        users_environments
+    WHERE
+        user_id = current_app_user()
+$$ LANGUAGE SQL SECURITY DEFINER;
+COMMENT ON FUNCTION get_environments_for_app_user IS
+    'All the environments the user has been given access to.';
+
+DROP POLICY multi_tenancy_policy ON environments;
+CREATE POLICY multi_tenancy_policy ON environments FOR ALL TO application
+USING (
+    vault_id IN (SELECT vault_id FROM users_vaults
+    WHERE user_id = current_app_user())
+    AND
+    id IN (SELECT get_environments_for_app_user())
+)
+WITH CHECK (
+    vault_id IN (SELECT vault_id FROM users_vaults
+    WHERE user_id = current_app_user())
+);
+
+COMMENT ON POLICY multi_tenancy_policy ON environments IS
+    'A user can see the environments of their vaults they have been given access to.';
+
+DROP POLICY multi_tenancy_policy ON secrets;
+CREATE POLICY multi_tenancy_policy ON secrets FOR ALL TO application
+USING (
+    vault_id IN (SELECT vault_id FROM users_vaults)
+    AND
+    environment_id IN (SELECT id FROM environments)
+);
+
+COMMENT ON POLICY multi_tenancy_policy ON secrets IS
+    'A user can only see secrets from the environments they can see.';
+
+-- Whoever creates an environment gets access to it, otherwise it would
+-- disappear as soon as it was created.
+CREATE FUNCTION grant_environment_to_creator() RETURNS TRIGGER AS
+$$
+DECLARE
+    -- Not set when the database is changed outside of the application.
+    creator integer := NULLIF(current_setting('row_level_security.user_id', true), '')::integer;
+BEGIN
+    IF creator IS NOT NULL THEN
+        INSERT INTO users_environments (environment_id, user_id) VALUES (NEW.id, creator);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql SECURITY DEFINER;
+
+CREATE TRIGGER grant_environment_to_creator
+    AFTER INSERT ON environments
+    FOR EACH ROW
+    EXECUTE FUNCTION grant_environment_to_creator();
+
+-- migrate:down
+DROP TRIGGER grant_environment_to_creator ON environments;
+DROP FUNCTION grant_environment_to_creator;
+
+DROP POLICY multi_tenancy_policy ON secrets;
+CREATE POLICY multi_tenancy_policy ON secrets FOR ALL TO application
+USING (
+    vault_id IN (SELECT vault_id FROM users_vaults)
+);
+
+DROP POLICY multi_tenancy_policy ON environments;
+CREATE POLICY multi_tenancy_policy ON environments FOR ALL TO application
+USING (
+    vault_id IN (SELECT vault_id FROM users_vaults
+    WHERE user_id = current_app_user())
+)
+WITH CHECK (
+    vault_id IN (SELECT vault_id FROM users_vaults
+    WHERE user_id = current_app_user())
+);
+
+DROP FUNCTION get_environments_for_app_user;
@@ -0,0 +1,26 @@
+-- migrate:up
+
+-- Environments can now be deleted, so anything that points at one
+-- needs to go with it.
+DELETE FROM secrets WHERE environment_id NOT IN (SELECT id FROM environments);
+ALTER TABLE secrets
+    ADD CONSTRAINT fk_environment
+        FOREIGN KEY (environment_id)
+        REFERENCES environments(id)
+        ON DELETE CASCADE;
+
+UPDATE service_accounts SET environment_id = NULL
+WHERE environment_id NOT IN (SELECT id FROM environments);
+ALTER TABLE service_accounts
+    ADD CONSTRAINT fk_environment
+        FOREIGN KEY (environment_id)
+        REFERENCES environments(id)
+        ON DELETE SET NULL;
+
+ALTER TABLE environments
+    ADD CONSTRAINT environments_vault_id_name_key UNIQUE (vault_id, name);
+
+-- migrate:down
+ALTER TABLE environments DROP CONSTRAINT environments_vault_id_name_key;
+ALTER TABLE service_accounts DROP CONSTRAINT fk_environment;
+ALTER TABLE secrets DROP CONSTRAINT fk_environment;
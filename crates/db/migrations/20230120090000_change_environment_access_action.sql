@@ -0,0 +1,5 @@
+-- migrate:up
+ALTER TYPE audit_action ADD VALUE 'ChangeEnvironmentAccess';
+
+-- migrate:down
+-- Postgres can't remove values from an enum, so ChangeEnvironmentAccess stays.
@@ -0,0 +1,164 @@
+-- migrate:up
+
+-- Audit entries outlive the team, otherwise deleting a team would need
+-- to delete from audit_trail which we don't allow below.
+ALTER TABLE audit_trail DROP CONSTRAINT fk_organisation;
+
+ALTER TABLE audit_trail ADD COLUMN sequence INTEGER;
+ALTER TABLE audit_trail ADD COLUMN previous_hash VARCHAR;
+ALTER TABLE audit_trail ADD COLUMN hash VARCHAR;
+
+COMMENT ON COLUMN audit_trail.sequence IS 'Starts at 1 for each organisation and goes up by one for each entry, so gaps show up.';
+COMMENT ON COLUMN audit_trail.previous_hash IS 'The hash of the entry before this one in the organisation, NULL for the first.';
+COMMENT ON COLUMN audit_trail.hash IS 'A SHA256 of the entry including previous_hash, so changing any entry breaks the chain.';
+
+-- NULL columns are left out so adding columns later doesn't change the
+-- hash of existing entries.
+CREATE FUNCTION audit_trail_hash(entry audit_trail) RETURNS VARCHAR AS
+$$
+    SELECT encode(sha256(convert_to(
+        jsonb_strip_nulls(jsonb_build_object(
+            'organisation_id', entry.organisation_id,
+            'sequence', entry.sequence,
+            'user_id', entry.user_id,
+            'service_account_id', entry.service_account_id,
+            'access_type', entry.access_type,
+            'action', entry.action,
+            'description', entry.description,
+            'ip_address', entry.ip_address,
+            'user_agent', entry.user_agent,
+            -- Microseconds since the epoch, so the time zone doesn't matter.
+            'created_at', (extract(epoch FROM entry.created_at) * 1000000)::bigint,
+            'previous_hash', entry.previous_hash
+        ))::text, 'UTF8')), 'hex')
+$$ LANGUAGE SQL IMMUTABLE;
+COMMENT ON FUNCTION audit_trail_hash IS
+    'The hash of an audit entry, used when it is inserted and when it is verified.';
+
+-- Chain up the entries we already have in the order they were made.
+DO $$
+DECLARE
+    entry audit_trail;
+    previous audit_trail;
+BEGIN
+    FOR entry IN SELECT * FROM audit_trail ORDER BY organisation_id, id LOOP
+        IF previous IS NULL OR previous.organisation_id <> entry.organisation_id THEN
+            entry.sequence := 1;
+            entry.previous_hash := NULL;
+        ELSE
+            entry.sequence := previous.sequence + 1;
+            entry.previous_hash := previous.hash;
+        END IF;
+        entry.hash := audit_trail_hash(entry);
+        UPDATE audit_trail
+        SET sequence = entry.sequence, previous_hash = entry.previous_hash, hash = entry.hash
+        WHERE id = entry.id;
+        previous := entry;
+    END LOOP;
+END;
+$$;
+
+ALTER TABLE audit_trail ALTER COLUMN sequence SET NOT NULL;
+ALTER TABLE audit_trail ALTER COLUMN hash SET NOT NULL;
+ALTER TABLE audit_trail ADD CONSTRAINT audit_trail_organisation_id_sequence_key
+    UNIQUE (organisation_id, sequence);
+
+-- Security definer as the chain has to see every entry for the
+-- organisation whoever is logging.
+CREATE FUNCTION chain_audit_trail() RETURNS TRIGGER AS
+$$
+DECLARE
+    previous audit_trail;
+BEGIN
+    -- One entry at a time per organisation, so two entries can't claim
+    -- the same place in the chain. Released when the transaction ends.
+    PERFORM pg_advisory_xact_lock(hashtext('audit_trail'), NEW.organisation_id);
+
+    SELECT * INTO previous
+    FROM audit_trail
+    WHERE organisation_id = NEW.organisation_id
+    ORDER BY sequence DESC
+    LIMIT 1;
+
+    NEW.sequence := COALESCE(previous.sequence, 0) + 1;
+    NEW.previous_hash := previous.hash;
+    NEW.hash := audit_trail_hash(NEW);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql SECURITY DEFINER;
+
+CREATE TRIGGER chain_audit_trail
+    BEFORE INSERT ON audit_trail
+    FOR EACH ROW
+    EXECUTE FUNCTION chain_audit_trail();
+
+-- Grants only stop the application, triggers also stop everyone else
+-- including the owner.
+CREATE FUNCTION forbid_audit_trail_changes() RETURNS TRIGGER AS
+$$
+BEGIN
+    RAISE EXCEPTION 'The audit trail can only be added to'
+        USING ERRCODE = 'insufficient_privilege';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER forbid_audit_trail_changes
+    BEFORE UPDATE OR DELETE ON audit_trail
+    FOR EACH ROW
+    EXECUTE FUNCTION forbid_audit_trail_changes();
+
+CREATE TRIGGER forbid_audit_trail_truncate
+    BEFORE TRUNCATE ON audit_trail
+    FOR EACH STATEMENT
+    EXECUTE FUNCTION forbid_audit_trail_changes();
+
+-- Walks the chain for an organisation and returns anything that doesn't
+-- add up. Runs with the callers permissions, so they need to be able to
+-- see the whole organisation.
+CREATE FUNCTION verify_audit_trail(org INTEGER)
+RETURNS TABLE (audit_sequence INTEGER, problem VARCHAR) AS
+$$
+    WITH chain AS (
+        SELECT
+            a.sequence,
+            a.previous_hash,
+            a.hash,
+            audit_trail_hash(a) AS expected_hash,
+            LAG(a.sequence) OVER (ORDER BY a.sequence) AS prior_sequence,
+            LAG(a.hash) OVER (ORDER BY a.sequence) AS prior_hash
+        FROM
+            audit_trail a
+        WHERE
+            a.organisation_id = org
+    )
+    SELECT sequence, 'Entries are missing before this one'::VARCHAR
+    FROM chain WHERE sequence <> COALESCE(prior_sequence, 0) + 1
+    UNION ALL
+    SELECT sequence, 'The entry has been changed'::VARCHAR
+    FROM chain WHERE hash IS DISTINCT FROM expected_hash
+    UNION ALL
+    SELECT sequence, 'The entry does not follow on from the one before'::VARCHAR
+    FROM chain WHERE previous_hash IS DISTINCT FROM prior_hash
+    ORDER BY 1
+$$ LANGUAGE SQL STABLE;
+COMMENT ON FUNCTION verify_audit_trail IS
+    'Gaps, edits or broken links in the audit trail of an organisation.';
+
+-- migrate:down
+DROP FUNCTION verify_audit_trail;
+DROP TRIGGER forbid_audit_trail_truncate ON audit_trail;
+DROP TRIGGER forbid_audit_trail_changes ON audit_trail;
+DROP FUNCTION forbid_audit_trail_changes;
+DROP TRIGGER chain_audit_trail ON audit_trail;
+DROP FUNCTION chain_audit_trail;
+ALTER TABLE audit_trail DROP CONSTRAINT audit_trail_organisation_id_sequence_key;
+ALTER TABLE audit_trail DROP COLUMN hash;
+ALTER TABLE audit_trail DROP COLUMN previous_hash;
+ALTER TABLE audit_trail DROP COLUMN sequence;
+DROP FUNCTION audit_trail_hash;
+DELETE FROM audit_trail WHERE organisation_id NOT IN (SELECT id FROM organisations);
+ALTER TABLE audit_trail
+    ADD CONSTRAINT fk_organisation
+        FOREIGN KEY(organisation_id)
+        REFERENCES organisations(id)
+        ON DELETE CASCADE;
@@ -0,0 +1,271 @@
+-- migrate:up
+
+CREATE TYPE audit_retention AS ENUM (
+    'Archive',
+    'Purge'
+);
+COMMENT ON TYPE audit_retention IS 'What happens to audit entries once they are older than the retention period.';
+
+ALTER TABLE organisations ADD COLUMN audit_retention_days INTEGER CHECK (audit_retention_days > 0);
+ALTER TABLE organisations ADD COLUMN audit_retention audit_retention NOT NULL DEFAULT 'Archive';
+
+COMMENT ON COLUMN organisations.audit_retention_days IS 'How long audit entries stay in the audit trail, NULL to keep them forever.';
+COMMENT ON COLUMN organisations.audit_retention IS 'Whether expired audit entries are archived or purged.';
+
+ALTER TYPE audit_action ADD VALUE 'ChangeAuditRetention';
+ALTER TYPE audit_action ADD VALUE 'ExportAuditTrail';
+
+-- Same columns as the audit trail, but nothing is ever taken out.
+CREATE TABLE audit_trail_archive (
+    LIKE audit_trail INCLUDING CONSTRAINTS,
+    PRIMARY KEY (id)
+);
+COMMENT ON TABLE audit_trail_archive IS 'Audit entries that have passed the retention period of an archiving organisation.';
+
+GRANT SELECT ON audit_trail_archive TO application;
+GRANT SELECT ON audit_trail_archive TO readonly;
+
+ALTER TABLE audit_trail_archive ENABLE ROW LEVEL SECURITY;
+CREATE POLICY readonly_policy ON audit_trail_archive FOR SELECT TO readonly USING (true);
+CREATE POLICY multi_tenancy_policy ON audit_trail_archive FOR ALL TO application
+USING (
+    organisation_id IN (SELECT get_orgs_for_app_user())
+);
+COMMENT ON POLICY multi_tenancy_policy ON audit_trail_archive IS
+    'A user can see the archived audit entries of the orgs they belong to.';
+
+-- Where the audit trail starts once older entries have gone, so it can
+-- still be verified.
+CREATE TABLE audit_trail_checkpoints (
+    organisation_id INTEGER PRIMARY KEY,
+    sequence INTEGER NOT NULL,
+    hash VARCHAR NOT NULL,
+    expired_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+COMMENT ON TABLE audit_trail_checkpoints IS 'The last audit entry taken out of the audit trail for each organisation.';
+COMMENT ON COLUMN audit_trail_checkpoints.sequence IS 'The sequence of the last entry taken out, the audit trail carries on from the one after.';
+COMMENT ON COLUMN audit_trail_checkpoints.hash IS 'The hash of the last entry taken out, the previous_hash of the one after.';
+
+GRANT SELECT ON audit_trail_checkpoints TO application;
+GRANT SELECT ON audit_trail_checkpoints TO readonly;
+
+ALTER TABLE audit_trail_checkpoints ENABLE ROW LEVEL SECURITY;
+CREATE POLICY readonly_policy ON audit_trail_checkpoints FOR SELECT TO readonly USING (true);
+CREATE POLICY multi_tenancy_policy ON audit_trail_checkpoints FOR ALL TO application
+USING (
+    organisation_id IN (SELECT get_orgs_for_app_user())
+);
+COMMENT ON POLICY multi_tenancy_policy ON audit_trail_checkpoints IS
+    'A user can see the checkpoints of the orgs they belong to.';
+
+-- Deleting is still forbidden, except while expire_audit_trail is running.
+-- Any session can set audit_trail.expiring, so it only counts when we're
+-- also running as the owner of the table i.e. inside the SECURITY DEFINER
+-- function. Other roles can't become the owner, whatever DELETE grants
+-- they have.
+CREATE OR REPLACE FUNCTION forbid_audit_trail_changes() RETURNS TRIGGER AS
+$$
+BEGIN
+    IF TG_OP = 'DELETE' AND TG_TABLE_NAME = 'audit_trail'
+        AND current_user = (SELECT pg_get_userbyid(relowner) FROM pg_class WHERE oid = TG_RELID)
+        AND current_setting('audit_trail.expiring', true) = 'on' THEN
+        RETURN OLD;
+    END IF;
+    RAISE EXCEPTION 'The audit trail can only be added to'
+        USING ERRCODE = 'insufficient_privilege';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER forbid_audit_trail_changes
+    BEFORE UPDATE OR DELETE ON audit_trail_archive
+    FOR EACH ROW
+    EXECUTE FUNCTION forbid_audit_trail_changes();
+
+CREATE TRIGGER forbid_audit_trail_truncate
+    BEFORE TRUNCATE ON audit_trail_archive
+    FOR EACH STATEMENT
+    EXECUTE FUNCTION forbid_audit_trail_changes();
+
+-- Takes everything older than the retention period out of the audit trail
+-- of each organisation that has one, archiving it first if asked to.
+-- Returns how many entries were taken out.
+CREATE FUNCTION expire_audit_trail() RETURNS INTEGER AS
+$$
+DECLARE
+    org organisations;
+    last_expired audit_trail;
+    expired INTEGER := 0;
+    total INTEGER := 0;
+BEGIN
+    FOR org IN SELECT * FROM organisations WHERE audit_retention_days IS NOT NULL LOOP
+        -- Stop entries being added while we work out where the trail starts.
+        PERFORM pg_advisory_xact_lock(hashtext('audit_trail'), org.id);
+
+        -- Entries go in sequence order, so the trail stays unbroken.
+        SELECT * INTO last_expired
+        FROM audit_trail
+        WHERE organisation_id = org.id
+        AND created_at < NOW() - make_interval(days => org.audit_retention_days)
+        ORDER BY sequence DESC
+        LIMIT 1;
+
+        CONTINUE WHEN NOT FOUND;
+
+        IF org.audit_retention = 'Archive' THEN
+            INSERT INTO audit_trail_archive
+            SELECT * FROM audit_trail
+            WHERE organisation_id = org.id AND sequence <= last_expired.sequence;
+        END IF;
+
+        INSERT INTO audit_trail_checkpoints (organisation_id, sequence, hash)
+        VALUES (org.id, last_expired.sequence, last_expired.hash)
+        ON CONFLICT (organisation_id) DO UPDATE
+        SET sequence = EXCLUDED.sequence, hash = EXCLUDED.hash, expired_at = NOW();
+
+        PERFORM set_config('audit_trail.expiring', 'on', true);
+        DELETE FROM audit_trail
+        WHERE organisation_id = org.id AND sequence <= last_expired.sequence;
+        GET DIAGNOSTICS expired = ROW_COUNT;
+        PERFORM set_config('audit_trail.expiring', 'off', true);
+
+        total := total + expired;
+    END LOOP;
+    RETURN total;
+END;
+$$ LANGUAGE plpgsql SECURITY DEFINER;
+COMMENT ON FUNCTION expire_audit_trail IS
+    'Applies the audit retention period of every organisation, called by a background task.';
+
+-- If every entry has expired the chain carries on from the checkpoint.
+CREATE OR REPLACE FUNCTION chain_audit_trail() RETURNS TRIGGER AS
+$$
+DECLARE
+    last_sequence INTEGER;
+    last_hash VARCHAR;
+BEGIN
+    -- One entry at a time per organisation, so two entries can't claim
+    -- the same place in the chain. Released when the transaction ends.
+    PERFORM pg_advisory_xact_lock(hashtext('audit_trail'), NEW.organisation_id);
+
+    SELECT sequence, hash INTO last_sequence, last_hash
+    FROM audit_trail
+    WHERE organisation_id = NEW.organisation_id
+    ORDER BY sequence DESC
+    LIMIT 1;
+
+    IF NOT FOUND THEN
+        SELECT sequence, hash INTO last_sequence, last_hash
+        FROM audit_trail_checkpoints
+        WHERE organisation_id = NEW.organisation_id;
+    END IF;
+
+    NEW.sequence := COALESCE(last_sequence, 0) + 1;
+    NEW.previous_hash := last_hash;
+    NEW.hash := audit_trail_hash(NEW);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql SECURITY DEFINER;
+
+-- The trail now starts after the checkpoint, if there is one.
+CREATE OR REPLACE FUNCTION verify_audit_trail(org INTEGER)
+RETURNS TABLE (audit_sequence INTEGER, problem VARCHAR) AS
+$$
+    WITH checkpoint AS (
+        SELECT sequence, hash FROM audit_trail_checkpoints WHERE organisation_id = org
+    ),
+    chain AS (
+        SELECT
+            a.sequence,
+            a.previous_hash,
+            a.hash,
+            audit_trail_hash(a) AS expected_hash,
+            COALESCE(
+                LAG(a.sequence) OVER (ORDER BY a.sequence),
+                (SELECT sequence FROM checkpoint)
+            ) AS prior_sequence,
+            COALESCE(
+                LAG(a.hash) OVER (ORDER BY a.sequence),
+                (SELECT hash FROM checkpoint)
+            ) AS prior_hash
+        FROM
+            audit_trail a
+        WHERE
+            a.organisation_id = org
+    )
+    SELECT sequence, 'Entries are missing before this one'::VARCHAR
+    FROM chain WHERE sequence <> COALESCE(prior_sequence, 0) + 1
+    UNION ALL
+    SELECT sequence, 'The entry has been changed'::VARCHAR
+    FROM chain WHERE hash IS DISTINCT FROM expected_hash
+    UNION ALL
+    SELECT sequence, 'The entry does not follow on from the one before'::VARCHAR
+    FROM chain WHERE previous_hash IS DISTINCT FROM prior_hash
+    ORDER BY 1
+$$ LANGUAGE SQL STABLE;
+
+-- migrate:down
+CREATE OR REPLACE FUNCTION verify_audit_trail(org INTEGER)
+RETURNS TABLE (audit_sequence INTEGER, problem VARCHAR) AS
+$$
+    WITH chain AS (
+        SELECT
+            a.sequence,
+            a.previous_hash,
+            a.hash,
+            audit_trail_hash(a) AS expected_hash,
+            LAG(a.sequence) OVER (ORDER BY a.sequence) AS prior_sequence,
+            LAG(a.hash) OVER (ORDER BY a.sequence) AS prior_hash
+        FROM
+            audit_trail a
+        WHERE
+            a.organisation_id = org
+    )
+    SELECT sequence, 'Entries are missing before this one'::VARCHAR
+    FROM chain WHERE sequence <> COALESCE(prior_sequence, 0) + 1
+    UNION ALL
+    SELECT sequence, 'The entry has been changed'::VARCHAR
+    FROM chain WHERE hash IS DISTINCT FROM expected_hash
+    UNION ALL
+    SELECT sequence, 'The entry does not follow on from the one before'::VARCHAR
+    FROM chain WHERE previous_hash IS DISTINCT FROM prior_hash
+    ORDER BY 1
+$$ LANGUAGE SQL STABLE;
+
+DROP FUNCTION expire_audit_trail;
+
+CREATE OR REPLACE FUNCTION chain_audit_trail() RETURNS TRIGGER AS
+$$
+DECLARE
+    previous audit_trail;
+BEGIN
+    -- One entry at a time per organisation, so two entries can't claim
+    -- the same place in the chain. Released when the transaction ends.
+    PERFORM pg_advisory_xact_lock(hashtext('audit_trail'), NEW.organisation_id);
+
+    SELECT * INTO previous
+    FROM audit_trail
+    WHERE organisation_id = NEW.organisation_id
+    ORDER BY sequence DESC
+    LIMIT 1;
+
+    NEW.sequence := COALESCE(previous.sequence, 0) + 1;
+    NEW.previous_hash := previous.hash;
+    NEW.hash := audit_trail_hash(NEW);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql SECURITY DEFINER;
+
+CREATE OR REPLACE FUNCTION forbid_audit_trail_changes() RETURNS TRIGGER AS
+$$
+BEGIN
+    RAISE EXCEPTION 'The audit trail can only be added to'
+        USING ERRCODE = 'insufficient_privilege';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TABLE audit_trail_checkpoints;
+DROP TABLE audit_trail_archive;
+ALTER TABLE organisations DROP COLUMN audit_retention;
+ALTER TABLE organisations DROP COLUMN audit_retention_days;
+DROP TYPE audit_retention;
+-- Postgres can't remove values from an enum, so ChangeAuditRetention and ExportAuditTrail stay.
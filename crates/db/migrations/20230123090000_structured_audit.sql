@@ -0,0 +1,86 @@
+-- migrate:up
+
+-- What an audit entry was about. There are no foreign keys as the entry
+-- has to outlive whatever it was about.
+ALTER TABLE audit_trail ADD COLUMN vault_id INTEGER;
+ALTER TABLE audit_trail ADD COLUMN secret_id INTEGER;
+ALTER TABLE audit_trail ADD COLUMN environment_id INTEGER;
+ALTER TABLE audit_trail ADD COLUMN target_user_id INTEGER;
+ALTER TABLE audit_trail ADD COLUMN target_service_account_id INTEGER;
+
+COMMENT ON COLUMN audit_trail.vault_id IS 'The vault the action was carried out on, if any.';
+COMMENT ON COLUMN audit_trail.secret_id IS 'The secret the action was carried out on, if any.';
+COMMENT ON COLUMN audit_trail.environment_id IS 'The environment the action was carried out on, if any.';
+COMMENT ON COLUMN audit_trail.target_user_id IS 'The user the action was carried out on i.e. the member removed from a vault.';
+COMMENT ON COLUMN audit_trail.target_service_account_id IS 'The service account the action was carried out on.';
+
+-- The archive is filled with SELECT * so the columns have to be in the same order.
+ALTER TABLE audit_trail_archive ADD COLUMN vault_id INTEGER;
+ALTER TABLE audit_trail_archive ADD COLUMN secret_id INTEGER;
+ALTER TABLE audit_trail_archive ADD COLUMN environment_id INTEGER;
+ALTER TABLE audit_trail_archive ADD COLUMN target_user_id INTEGER;
+ALTER TABLE audit_trail_archive ADD COLUMN target_service_account_id INTEGER;
+
+CREATE INDEX audit_trail_organisation_id_vault_id ON audit_trail (organisation_id, vault_id);
+CREATE INDEX audit_trail_organisation_id_target_user_id ON audit_trail (organisation_id, target_user_id);
+
+-- The new columns are NULL on existing entries and left out of the hash,
+-- so their hashes don't change.
+CREATE OR REPLACE FUNCTION audit_trail_hash(entry audit_trail) RETURNS VARCHAR AS
+$$
+    SELECT encode(sha256(convert_to(
+        jsonb_strip_nulls(jsonb_build_object(
+            'organisation_id', entry.organisation_id,
+            'sequence', entry.sequence,
+            'user_id', entry.user_id,
+            'service_account_id', entry.service_account_id,
+            'access_type', entry.access_type,
+            'action', entry.action,
+            'description', entry.description,
+            'ip_address', entry.ip_address,
+            'user_agent', entry.user_agent,
+            'vault_id', entry.vault_id,
+            'secret_id', entry.secret_id,
+            'environment_id', entry.environment_id,
+            'target_user_id', entry.target_user_id,
+            'target_service_account_id', entry.target_service_account_id,
+            -- Microseconds since the epoch, so the time zone doesn't matter.
+            'created_at', (extract(epoch FROM entry.created_at) * 1000000)::bigint,
+            'previous_hash', entry.previous_hash
+        ))::text, 'UTF8')), 'hex')
+$$ LANGUAGE SQL IMMUTABLE;
+
+-- migrate:down
+CREATE OR REPLACE FUNCTION audit_trail_hash(entry audit_trail) RETURNS VARCHAR AS
+$$
+    SELECT encode(sha256(convert_to(
+        jsonb_strip_nulls(jsonb_build_object(
+            'organisation_id', entry.organisation_id,
+            'sequence', entry.sequence,
+            'user_id', entry.user_id,
+            'service_account_id', entry.service_account_id,
+            'access_type', entry.access_type,
+            'action', entry.action,
+            'description', entry.description,
+            'ip_address', entry.ip_address,
+            'user_agent', entry.user_agent,
+            -- Microseconds since the epoch, so the time zone doesn't matter.
+            'created_at', (extract(epoch FROM entry.created_at) * 1000000)::bigint,
+            'previous_hash', entry.previous_hash
+        ))::text, 'UTF8')), 'hex')
+$$ LANGUAGE SQL IMMUTABLE;
+
+DROP INDEX audit_trail_organisation_id_target_user_id;
+DROP INDEX audit_trail_organisation_id_vault_id;
+
+ALTER TABLE audit_trail_archive DROP COLUMN target_service_account_id;
+ALTER TABLE audit_trail_archive DROP COLUMN target_user_id;
+ALTER TABLE audit_trail_archive DROP COLUMN environment_id;
+ALTER TABLE audit_trail_archive DROP COLUMN secret_id;
+ALTER TABLE audit_trail_archive DROP COLUMN vault_id;
+
+ALTER TABLE audit_trail DROP COLUMN target_service_account_id;
+ALTER TABLE audit_trail DROP COLUMN target_user_id;
+ALTER TABLE audit_trail DROP COLUMN environment_id;
+ALTER TABLE audit_trail DROP COLUMN secret_id;
+ALTER TABLE audit_trail DROP COLUMN vault_id;
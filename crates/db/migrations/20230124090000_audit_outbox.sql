@@ -0,0 +1,109 @@
+-- migrate:up
+
+-- Audit entries waiting to be sent on to a collector i.e. a SIEM. Filled in
+-- the same transaction as the audit trail so nothing is missed if the
+-- collector is down or we crash before sending.
+CREATE TABLE audit_outbox (
+    id SERIAL PRIMARY KEY,
+    audit_id INTEGER NOT NULL REFERENCES audit_trail(id) ON DELETE CASCADE,
+    organisation_id INTEGER NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error VARCHAR,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+COMMENT ON TABLE audit_outbox IS 'Audit entries that have not been forwarded to the audit sink yet.';
+COMMENT ON COLUMN audit_outbox.attempts IS 'How many times sending the entry has failed.';
+COMMENT ON COLUMN audit_outbox.next_attempt_at IS 'When we next try to send the entry, backing off after each failure.';
+
+CREATE INDEX audit_outbox_next_attempt_at ON audit_outbox (next_attempt_at);
+
+-- No grants, the application only gets at the outbox through the functions below.
+ALTER TABLE audit_outbox ENABLE ROW LEVEL SECURITY;
+
+CREATE FUNCTION queue_audit_entry() RETURNS TRIGGER AS
+$$
+BEGIN
+    INSERT INTO audit_outbox (audit_id, organisation_id) VALUES (NEW.id, NEW.organisation_id);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql SECURITY DEFINER;
+
+CREATE TRIGGER queue_audit_entry AFTER INSERT ON audit_trail
+    FOR EACH ROW EXECUTE FUNCTION queue_audit_entry();
+
+-- Locks the entries it returns so more than one server can forward at once.
+CREATE FUNCTION claim_audit_outbox(batch INTEGER) RETURNS TABLE (
+    outbox_id INTEGER,
+    id INTEGER,
+    organisation_id INTEGER,
+    sequence INTEGER,
+    created_at TIMESTAMP WITH TIME ZONE,
+    user_id INTEGER,
+    service_account_id INTEGER,
+    access_type VARCHAR,
+    action VARCHAR,
+    description VARCHAR,
+    ip_address VARCHAR,
+    user_agent VARCHAR,
+    vault_id INTEGER,
+    secret_id INTEGER,
+    environment_id INTEGER,
+    target_user_id INTEGER,
+    target_service_account_id INTEGER,
+    hash VARCHAR
+) AS
+$$
+    SELECT
+        o.id, a.id, a.organisation_id, a.sequence, a.created_at, a.user_id,
+        a.service_account_id, a.access_type::VARCHAR, a.action::VARCHAR, a.description,
+        a.ip_address, a.user_agent, a.vault_id, a.secret_id, a.environment_id,
+        a.target_user_id, a.target_service_account_id, a.hash
+    FROM audit_outbox o
+    JOIN audit_trail a ON a.id = o.audit_id
+    WHERE o.next_attempt_at <= NOW()
+    ORDER BY o.id
+    LIMIT batch
+    FOR UPDATE OF o SKIP LOCKED;
+$$ LANGUAGE sql SECURITY DEFINER;
+
+CREATE FUNCTION forwarded_audit_outbox(ids INTEGER[]) RETURNS INTEGER AS
+$$
+    WITH forwarded AS (DELETE FROM audit_outbox WHERE id = ANY(ids) RETURNING id)
+    SELECT COUNT(*)::INTEGER FROM forwarded;
+$$ LANGUAGE sql SECURITY DEFINER;
+
+-- Back off up to an hour between attempts.
+CREATE FUNCTION failed_audit_outbox(ids INTEGER[], error VARCHAR) RETURNS INTEGER AS
+$$
+    WITH failed AS (
+        UPDATE audit_outbox
+        SET attempts = attempts + 1,
+            last_error = error,
+            next_attempt_at = NOW() + make_interval(mins => LEAST(POWER(2, attempts)::INTEGER, 60))
+        WHERE id = ANY(ids)
+        RETURNING id
+    )
+    SELECT COUNT(*)::INTEGER FROM failed;
+$$ LANGUAGE sql SECURITY DEFINER;
+
+-- When there's no audit sink configured there's nobody to forward to.
+CREATE FUNCTION discard_audit_outbox() RETURNS INTEGER AS
+$$
+    WITH discarded AS (DELETE FROM audit_outbox RETURNING id)
+    SELECT COUNT(*)::INTEGER FROM discarded;
+$$ LANGUAGE sql SECURITY DEFINER;
+
+REVOKE EXECUTE ON FUNCTION claim_audit_outbox, forwarded_audit_outbox,
+    failed_audit_outbox, discard_audit_outbox FROM PUBLIC;
+GRANT EXECUTE ON FUNCTION claim_audit_outbox, forwarded_audit_outbox,
+    failed_audit_outbox, discard_audit_outbox TO application;
+
+-- migrate:down
+DROP FUNCTION discard_audit_outbox;
+DROP FUNCTION failed_audit_outbox;
+DROP FUNCTION forwarded_audit_outbox;
+DROP FUNCTION claim_audit_outbox;
+DROP TRIGGER queue_audit_entry ON audit_trail;
+DROP FUNCTION queue_audit_entry;
+DROP TABLE audit_outbox;
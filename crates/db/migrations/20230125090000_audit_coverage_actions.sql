@@ -0,0 +1,12 @@
+-- migrate:up
+ALTER TYPE audit_action ADD VALUE 'AcceptInvite';
+ALTER TYPE audit_action ADD VALUE 'RenameTeam';
+ALTER TYPE audit_action ADD VALUE 'CreateTeam';
+ALTER TYPE audit_action ADD VALUE 'UpdateProfile';
+ALTER TYPE audit_action ADD VALUE 'CreateEnvironment';
+ALTER TYPE audit_action ADD VALUE 'RenameEnvironment';
+ALTER TYPE audit_action ADD VALUE 'DeleteEnvironment';
+ALTER TYPE audit_action ADD VALUE 'ShareSecrets';
+
+-- migrate:down
+-- Postgres can't remove values from an enum, so the new actions stay.
@@ -0,0 +1,16 @@
+-- migrate:up
+
+-- On their own as a new enum value can't be used in the transaction that
+-- adds it.
+ALTER TYPE permission ADD VALUE 'CreateVault';
+ALTER TYPE permission ADD VALUE 'DeleteVault';
+ALTER TYPE permission ADD VALUE 'ManageServiceAccounts';
+ALTER TYPE permission ADD VALUE 'ViewAudit';
+ALTER TYPE permission ADD VALUE 'InviteMembers';
+
+ALTER TYPE audit_action ADD VALUE 'CreateRole';
+ALTER TYPE audit_action ADD VALUE 'DeleteRole';
+
+-- migrate:down
+-- Postgres can't remove values from an enum, so the new permissions and
+-- CreateRole and DeleteRole stay.
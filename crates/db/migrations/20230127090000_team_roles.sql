@@ -0,0 +1,74 @@
+-- migrate:up
+
+-- Administrators can do everything. Collaborators keep what they could
+-- already do, but managing the team and inviting people is for admins.
+INSERT INTO roles_permissions VALUES
+    ('Administrator', 'CreateVault'),
+    ('Administrator', 'DeleteVault'),
+    ('Administrator', 'ManageServiceAccounts'),
+    ('Administrator', 'ViewAudit'),
+    ('Administrator', 'InviteMembers'),
+    ('Collaborator', 'CreateVault'),
+    ('Collaborator', 'DeleteVault'),
+    ('Collaborator', 'ManageServiceAccounts'),
+    ('Collaborator', 'ViewAudit');
+
+CREATE TABLE team_roles (
+    id SERIAL PRIMARY KEY,
+    organisation_id INT NOT NULL,
+    name VARCHAR NOT NULL,
+    permissions permission ARRAY NOT NULL,
+
+    UNIQUE (organisation_id, name),
+    -- So members and invitations can only use a role from their own team.
+    UNIQUE (id, organisation_id),
+
+    CONSTRAINT fk_organisation
+        FOREIGN KEY(organisation_id)
+        REFERENCES organisations(id)
+        ON DELETE CASCADE
+);
+
+COMMENT ON TABLE team_roles IS 'Roles a team defines for itself on top of the built in ones.';
+COMMENT ON COLUMN team_roles.name IS 'What the team calls the role i.e. Auditor.';
+COMMENT ON COLUMN team_roles.permissions IS 'Everything a member with this role is allowed to do.';
+
+ALTER TABLE organisation_users ADD COLUMN team_role_id INT;
+ALTER TABLE organisation_users ADD CONSTRAINT fk_team_role
+    FOREIGN KEY(team_role_id, organisation_id)
+    REFERENCES team_roles(id, organisation_id);
+COMMENT ON COLUMN organisation_users.team_role_id IS 'A custom role, used instead of the built in roles when set.';
+
+ALTER TABLE invitations ADD COLUMN team_role_id INT;
+ALTER TABLE invitations ADD CONSTRAINT fk_team_role
+    FOREIGN KEY(team_role_id, organisation_id)
+    REFERENCES team_roles(id, organisation_id);
+COMMENT ON COLUMN invitations.team_role_id IS 'The custom role the user will receive on joining the team.';
+
+-- Give access to the application user
+GRANT SELECT, INSERT, DELETE ON team_roles TO application;
+GRANT USAGE, SELECT ON team_roles_id_seq TO application;
+
+-- Give access to the readonly user
+GRANT SELECT ON team_roles TO readonly;
+GRANT SELECT ON team_roles_id_seq TO readonly;
+
+ALTER TABLE team_roles ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY readonly_policy ON team_roles FOR SELECT TO readonly USING (true);
+
+CREATE POLICY multi_tenancy_policy ON team_roles FOR ALL TO application
+USING (
+    organisation_id IN (SELECT get_orgs_for_app_user())
+);
+
+COMMENT ON POLICY multi_tenancy_policy ON team_roles IS
+    'A user can only see and change the roles of teams they belong to.';
+
+-- migrate:down
+ALTER TABLE invitations DROP COLUMN team_role_id;
+ALTER TABLE organisation_users DROP COLUMN team_role_id;
+DROP POLICY multi_tenancy_policy ON team_roles;
+DROP POLICY readonly_policy ON team_roles;
+DROP TABLE team_roles;
+DELETE FROM roles_permissions WHERE permission <> 'ManageTeam';
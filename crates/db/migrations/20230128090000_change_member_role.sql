@@ -0,0 +1,19 @@
+-- migrate:up
+
+-- So an Administrator can promote or demote someone after they've joined.
+GRANT UPDATE ON organisation_users TO application;
+
+CREATE POLICY multi_tenancy_policy_update ON organisation_users FOR UPDATE TO application
+USING (
+    organisation_id IN (SELECT get_orgs_for_app_user())
+);
+
+COMMENT ON POLICY multi_tenancy_policy_update ON organisation_users IS
+    'A user can only change the roles of members of teams they belong to.';
+
+ALTER TYPE audit_action ADD VALUE 'ChangeRole';
+
+-- migrate:down
+-- Postgres can't remove values from an enum, so ChangeRole stays.
+DROP POLICY multi_tenancy_policy_update ON organisation_users;
+REVOKE UPDATE ON organisation_users FROM application;
@@ -0,0 +1,82 @@
+-- migrate:up
+
+-- Deleting is still forbidden, except while expire_audit_trail is running
+-- or the whole team is being deleted by delete_organisation. As before the
+-- settings only count when we're running as the owner of the table.
+CREATE OR REPLACE FUNCTION forbid_audit_trail_changes() RETURNS TRIGGER AS
+$$
+BEGIN
+    IF TG_OP <> 'DELETE'
+        OR current_user <> (SELECT pg_get_userbyid(relowner) FROM pg_class WHERE oid = TG_RELID) THEN
+        RAISE EXCEPTION 'The audit trail can only be added to'
+            USING ERRCODE = 'insufficient_privilege';
+    END IF;
+    IF TG_TABLE_NAME = 'audit_trail'
+        AND current_setting('audit_trail.expiring', true) = 'on' THEN
+        RETURN OLD;
+    END IF;
+    IF current_setting('audit_trail.deleting_organisation', true) = OLD.organisation_id::text THEN
+        RETURN OLD;
+    END IF;
+    RAISE EXCEPTION 'The audit trail can only be added to'
+        USING ERRCODE = 'insufficient_privilege';
+END;
+$$ LANGUAGE plpgsql;
+
+-- The application can't delete organisations or their audit trail, so
+-- this does it for the owner. Vaults, service accounts, API keys, roles
+-- and the audit trail go with the organisation. Returns 1 if it was
+-- deleted, 0 if the current user doesn't own it.
+CREATE FUNCTION delete_organisation(org INTEGER) RETURNS INTEGER AS
+$$
+DECLARE
+    deleted INTEGER;
+BEGIN
+    PERFORM 1 FROM organisations WHERE id = org AND created_by_user_id = current_app_user();
+    IF NOT FOUND THEN
+        RETURN 0;
+    END IF;
+
+    PERFORM set_config('audit_trail.deleting_organisation', org::text, true);
+    -- Nothing references organisations from the audit trail any more, so
+    -- it doesn't cascade.
+    DELETE FROM audit_outbox WHERE organisation_id = org;
+    DELETE FROM audit_trail WHERE organisation_id = org;
+    DELETE FROM audit_trail_archive WHERE organisation_id = org;
+    DELETE FROM audit_trail_checkpoints WHERE organisation_id = org;
+    DELETE FROM invitations WHERE organisation_id = org;
+    DELETE FROM organisation_users WHERE organisation_id = org;
+    DELETE FROM organisations WHERE id = org;
+    GET DIAGNOSTICS deleted = ROW_COUNT;
+    PERFORM set_config('audit_trail.deleting_organisation', '', true);
+
+    RETURN deleted;
+END;
+$$ LANGUAGE plpgsql SECURITY DEFINER;
+COMMENT ON FUNCTION delete_organisation IS
+    'Deletes a team and everything in it, only for the user who owns it.';
+
+REVOKE EXECUTE ON FUNCTION delete_organisation FROM PUBLIC;
+GRANT EXECUTE ON FUNCTION delete_organisation TO application;
+
+ALTER TYPE audit_action ADD VALUE 'TransferTeam';
+ALTER TYPE audit_action ADD VALUE 'LeaveTeam';
+ALTER TYPE audit_action ADD VALUE 'DeleteTeam';
+
+-- migrate:down
+-- Postgres can't remove values from an enum, so TransferTeam, LeaveTeam and
+-- DeleteTeam stay.
+DROP FUNCTION delete_organisation;
+
+CREATE OR REPLACE FUNCTION forbid_audit_trail_changes() RETURNS TRIGGER AS
+$$
+BEGIN
+    IF TG_OP = 'DELETE' AND TG_TABLE_NAME = 'audit_trail'
+        AND current_user = (SELECT pg_get_userbyid(relowner) FROM pg_class WHERE oid = TG_RELID)
+        AND current_setting('audit_trail.expiring', true) = 'on' THEN
+        RETURN OLD;
+    END IF;
+    RAISE EXCEPTION 'The audit trail can only be added to'
+        USING ERRCODE = 'insufficient_privilege';
+END;
+$$ LANGUAGE plpgsql;
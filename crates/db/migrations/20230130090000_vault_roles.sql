@@ -0,0 +1,146 @@
+-- migrate:up
+CREATE TYPE vault_role AS ENUM (
+    'Viewer',
+    'Editor',
+    'Admin'
+);
+COMMENT ON TYPE vault_role IS 'Viewers can decrypt secrets, Editors can also change them and Admins can also manage the members and delete the vault.';
+
+-- Everyone could do everything before, so existing members keep that.
+ALTER TABLE users_vaults ADD COLUMN role vault_role NOT NULL DEFAULT 'Admin';
+ALTER TABLE users_vaults ALTER COLUMN role DROP DEFAULT;
+COMMENT ON COLUMN users_vaults.role IS 'What the user can do in the vault.';
+
+-- So an Admin can change the role of a member.
+GRANT UPDATE ON users_vaults TO application;
+
+-- The users_vaults policies can't look at users_vaults directly.
+CREATE FUNCTION vault_role_for_app_user(vault INTEGER) RETURNS vault_role AS
+$$
+    SELECT
+        role
+    FROM
+        users_vaults
+    WHERE
+        vault_id = vault
+    AND
+        user_id = current_app_user()
+$$ LANGUAGE SQL SECURITY DEFINER;
+COMMENT ON FUNCTION vault_role_for_app_user IS
+    'The role the user has in the vault, NULL if they are not a member.';
+
+CREATE FUNCTION vault_has_members(vault INTEGER) RETURNS BOOLEAN AS
+$$
+    SELECT EXISTS (SELECT 1 FROM users_vaults WHERE vault_id = vault)
+$$ LANGUAGE SQL SECURITY DEFINER;
+COMMENT ON FUNCTION vault_has_members IS
+    'A vault with no members is one that is being created.';
+
+-- The policies below are restrictive so they narrow the tenancy policies
+-- rather than open anything up.
+
+CREATE POLICY vault_role_insert ON secrets AS RESTRICTIVE FOR INSERT TO application
+WITH CHECK (vault_role_for_app_user(vault_id) IN ('Editor', 'Admin'));
+CREATE POLICY vault_role_update ON secrets AS RESTRICTIVE FOR UPDATE TO application
+USING (vault_role_for_app_user(vault_id) IN ('Editor', 'Admin'));
+CREATE POLICY vault_role_delete ON secrets AS RESTRICTIVE FOR DELETE TO application
+USING (vault_role_for_app_user(vault_id) IN ('Editor', 'Admin'));
+
+COMMENT ON POLICY vault_role_insert ON secrets IS
+    'Viewers can only read secrets.';
+
+CREATE POLICY vault_role_insert ON secret_versions AS RESTRICTIVE FOR INSERT TO application
+WITH CHECK (
+    vault_role_for_app_user((SELECT vault_id FROM secrets WHERE id = secret_id)) IN ('Editor', 'Admin')
+);
+
+COMMENT ON POLICY vault_role_insert ON secret_versions IS
+    'Viewers can not add to the history of a secret.';
+
+CREATE POLICY vault_role_insert ON service_account_secrets AS RESTRICTIVE FOR INSERT TO application
+WITH CHECK (
+    vault_role_for_app_user((SELECT vault_id FROM service_accounts WHERE id = service_account_id)) IN ('Editor', 'Admin')
+);
+CREATE POLICY vault_role_update ON service_account_secrets AS RESTRICTIVE FOR UPDATE TO application
+USING (
+    vault_role_for_app_user((SELECT vault_id FROM service_accounts WHERE id = service_account_id)) IN ('Editor', 'Admin')
+);
+CREATE POLICY vault_role_delete ON service_account_secrets AS RESTRICTIVE FOR DELETE TO application
+USING (
+    vault_role_for_app_user((SELECT vault_id FROM service_accounts WHERE id = service_account_id)) IN ('Editor', 'Admin')
+);
+
+COMMENT ON POLICY vault_role_insert ON service_account_secrets IS
+    'Only those who can change the secrets of the vault can share them with its service accounts.';
+
+CREATE POLICY vault_role_insert ON environments AS RESTRICTIVE FOR INSERT TO application
+WITH CHECK (vault_role_for_app_user(vault_id) = 'Admin');
+CREATE POLICY vault_role_update ON environments AS RESTRICTIVE FOR UPDATE TO application
+USING (vault_role_for_app_user(vault_id) = 'Admin');
+CREATE POLICY vault_role_delete ON environments AS RESTRICTIVE FOR DELETE TO application
+USING (vault_role_for_app_user(vault_id) = 'Admin');
+
+COMMENT ON POLICY vault_role_insert ON environments IS
+    'Only Admins can change the environments of a vault.';
+
+CREATE POLICY vault_role_insert ON users_vaults AS RESTRICTIVE FOR INSERT TO application
+WITH CHECK (
+    vault_role_for_app_user(vault_id) = 'Admin'
+    OR
+    NOT vault_has_members(vault_id)
+);
+CREATE POLICY vault_role_update ON users_vaults AS RESTRICTIVE FOR UPDATE TO application
+USING (vault_role_for_app_user(vault_id) = 'Admin');
+-- Anyone can take themselves out of a vault.
+CREATE POLICY vault_role_delete ON users_vaults AS RESTRICTIVE FOR DELETE TO application
+USING (
+    vault_role_for_app_user(vault_id) = 'Admin'
+    OR
+    user_id = current_app_user()
+);
+
+COMMENT ON POLICY vault_role_insert ON users_vaults IS
+    'Only Admins can add members, apart from whoever creates the vault.';
+
+CREATE POLICY vault_role_insert ON users_environments AS RESTRICTIVE FOR INSERT TO application
+WITH CHECK (
+    vault_role_for_app_user((SELECT vault_id FROM environments WHERE id = environment_id)) = 'Admin'
+);
+CREATE POLICY vault_role_delete ON users_environments AS RESTRICTIVE FOR DELETE TO application
+USING (
+    vault_role_for_app_user((SELECT vault_id FROM environments WHERE id = environment_id)) = 'Admin'
+    OR
+    user_id = current_app_user()
+);
+
+COMMENT ON POLICY vault_role_insert ON users_environments IS
+    'Only Admins can change who has access to an environment.';
+
+CREATE POLICY vault_role_delete ON vaults AS RESTRICTIVE FOR DELETE TO application
+USING (vault_role_for_app_user(id) = 'Admin');
+
+COMMENT ON POLICY vault_role_delete ON vaults IS
+    'Only Admins can delete a vault.';
+
+-- migrate:down
+DROP POLICY vault_role_delete ON vaults;
+DROP POLICY vault_role_delete ON users_environments;
+DROP POLICY vault_role_insert ON users_environments;
+DROP POLICY vault_role_delete ON users_vaults;
+DROP POLICY vault_role_update ON users_vaults;
+DROP POLICY vault_role_insert ON users_vaults;
+DROP POLICY vault_role_delete ON environments;
+DROP POLICY vault_role_update ON environments;
+DROP POLICY vault_role_insert ON environments;
+DROP POLICY vault_role_delete ON service_account_secrets;
+DROP POLICY vault_role_update ON service_account_secrets;
+DROP POLICY vault_role_insert ON service_account_secrets;
+DROP POLICY vault_role_insert ON secret_versions;
+DROP POLICY vault_role_delete ON secrets;
+DROP POLICY vault_role_update ON secrets;
+DROP POLICY vault_role_insert ON secrets;
+DROP FUNCTION vault_has_members;
+DROP FUNCTION vault_role_for_app_user;
+REVOKE UPDATE ON users_vaults FROM application;
+ALTER TABLE users_vaults DROP COLUMN role;
+DROP TYPE vault_role;
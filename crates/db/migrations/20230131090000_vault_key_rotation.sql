@@ -0,0 +1,225 @@
+-- migrate:up
+ALTER TABLE vaults ADD COLUMN key_version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE vaults ADD COLUMN key_created_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+ALTER TABLE vaults ADD COLUMN member_removed_at TIMESTAMPTZ;
+-- We don't know when members were removed before now, so existing keys
+-- date from when the vault was created.
+UPDATE vaults SET key_created_at = created_at;
+COMMENT ON COLUMN vaults.key_version IS 'Goes up by one each time the vault key is rotated.';
+COMMENT ON COLUMN vaults.key_created_at IS 'When the current vault key was created.';
+COMMENT ON COLUMN vaults.member_removed_at IS 'When someone last lost access to the vault. If this is after key_created_at they may still hold the key.';
+
+ALTER TABLE secrets ADD COLUMN key_version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE secret_versions ADD COLUMN key_version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE users_vaults ADD COLUMN key_version INTEGER NOT NULL DEFAULT 1;
+COMMENT ON COLUMN secrets.key_version IS 'The version of the vault key the secret is encrypted with.';
+COMMENT ON COLUMN secret_versions.key_version IS 'The version of the vault key this version is encrypted with.';
+COMMENT ON COLUMN users_vaults.key_version IS 'The version of the vault key encrypted_vault_key holds.';
+
+-- The browser says which key it encrypted with, and anything written with a
+-- key that has been rotated away is turned down. The share lock is held until
+-- the transaction ends so the key can't be rotated between the check and the
+-- write. Runs as the owner as only Admins pass the UPDATE policy on vaults.
+CREATE FUNCTION lock_key_version(vault INTEGER) RETURNS INTEGER AS
+$$
+    SELECT
+        key_version
+    FROM
+        vaults
+    WHERE
+        id = vault
+    AND
+        id IN (SELECT vault_id FROM users_vaults WHERE user_id = current_app_user())
+    FOR SHARE
+$$ LANGUAGE SQL SECURITY DEFINER SET search_path = public;
+COMMENT ON FUNCTION lock_key_version IS
+    'The current key version of a vault the user is a member of, locked until the transaction ends.';
+
+-- Re-encrypting a secret under a new key doesn't change it,
+-- so that isn't a new version.
+CREATE OR REPLACE FUNCTION record_secret_version() RETURNS TRIGGER AS
+$$
+BEGIN
+    IF TG_OP = 'UPDATE' AND NEW.key_version <> OLD.key_version THEN
+        RETURN NEW;
+    END IF;
+    INSERT INTO secret_versions (secret_id, version, name, secret, created_by_user_id, key_version)
+    SELECT
+        NEW.id,
+        COALESCE(MAX(version), 0) + 1,
+        NEW.name,
+        NEW.secret,
+        -- Not set when the database is changed outside of the application.
+        NULLIF(current_setting('row_level_security.user_id', true), '')::integer,
+        NEW.key_version
+    FROM
+        secret_versions
+    WHERE
+        secret_id = NEW.id;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Members who leave a vault, or the team, can still have the vault key.
+-- These run as the owner as non Admins can leave.
+CREATE FUNCTION record_vault_member_removed() RETURNS TRIGGER AS
+$$
+BEGIN
+    UPDATE vaults SET member_removed_at = NOW() WHERE id = OLD.vault_id;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql SECURITY DEFINER;
+
+CREATE TRIGGER record_vault_member_removed
+    AFTER DELETE ON users_vaults
+    FOR EACH ROW
+    EXECUTE FUNCTION record_vault_member_removed();
+
+CREATE FUNCTION record_team_member_removed() RETURNS TRIGGER AS
+$$
+BEGIN
+    UPDATE
+        vaults
+    SET
+        member_removed_at = NOW()
+    WHERE
+        organisation_id = OLD.organisation_id
+    AND
+        id IN (SELECT vault_id FROM users_vaults WHERE user_id = OLD.user_id);
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql SECURITY DEFINER;
+
+CREATE TRIGGER record_team_member_removed
+    AFTER DELETE ON organisation_users
+    FOR EACH ROW
+    EXECUTE FUNCTION record_team_member_removed();
+
+-- Rotating the key rewrites the ciphertext of the history as well.
+GRANT UPDATE (name, secret, key_version) ON secret_versions TO application;
+
+CREATE POLICY vault_role_update ON secret_versions AS RESTRICTIVE FOR UPDATE TO application
+USING (
+    vault_role_for_app_user((SELECT vault_id FROM secrets WHERE id = secret_id)) = 'Admin'
+);
+
+COMMENT ON POLICY vault_role_update ON secret_versions IS
+    'Only Admins can re-encrypt the history of a secret.';
+
+CREATE POLICY vault_role_update ON vaults AS RESTRICTIVE FOR UPDATE TO application
+USING (vault_role_for_app_user(id) = 'Admin');
+
+COMMENT ON POLICY vault_role_update ON vaults IS
+    'Only Admins can rotate the vault key.';
+
+-- Row level security only lets an Admin re-encrypt the secrets of the
+-- environments they have been given, so they need all of them to rotate.
+CREATE FUNCTION hidden_environments_for_app_user(vault INTEGER) RETURNS BIGINT AS
+$$
+    SELECT
+        count(*)
+    FROM
+        environments
+    WHERE
+        vault_id = vault
+    AND
+        id NOT IN (SELECT environment_id FROM users_environments WHERE user_id = current_app_user())
+$$ LANGUAGE SQL SECURITY DEFINER;
+COMMENT ON FUNCTION hidden_environments_for_app_user IS
+    'How many environments of the vault the user has not been given access to.';
+
+-- Called at the end of a rotation. Anyone who is no longer in the team keeps
+-- their old key until now as the Admin can't see them to leave them out.
+CREATE FUNCTION finish_key_rotation(vault INTEGER, new_key_version INTEGER) RETURNS BIGINT AS
+$$
+DECLARE
+    stale BIGINT;
+BEGIN
+    IF vault_role_for_app_user(vault) IS DISTINCT FROM 'Admin' THEN
+        RAISE EXCEPTION 'Only Admins can rotate the vault key'
+            USING ERRCODE = 'insufficient_privilege';
+    END IF;
+
+    -- Otherwise the count below includes secrets they could never re-encrypt.
+    IF hidden_environments_for_app_user(vault) > 0 THEN
+        RAISE EXCEPTION 'Only Admins with every environment can rotate the vault key'
+            USING ERRCODE = 'insufficient_privilege';
+    END IF;
+
+    DELETE FROM
+        users_environments
+    WHERE
+        environment_id IN (SELECT id FROM environments WHERE vault_id = vault)
+    AND
+        user_id NOT IN (
+            SELECT ou.user_id
+            FROM organisation_users ou
+            JOIN vaults v ON v.organisation_id = ou.organisation_id
+            WHERE v.id = vault
+        );
+
+    DELETE FROM
+        users_vaults
+    WHERE
+        vault_id = vault
+    AND
+        user_id NOT IN (
+            SELECT ou.user_id
+            FROM organisation_users ou
+            JOIN vaults v ON v.organisation_id = ou.organisation_id
+            WHERE v.id = vault
+        );
+
+    SELECT
+        (SELECT count(*) FROM users_vaults WHERE vault_id = vault AND key_version <> new_key_version)
+        +
+        (SELECT count(*) FROM secrets WHERE vault_id = vault AND key_version <> new_key_version)
+        +
+        (SELECT count(*) FROM secret_versions sv JOIN secrets s ON s.id = sv.secret_id
+            WHERE s.vault_id = vault AND sv.key_version <> new_key_version)
+    INTO stale;
+
+    RETURN stale;
+END;
+$$ LANGUAGE plpgsql SECURITY DEFINER;
+COMMENT ON FUNCTION finish_key_rotation IS
+    'Removes members who left the team and returns how much is still on an old key.';
+
+ALTER TYPE audit_action ADD VALUE 'RotateVaultKey';
+
+-- migrate:down
+-- Postgres can't remove values from an enum, so RotateVaultKey stays.
+DROP FUNCTION finish_key_rotation;
+DROP FUNCTION hidden_environments_for_app_user;
+DROP POLICY vault_role_update ON vaults;
+DROP POLICY vault_role_update ON secret_versions;
+REVOKE UPDATE (name, secret, key_version) ON secret_versions FROM application;
+DROP TRIGGER record_team_member_removed ON organisation_users;
+DROP FUNCTION record_team_member_removed;
+DROP TRIGGER record_vault_member_removed ON users_vaults;
+DROP FUNCTION record_vault_member_removed;
+CREATE OR REPLACE FUNCTION record_secret_version() RETURNS TRIGGER AS
+$$
+BEGIN
+    INSERT INTO secret_versions (secret_id, version, name, secret, created_by_user_id)
+    SELECT
+        NEW.id,
+        COALESCE(MAX(version), 0) + 1,
+        NEW.name,
+        NEW.secret,
+        -- Not set when the database is changed outside of the application.
+        NULLIF(current_setting('row_level_security.user_id', true), '')::integer
+    FROM
+        secret_versions
+    WHERE
+        secret_id = NEW.id;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+DROP FUNCTION lock_key_version;
+ALTER TABLE users_vaults DROP COLUMN key_version;
+ALTER TABLE secret_versions DROP COLUMN key_version;
+ALTER TABLE secrets DROP COLUMN key_version;
+ALTER TABLE vaults DROP COLUMN member_removed_at;
+ALTER TABLE vaults DROP COLUMN key_created_at;
+ALTER TABLE vaults DROP COLUMN key_version;
@@ -0,0 +1,174 @@
+-- migrate:up
+ALTER TABLE service_accounts ADD COLUMN previous_ecdh_public_key VARCHAR;
+ALTER TABLE service_accounts ADD COLUMN previous_key_expires_at TIMESTAMPTZ;
+COMMENT ON COLUMN service_accounts.previous_ecdh_public_key IS 'The key the service account had before it was rotated, still valid until previous_key_expires_at.';
+COMMENT ON COLUMN service_accounts.previous_key_expires_at IS 'When the previous key stops working and its copy of the secrets is removed.';
+
+ALTER TABLE service_account_secrets ADD COLUMN service_account_ecdh_public_key VARCHAR;
+UPDATE service_account_secrets sas
+SET service_account_ecdh_public_key = sa.ecdh_public_key
+FROM service_accounts sa
+WHERE sa.id = sas.service_account_id;
+ALTER TABLE service_account_secrets ALTER COLUMN service_account_ecdh_public_key SET NOT NULL;
+COMMENT ON COLUMN service_account_secrets.service_account_ecdh_public_key IS 'The service account key this copy is encrypted for, there is a copy for each key while one is being retired.';
+
+-- The service account a key belongs to, as long as the key is still valid.
+CREATE FUNCTION service_account_for_key(key TEXT) RETURNS INTEGER AS
+$$
+    SELECT
+        id
+    FROM
+        service_accounts
+    WHERE
+        ecdh_public_key = key
+    OR
+        (previous_ecdh_public_key = key AND previous_key_expires_at > NOW())
+$$ LANGUAGE SQL SECURITY DEFINER;
+COMMENT ON FUNCTION service_account_for_key IS
+    'Finds a service account by its current key or its previous key until that expires.';
+
+CREATE OR REPLACE FUNCTION get_orgs_for_app_user() RETURNS setof integer AS
+$$
+DECLARE
+    current_key text := current_ecdh_public_key();
+BEGIN
+    -- Is this an API call using the ECDH public key?
+    IF current_key IS NOT NULL AND LENGTH(current_key) > 10 THEN
+        RETURN QUERY SELECT
+            organisation_id
+        FROM
+            service_accounts
+        WHERE
+            id = service_account_for_key(current_key);
+    -- It's a normal call get the current app user
+    ELSE
+        RETURN QUERY SELECT
+            organisation_id
+        FROM
+            organisation_users
+        WHERE
+            user_id = current_app_user();
+    END IF;
+END;
+$$ LANGUAGE plpgsql SECURITY DEFINER;
+
+DROP POLICY multi_tenancy_policy ON service_accounts;
+CREATE POLICY multi_tenancy_policy ON service_accounts FOR ALL TO application
+USING (
+    id = service_account_for_key(current_ecdh_public_key())
+    OR
+    organisation_id IN (SELECT get_orgs_for_app_user())
+);
+
+DROP POLICY multi_tenancy_policy ON service_account_challenges;
+CREATE POLICY multi_tenancy_policy ON service_account_challenges FOR ALL TO application
+USING (
+    service_account_id = service_account_for_key(current_ecdh_public_key())
+);
+
+COMMENT ON POLICY multi_tenancy_policy ON service_account_challenges IS
+    'Challenges are only visible to the service account they were issued to.';
+
+ALTER TYPE audit_action ADD VALUE 'RotateServiceAccountKey';
+ALTER TYPE audit_action ADD VALUE 'RetireServiceAccountKey';
+
+-- Called by a background task. There's no user so the retirement is
+-- recorded against the service account itself.
+CREATE FUNCTION retire_service_account_keys() RETURNS INTEGER AS
+$$
+DECLARE
+    sa service_accounts;
+    retired INTEGER := 0;
+BEGIN
+    FOR sa IN SELECT * FROM service_accounts WHERE previous_key_expires_at <= NOW() FOR UPDATE LOOP
+        DELETE FROM
+            service_account_secrets
+        WHERE
+            service_account_id = sa.id
+        AND
+            service_account_ecdh_public_key = sa.previous_ecdh_public_key;
+
+        UPDATE
+            service_accounts
+        SET
+            previous_ecdh_public_key = NULL,
+            previous_key_expires_at = NULL
+        WHERE
+            id = sa.id;
+
+        INSERT INTO audit_trail (
+            service_account_id,
+            organisation_id,
+            action,
+            access_type,
+            description,
+            vault_id,
+            environment_id,
+            target_service_account_id)
+        VALUES (
+            sa.id,
+            sa.organisation_id,
+            'RetireServiceAccountKey',
+            'ServiceAccount',
+            format('Previous key of service account %s retired', sa.name),
+            sa.vault_id,
+            sa.environment_id,
+            sa.id);
+
+        retired := retired + 1;
+    END LOOP;
+    RETURN retired;
+END;
+$$ LANGUAGE plpgsql SECURITY DEFINER;
+COMMENT ON FUNCTION retire_service_account_keys IS
+    'Retires every previous service account key that has passed its grace period.';
+
+-- migrate:down
+-- Postgres can't remove values from an enum, so RotateServiceAccountKey
+-- and RetireServiceAccountKey stay.
+DROP FUNCTION retire_service_account_keys;
+DROP POLICY multi_tenancy_policy ON service_account_challenges;
+CREATE POLICY multi_tenancy_policy ON service_account_challenges FOR ALL TO application
+USING (
+    service_account_id IN (
+        SELECT id
+        FROM service_accounts
+        WHERE ecdh_public_key = current_ecdh_public_key())
+);
+COMMENT ON POLICY multi_tenancy_policy ON service_account_challenges IS
+    'Challenges are only visible to the service account they were issued to.';
+DROP POLICY multi_tenancy_policy ON service_accounts;
+CREATE POLICY multi_tenancy_policy ON service_accounts FOR ALL TO application
+USING (
+    ecdh_public_key = current_ecdh_public_key()
+    OR
+    organisation_id IN (SELECT get_orgs_for_app_user())
+);
+CREATE OR REPLACE FUNCTION get_orgs_for_app_user() RETURNS setof integer AS
+$$
+DECLARE
+    current_key text := current_ecdh_public_key();
+BEGIN
+    -- Is this an API call using the ECDH public key?
+    IF current_key IS NOT NULL AND LENGTH(current_key) > 10 THEN
+        RETURN QUERY SELECT
+            organisation_id
+        FROM
+            service_accounts
+        WHERE
+            ecdh_public_key = current_key;
+    -- It's a normal call get the current app user
+    ELSE
+        RETURN QUERY SELECT
+            organisation_id
+        FROM
+            organisation_users
+        WHERE
+            user_id = current_app_user();
+    END IF;
+END;
+$$ LANGUAGE plpgsql SECURITY DEFINER;
+DROP FUNCTION service_account_for_key;
+ALTER TABLE service_account_secrets DROP COLUMN service_account_ecdh_public_key;
+ALTER TABLE service_accounts DROP COLUMN previous_key_expires_at;
+ALTER TABLE service_accounts DROP COLUMN previous_ecdh_public_key;
@@ -0,0 +1,72 @@
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures::StreamExt;
+use std::str::FromStr;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_postgres::{AsyncMessage, Client, Connection};
+
+// Payloads from a Postgres NOTIFY on the channel we are listening to.
+pub struct Notifications {
+    // Dropping the client closes the connection.
+    _client: Client,
+    receiver: UnboundedReceiver<String>,
+}
+
+impl Notifications {
+    // Returns None once the connection has gone away.
+    pub async fn next(&mut self) -> Option<String> {
+        self.receiver.next().await
+    }
+}
+
+// Deadpool connections don't give us access to notifications, so anything
+// that wants to LISTEN gets its own connection.
+pub async fn listen(
+    database_url: &str,
+    channel: &str,
+) -> Result<Notifications, tokio_postgres::Error> {
+    let config = tokio_postgres::Config::from_str(database_url)?;
+    let (sender, receiver) = unbounded();
+
+    let client = if database_url.contains("sslmode=require") {
+        let tls = tokio_postgres_rustls::MakeRustlsConnect::new(super::tls_config());
+        let (client, connection) = config.connect(tls).await?;
+        tokio::spawn(forward_notifications(connection, sender));
+        client
+    } else {
+        let (client, connection) = config.connect(tokio_postgres::NoTls).await?;
+        tokio::spawn(forward_notifications(connection, sender));
+        client
+    };
+
+    // Channel names are identifiers so they can't be sent as a parameter.
+    client
+        .batch_execute(&format!("LISTEN \"{}\"", channel.replace('"', "\"\"")))
+        .await?;
+
+    Ok(Notifications {
+        _client: client,
+        receiver,
+    })
+}
+
+async fn forward_notifications<S, T>(
+    mut connection: Connection<S, T>,
+    sender: UnboundedSender<String>,
+) where
+    S: AsyncRead + AsyncWrite + Unpin,
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut messages = futures::stream::poll_fn(move |cx| connection.poll_message(cx));
+
+    while let Some(Ok(message)) = messages.next().await {
+        if let AsyncMessage::Notification(notification) = message {
+            if sender
+                .unbounded_send(notification.payload().to_string())
+                .is_err()
+            {
+                // Nobody is listening anymore.
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+--: ApiKey(expires_at?)
+
+--! insert(expires_in_days?)
+INSERT INTO 
+    api_keys (user_id, organisation_id, name, api_key_hash, expires_at)
+VALUES(
+    :user_id, 
+    :organisation_id, 
+    :name, 
+    :api_key_hash, 
+    -- A NULL number of days means the key never expires.
+    NOW() + (:expires_in_days::INTEGER * INTERVAL '1 day')
+);
+
+--! get_all : ApiKey
+SELECT 
+    id, 
+    name, 
+    -- Convert times to ISO 8601 string.
+    trim(both '"' from to_json(expires_at)::text) as expires_at, 
+    trim(both '"' from to_json(created_at)::text) as created_at
+FROM 
+    api_keys
+WHERE 
+    user_id = :user_id
+AND 
+    organisation_id = :organisation_id
+ORDER BY created_at DESC;
+
+--! get : ApiKey
+SELECT 
+    id, 
+    name, 
+    -- Convert times to ISO 8601 string.
+    trim(both '"' from to_json(expires_at)::text) as expires_at, 
+    trim(both '"' from to_json(created_at)::text) as created_at
+FROM 
+    api_keys
+WHERE 
+    id = :id
+AND 
+    user_id = :user_id;
+
+--! delete
+DELETE FROM
+    api_keys
+WHERE
+    id = :id
+AND 
+    user_id = :user_id;
+
+--! user_for_api_key : (user_id?)
+SELECT get_user_for_api_key(:api_key_hash, :organisation_id) as user_id;
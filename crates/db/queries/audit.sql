@@ -1,25 +1,125 @@
---! audit(id?, action?, access_type?, user_id?) : Audit()
+--! audit(before?, after?, action?, access_type?, user_id?, vault_id?, target_user_id?, from_date?, to_date?, search?) : Audit(email?, service_account_name?, ip_address?, user_agent?, vault_id?, vault_name?, secret_id?, environment_name?, target_email?, target_service_account_name?)
 SELECT 
     id,
     (SELECT email from users WHERE id = user_id) as email,
+    (SELECT name from service_accounts WHERE id = service_account_id) as service_account_name,
     -- Convert times to ISO 8601 string.
     trim(both '"' from to_json(created_at)::text) as created_at,
     action, 
     access_type, 
-    description 
+    description,
+    ip_address,
+    user_agent,
+    vault_id,
+    -- NULL once the thing the entry was about has been deleted.
+    (SELECT name from vaults v WHERE v.id = vault_id) as vault_name,
+    secret_id,
+    (SELECT name from environments e WHERE e.id = environment_id) as environment_name,
+    (SELECT email from users WHERE id = target_user_id) as target_email,
+    (SELECT name from service_accounts WHERE id = target_service_account_id) as target_service_account_name
 FROM 
     audit_trail
 WHERE 
     -- The inputs are optional in which case we can use COALESCE to skip
-    id < COALESCE(:id, 2147483647)
+    id < COALESCE(:before, 2147483647)
+    AND id > COALESCE(:after, 0)
     AND action = COALESCE(:action, action)
     AND access_type = COALESCE(:access_type, access_type)
-    AND user_id = COALESCE(:user_id, user_id)
+    -- Service account rows have no user_id so COALESCE won't work here.
+    AND (user_id = :user_id OR :user_id IS NULL)
+    -- Same for the entries that aren't about a vault or a user.
+    AND (vault_id = :vault_id OR :vault_id IS NULL)
+    AND (target_user_id = :target_user_id OR :target_user_id IS NULL)
+    -- Dates are YYYY-MM-DD and both inclusive.
+    AND (created_at >= to_date(:from_date, 'YYYY-MM-DD') OR :from_date IS NULL)
+    AND (created_at < to_date(:to_date, 'YYYY-MM-DD') + 1 OR :to_date IS NULL)
+    -- strpos rather than LIKE so % and _ in the search aren't wildcards.
+    AND (strpos(lower(description), lower(:search)) > 0 OR :search IS NULL)
     AND organisation_id = :organisation_id
-ORDER BY created_at DESC
+-- Going back a page we need the entries just after the cursor, newest first
+-- otherwise.
+ORDER BY CASE WHEN :after::INTEGER IS NULL THEN -id ELSE id END
 LIMIT :limit;
 
---! insert
+--! insert(user_id?, service_account_id?, vault_id?, secret_id?, environment_id?, target_user_id?, target_service_account_id?, ip_address?, user_agent?)
 INSERT INTO 
-    audit_trail (user_id, organisation_id, action, access_type, description) 
-    VALUES (:user_id, :organisation_id, :action, :access_type, :description);
\ No newline at end of file
+    audit_trail (
+        user_id, 
+        service_account_id, 
+        organisation_id, 
+        action, 
+        access_type, 
+        description, 
+        vault_id, 
+        secret_id, 
+        environment_id, 
+        target_user_id, 
+        target_service_account_id, 
+        ip_address, 
+        user_agent)
+    VALUES (
+        :user_id, 
+        :service_account_id, 
+        :organisation_id, 
+        :action, 
+        :access_type, 
+        :description, 
+        :vault_id, 
+        :secret_id, 
+        :environment_id, 
+        :target_user_id, 
+        :target_service_account_id, 
+        :ip_address, 
+        :user_agent);
+
+--! verify : AuditProblem()
+SELECT 
+    audit_sequence, 
+    problem 
+FROM 
+    verify_audit_trail(:organisation_id);
+
+--! organisations
+SELECT DISTINCT 
+    organisation_id 
+FROM 
+    audit_trail 
+ORDER BY organisation_id;
+
+--! export(from_date, to_date) : AuditExport(email?, service_account_name?, ip_address?, user_agent?, vault_id?, vault_name?, secret_id?, environment_id?, environment_name?, target_user_id?, target_email?, target_service_account_id?, target_service_account_name?, previous_hash?)
+SELECT 
+    sequence,
+    -- Convert times to ISO 8601 string.
+    trim(both '"' from to_json(created_at)::text) as created_at,
+    (SELECT email from users WHERE id = user_id) as email,
+    (SELECT name from service_accounts WHERE id = service_account_id) as service_account_name,
+    access_type::text as access_type,
+    action::text as action,
+    description,
+    ip_address,
+    user_agent,
+    vault_id,
+    (SELECT name from vaults v WHERE v.id = vault_id) as vault_name,
+    secret_id,
+    environment_id,
+    (SELECT name from environments e WHERE e.id = environment_id) as environment_name,
+    target_user_id,
+    (SELECT email from users WHERE id = target_user_id) as target_email,
+    target_service_account_id,
+    (SELECT name from service_accounts WHERE id = target_service_account_id) as target_service_account_name,
+    previous_hash,
+    hash
+FROM (
+    -- Archived entries are still part of the history.
+    SELECT * FROM audit_trail WHERE organisation_id = :organisation_id
+    UNION ALL
+    SELECT * FROM audit_trail_archive WHERE organisation_id = :organisation_id
+) entries
+WHERE 
+    created_at >= to_date(:from_date, 'YYYY-MM-DD')
+    -- The to date is inclusive.
+    AND created_at < to_date(:to_date, 'YYYY-MM-DD') + 1
+ORDER BY sequence;
+
+--! expire
+SELECT expire_audit_trail();
@@ -0,0 +1,31 @@
+--! claim : AuditOutbox(user_id?, service_account_id?, ip_address?, user_agent?, vault_id?, secret_id?, environment_id?, target_user_id?, target_service_account_id?)
+SELECT
+    outbox_id,
+    id,
+    organisation_id,
+    sequence,
+    -- Convert times to ISO 8601 string.
+    trim(both '"' from to_json(created_at)::text) as created_at,
+    user_id,
+    service_account_id,
+    access_type,
+    action,
+    description,
+    ip_address,
+    user_agent,
+    vault_id,
+    secret_id,
+    environment_id,
+    target_user_id,
+    target_service_account_id,
+    hash
+FROM claim_audit_outbox(:batch);
+
+--! forwarded
+SELECT forwarded_audit_outbox(:ids);
+
+--! failed
+SELECT failed_audit_outbox(:ids, :error);
+
+--! discard
+SELECT discard_audit_outbox();
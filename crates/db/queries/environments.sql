@@ -21,14 +21,41 @@ ORDER BY name;
 --! connect_environment_to_user
 INSERT INTO users_environments (user_id, environment_id) VALUES(:user_id, :environment_id);
 
+--! disconnect_environment_from_user
+DELETE FROM
+    users_environments
+WHERE
+    user_id = :user_id
+AND
+    environment_id = :environment_id;
+
+--! get_for_user : Environment
+SELECT  
+    id, 
+    name
+FROM 
+    environments 
+WHERE 
+    vault_id = :vault_id
+AND 
+    id
+IN
+    (SELECT environment_id 
+    FROM
+        users_environments
+    WHERE
+        user_id = :user_id)
+ORDER BY name;
+
 --! setup_environments
+-- The creator is given access to these by a trigger. We can't use
+-- RETURNING as the rows aren't visible until that has happened.
 INSERT INTO 
     environments (vault_id, name)
 VALUES
     (:vault_id, 'Development'),
     (:vault_id, 'Staging'),
-    (:vault_id, 'Production')
-RETURNING id, name;
+    (:vault_id, 'Production');
 
 --! get_environments_and_vaults : EnvironmentsAndVault()
 SELECT  
@@ -46,4 +73,69 @@ IN
         users_environments
     WHERE
         user_id = current_app_user())
-ORDER BY name;
\ No newline at end of file
+ORDER BY name;
+
+--! get : Environment
+SELECT
+    id,
+    name
+FROM
+    environments
+WHERE
+    id = :id
+AND
+    vault_id = :vault_id;
+
+--! insert
+-- As with setup_environments, the row isn't visible to RETURNING until
+-- the trigger has given the creator access, so use get_by_name afterwards.
+INSERT INTO environments (vault_id, name) VALUES(:vault_id, :name);
+
+--! get_by_name : Environment
+SELECT
+    id,
+    name
+FROM
+    environments
+WHERE
+    name = :name
+AND
+    vault_id = :vault_id;
+
+--! rename
+UPDATE
+    environments
+SET
+    name = :name
+WHERE
+    id = :id
+AND
+    vault_id = :vault_id;
+
+--! delete
+DELETE FROM
+    environments
+WHERE
+    id = :id
+AND
+    vault_id = :vault_id;
+
+--! delete_service_account_secrets
+-- The secrets were copied from the environment so they go with it.
+DELETE FROM
+    service_account_secrets
+WHERE
+    service_account_id
+IN
+    (SELECT id FROM service_accounts WHERE environment_id = :environment_id);
+
+--! disconnect_service_accounts
+UPDATE
+    service_accounts
+SET
+    vault_id = NULL, environment_id = NULL
+WHERE
+    environment_id = :environment_id;
+
+--! hidden_environments
+SELECT hidden_environments_for_app_user(:vault_id);
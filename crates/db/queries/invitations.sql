@@ -1,6 +1,6 @@
---: Invitation()
+--: Invitation(team_role_id?, team_role?)
 
---! insert_invitation
+--! insert_invitation(team_role_id?)
 INSERT INTO 
     invitations (
         organisation_id, 
@@ -9,7 +9,8 @@ INSERT INTO
         last_name, 
         invitation_selector, 
         invitation_verifier_hash, 
-        roles)
+        roles,
+        team_role_id)
     VALUES(
         :organisation_id, 
         :email, 
@@ -17,23 +18,27 @@ INSERT INTO
         :last_name, 
         :invitation_selector, 
         :invitation_verifier_hash, 
-        :roles);
+        :roles,
+        :team_role_id);
 
 --! get_invitation : Invitation
 SELECT
-    id, 
-    organisation_id, 
-    email, 
-    first_name, 
-    last_name, 
-    invitation_selector, 
-    invitation_verifier_hash,
-    roles,
-    created_at
+    i.id, 
+    i.organisation_id, 
+    i.email, 
+    i.first_name, 
+    i.last_name, 
+    i.invitation_selector, 
+    i.invitation_verifier_hash,
+    i.roles,
+    i.team_role_id,
+    tr.name as team_role,
+    i.created_at
 FROM 
-    invitations 
+    invitations i
+LEFT JOIN team_roles tr ON tr.id = i.team_role_id
 WHERE
-    invitation_selector = :invitation_selector;
+    i.invitation_selector = :invitation_selector;
 
 --! delete_invitation
 DELETE FROM
@@ -45,15 +50,18 @@ AND
 
 --! get_all : Invitation
 SELECT  
-    id, 
-    email,
-    first_name, 
-    last_name, 
-    invitation_selector, 
-    invitation_verifier_hash,
-    organisation_id,
-    roles,
-    created_at  
+    i.id, 
+    i.email,
+    i.first_name, 
+    i.last_name, 
+    i.invitation_selector, 
+    i.invitation_verifier_hash,
+    i.organisation_id,
+    i.roles,
+    i.team_role_id,
+    tr.name as team_role,
+    i.created_at  
 FROM 
-    invitations 
-WHERE organisation_id = :organisation_id;
\ No newline at end of file
+    invitations i
+LEFT JOIN team_roles tr ON tr.id = i.team_role_id
+WHERE i.organisation_id = :organisation_id;
\ No newline at end of file
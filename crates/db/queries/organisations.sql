@@ -1,6 +1,6 @@
 --! organisation : (name?)
 SELECT 
-    id, name
+    id, name, created_by_user_id
 FROM 
     organisations
 WHERE
@@ -20,14 +20,14 @@ SELECT
 FROM 
     organisations
 WHERE
-    created_by_user_id = :created_by_user_id
-ORDER BY id ASC
+    id IN (SELECT organisation_id FROM organisation_users WHERE user_id = :user_id)
+ORDER BY created_by_user_id = :user_id DESC, id ASC
 LIMIT 1;
 
---! add_user_to_organisation
+--! add_user_to_organisation(team_role_id?)
 INSERT INTO 
-    organisation_users (user_id, organisation_id, roles)
-VALUES(:user_id, :organisation_id, :roles);
+    organisation_users (user_id, organisation_id, roles, team_role_id)
+VALUES(:user_id, :organisation_id, :roles, :team_role_id);
 
 --! insert_organisation
 INSERT INTO 
@@ -35,7 +35,7 @@ INSERT INTO
 VALUES(current_app_user()) 
 RETURNING id;
 
---! get_users : (first_name?, last_name?)
+--! get_users : (first_name?, last_name?, team_role_id?, team_role?)
 SELECT 
     u.id, 
     ou.organisation_id, 
@@ -43,10 +43,13 @@ SELECT
     u.first_name,
     u.last_name,
     u.ecdh_public_key, 
-    ou.roles
+    ou.roles,
+    ou.team_role_id,
+    tr.name as team_role
 FROM 
     organisation_users ou
 LEFT JOIN users u ON u.id = ou.user_id
+LEFT JOIN team_roles tr ON tr.id = ou.team_role_id
 WHERE
     ou.organisation_id = :organisation_id;
 
@@ -69,4 +72,60 @@ DELETE FROM
 WHERE
     user_id = :user_id_to_remove
 AND
-    organisation_id = :organisation_id;
\ No newline at end of file
+    organisation_id = :organisation_id;
+
+--! set_roles(team_role_id?)
+UPDATE
+    organisation_users
+SET 
+    roles = :roles,
+    team_role_id = :team_role_id
+WHERE
+    user_id = :user_id
+AND
+    organisation_id = :organisation_id;
+
+--! count_administrators
+SELECT 
+    COUNT(*)
+FROM 
+    organisation_users
+WHERE
+    organisation_id = :organisation_id
+AND
+    'Administrator' = ANY(roles);
+--! audit_retention : AuditRetentionSettings(audit_retention_days?)
+SELECT 
+    audit_retention_days, audit_retention
+FROM 
+    organisations
+WHERE
+    id = :org_id;
+
+--! set_audit_retention(audit_retention_days?)
+UPDATE
+    organisations
+SET 
+    audit_retention_days = :audit_retention_days,
+    audit_retention = :audit_retention
+WHERE
+    id = :org_id;
+
+--! transfer_ownership
+UPDATE
+    organisations
+SET 
+    created_by_user_id = :new_owner_id
+WHERE
+    id = :org_id
+AND
+    created_by_user_id = current_app_user()
+AND 
+    :new_owner_id IN (
+        SELECT user_id FROM organisation_users 
+        WHERE organisation_id = :org_id AND 'Administrator' = ANY(roles)
+    )
+RETURNING id;
+
+--! delete_organisation
+SELECT delete_organisation(:org_id);
@@ -10,4 +10,50 @@ IN
         SELECT UNNEST(roles) 
         FROM organisation_users 
         WHERE user_id = :current_user_id AND organisation_id = :organisation_id
-    );
\ No newline at end of file
+        AND team_role_id IS NULL
+    )
+UNION
+SELECT 
+    UNNEST(tr.permissions)
+FROM 
+    team_roles tr
+JOIN organisation_users ou ON ou.team_role_id = tr.id
+WHERE 
+    ou.user_id = :current_user_id AND ou.organisation_id = :organisation_id;
+
+--! team_roles : TeamRole
+SELECT 
+    id, organisation_id, name, permissions
+FROM 
+    team_roles
+WHERE
+    organisation_id = :organisation_id
+ORDER BY name ASC;
+
+--! team_role : TeamRole
+SELECT 
+    id, organisation_id, name, permissions
+FROM 
+    team_roles
+WHERE
+    id = :id
+AND
+    organisation_id = :organisation_id;
+
+--! insert_team_role
+INSERT INTO 
+    team_roles (organisation_id, name, permissions)
+VALUES(:organisation_id, :name, :permissions);
+
+--! delete_team_role
+DELETE FROM
+    team_roles
+WHERE
+    id = :id
+AND
+    organisation_id = :organisation_id
+AND NOT EXISTS 
+    (SELECT 1 FROM organisation_users WHERE team_role_id = :id)
+AND NOT EXISTS 
+    (SELECT 1 FROM invitations WHERE team_role_id = :id)
+RETURNING name;
@@ -2,13 +2,14 @@
 
 --! insert
 INSERT INTO 
-    secrets (vault_id, name, name_blind_index, secret, environment_id)
+    secrets (vault_id, name, name_blind_index, secret, environment_id, key_version)
 VALUES(
     :vault_id, 
     :name, 
     :name_blind_index, 
     :secret, 
-    :environment_id
+    :environment_id,
+    :key_version
 );
 
 --! get_all : Secret
@@ -24,6 +25,14 @@ SELECT
     trim(both '"' from to_json(updated_at)::text) as updated_at, 
     trim(both '"' from to_json(created_at)::text) as created_at
 FROM secrets WHERE vault_id = :vault_id
+AND
+    environment_id
+IN
+    (SELECT environment_id 
+    FROM
+        users_environments
+    WHERE
+        user_id = current_app_user())
 ORDER BY environment_name;
 
 --! get : Secret
@@ -40,6 +49,20 @@ SELECT
     trim(both '"' from to_json(created_at)::text) as created_at
 FROM secrets WHERE id = :id;
 
+--! update_secret
+-- Only succeeds if nobody else has changed the secret since the
+-- caller read it, so two editors can't overwrite each other.
+UPDATE
+    secrets
+SET
+    secret = :secret,
+    key_version = :key_version,
+    updated_at = NOW()
+WHERE
+    id = :id
+AND
+    trim(both '"' from to_json(updated_at)::text) = :updated_at;
+
 --! delete_secret
 DELETE FROM
     secrets
@@ -54,4 +77,116 @@ WHERE
 AND
     service_account_id
 IN
-    (SELECT id FROM service_accounts WHERE vault_id = :vault_id);
\ No newline at end of file
+    (SELECT id FROM service_accounts WHERE vault_id = :vault_id);
+
+-- The secrets a service account connected to the environment has a copy of.
+--! get_blind_indexes
+SELECT
+    name_blind_index
+FROM
+    secrets
+WHERE
+    vault_id = :vault_id
+AND
+    environment_id = :environment_id;
+
+--: SecretVersion(email?)
+
+--! get_versions : SecretVersion
+SELECT
+    sv.id,
+    sv.secret_id,
+    sv.version,
+    sv.name,
+    sv.secret,
+    (SELECT email FROM users WHERE id = sv.created_by_user_id) AS email,
+    -- Convert times to ISO 8601 string.
+    trim(both '"' from to_json(sv.created_at)::text) as created_at
+FROM
+    secret_versions sv
+WHERE
+    sv.secret_id IN (SELECT id FROM secrets WHERE vault_id = :vault_id)
+ORDER BY sv.secret_id, sv.version DESC;
+
+--! get_version : SecretVersion
+SELECT
+    sv.id,
+    sv.secret_id,
+    sv.version,
+    sv.name,
+    sv.secret,
+    (SELECT email FROM users WHERE id = sv.created_by_user_id) AS email,
+    -- Convert times to ISO 8601 string.
+    trim(both '"' from to_json(sv.created_at)::text) as created_at
+FROM
+    secret_versions sv
+WHERE
+    sv.id = :id
+AND
+    sv.secret_id = :secret_id;
+
+--! restore_version
+-- Writing the old ciphertext back records it as a new version.
+UPDATE
+    secrets
+SET
+    name = sv.name,
+    secret = sv.secret,
+    key_version = sv.key_version,
+    updated_at = NOW()
+FROM
+    secret_versions sv
+WHERE
+    sv.id = :version_id
+AND
+    sv.secret_id = secrets.id
+AND
+    secrets.id = :secret_id;
+
+-- Every secret in the vault whatever environment it's in, as they all
+-- need re-encrypting when the key is rotated.
+--! get_for_rotation : Ciphertext()
+SELECT
+    id,
+    name,
+    secret
+FROM
+    secrets
+WHERE
+    vault_id = :vault_id;
+
+--! get_versions_for_rotation : Ciphertext()
+SELECT
+    sv.id,
+    sv.name,
+    sv.secret
+FROM
+    secret_versions sv
+WHERE
+    sv.secret_id IN (SELECT id FROM secrets WHERE vault_id = :vault_id);
+
+--! reencrypt
+-- Anyone still editing with the old key gets a conflict when they save.
+UPDATE
+    secrets
+SET
+    name = :name,
+    secret = :secret,
+    key_version = :key_version,
+    updated_at = NOW()
+WHERE
+    id = :id
+AND
+    vault_id = :vault_id;
+
+--! reencrypt_version
+UPDATE
+    secret_versions
+SET
+    name = :name,
+    secret = :secret,
+    key_version = :key_version
+WHERE
+    id = :id
+AND
+    secret_id IN (SELECT id FROM secrets WHERE vault_id = :vault_id);
@@ -0,0 +1,25 @@
+--! insert
+INSERT INTO service_account_challenges
+    (service_account_id, nonce)
+VALUES
+    (:service_account_id, :nonce);
+
+--! delete_expired
+DELETE FROM
+    service_account_challenges
+WHERE
+    service_account_id = :service_account_id
+AND
+    created_at < NOW() - INTERVAL '5 minutes';
+
+-- Challenges are single use, so we delete it as we check it.
+--! consume
+DELETE FROM
+    service_account_challenges
+WHERE
+    service_account_id = :service_account_id
+AND
+    nonce = :nonce
+AND
+    created_at > NOW() - INTERVAL '5 minutes'
+RETURNING id;
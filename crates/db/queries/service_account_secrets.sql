@@ -4,7 +4,10 @@ SELECT
 FROM 
     service_account_secrets 
 WHERE 
-    service_account_id = :service_account_id;
+    service_account_id = :service_account_id
+AND
+    -- Only the copies for the key the service account used.
+    service_account_ecdh_public_key = :service_account_ecdh_public_key;
 
 --! get_users_vaults
 SELECT user_id 
@@ -15,14 +18,26 @@ WHERE
 AND
     vault_id = :vault_id;
 
---! insert
+--! insert(service_account_ecdh_public_key?)
 INSERT INTO service_account_secrets
-    (service_account_id, name, name_blind_index, secret, ecdh_public_key)
+    (service_account_id, name, name_blind_index, secret, ecdh_public_key, service_account_ecdh_public_key)
 VALUES
     (
         :service_account_id, 
         :name, 
         :name_blind_index, 
         :secret, 
-        :ecdh_public_key
-);
\ No newline at end of file
+        :ecdh_public_key,
+        -- The current key of the service account unless we're told otherwise.
+        COALESCE(
+            :service_account_ecdh_public_key,
+            (SELECT ecdh_public_key FROM service_accounts WHERE id = :service_account_id))
+);
+
+--! delete_for_key
+DELETE FROM
+    service_account_secrets
+WHERE
+    service_account_id = :service_account_id
+AND
+    service_account_ecdh_public_key = :service_account_ecdh_public_key;
\ No newline at end of file
@@ -1,4 +1,4 @@
---: ServiceAccount(vault_id?, vault_name?, environment_name?)
+--: ServiceAccount(vault_id?, vault_name?, environment_id?, environment_name?, previous_key_expires_at?)
 
 --! connect
 UPDATE service_accounts 
@@ -27,18 +27,20 @@ SELECT
     sa.vault_id, 
     sa.name as account_name,
     (SELECT name FROM vaults WHERE id = sa.vault_id) as vault_name,
+    sa.environment_id,
     (SELECT name FROM environments WHERE id = sa.environment_id) as environment_name,
     sa.ecdh_public_key, 
     sa.encrypted_ecdh_private_key,
     -- Convert times to ISO 8601 string.
     trim(both '"' from to_json(updated_at)::text) as updated_at, 
-    trim(both '"' from to_json(created_at)::text) as created_at
+    trim(both '"' from to_json(created_at)::text) as created_at,
+    trim(both '"' from to_json(previous_key_expires_at)::text) as previous_key_expires_at
 FROM 
     service_accounts sa
 WHERE 
     sa.organisation_id = :organisation_id;
 
---! get_by_vault : (vault_id?, environment_id?)
+--! get_by_vault : (vault_id?, environment_id?, previous_ecdh_public_key?)
 SELECT 
     sa.id as id, 
     sa.vault_id as vault_id, 
@@ -47,6 +49,9 @@ SELECT
     sa.ecdh_public_key, 
     sa.encrypted_ecdh_private_key,
     sa.environment_id,
+    -- Secrets are encrypted for both keys until the previous one expires.
+    CASE WHEN sa.previous_key_expires_at > NOW() THEN sa.previous_ecdh_public_key END
+        as previous_ecdh_public_key,
     -- Convert times to ISO 8601 string.
     trim(both '"' from to_json(sa.updated_at)::text) as updated_at, 
     trim(both '"' from to_json(sa.created_at)::text) as created_at
@@ -59,10 +64,12 @@ ON
 WHERE 
     sa.vault_id = :vault_id;
 
---! get_by_ecdh_public_key : (vault_id?, vault_name?)
+--! get_by_ecdh_public_key : (vault_id?, vault_name?, environment_id?)
 SELECT 
     sa.id, 
+    sa.organisation_id, 
     sa.vault_id, 
+    sa.environment_id, 
     sa.name as account_name, 
     v.name as vault_name, 
     sa.ecdh_public_key, 
@@ -76,12 +83,15 @@ LEFT OUTER JOIN
     vaults v
 ON 
     v.id = sa.vault_id
-WHERE sa.ecdh_public_key = :ecdh_public_key;
+-- The previous key works too until it expires.
+WHERE sa.id = service_account_for_key(:ecdh_public_key);
 
---! get_dangerous : (vault_id?, vault_name?)
+--! get_dangerous : (vault_id?, vault_name?, environment_id?)
 SELECT
     sa.id, 
+    sa.organisation_id, 
     sa.vault_id, 
+    sa.environment_id, 
     sa.name, 
     v.name as vault_name, 
     sa.ecdh_public_key,
@@ -110,4 +120,40 @@ AND
 DELETE FROM
     service_account_secrets
 WHERE
-    service_account_id = :service_account_id;
\ No newline at end of file
+    service_account_id = :service_account_id;
+
+-- The current key becomes the previous key, which keeps working until
+-- the grace period is over. Only one key can be retiring at a time.
+--! rotate_key
+UPDATE
+    service_accounts
+SET
+    previous_ecdh_public_key = ecdh_public_key,
+    previous_key_expires_at = NOW() + make_interval(hours => :grace_hours),
+    ecdh_public_key = :ecdh_public_key,
+    encrypted_ecdh_private_key = :encrypted_ecdh_private_key,
+    updated_at = NOW()
+WHERE
+    id = :id
+AND
+    organisation_id = :organisation_id
+AND
+    previous_ecdh_public_key IS NULL
+RETURNING
+    previous_ecdh_public_key,
+    -- Convert times to ISO 8601 string.
+    trim(both '"' from to_json(previous_key_expires_at)::text) as previous_key_expires_at;
+
+--! retire_previous_key
+UPDATE
+    service_accounts
+SET
+    previous_ecdh_public_key = NULL,
+    previous_key_expires_at = NULL
+WHERE
+    id = :id
+AND
+    organisation_id = :organisation_id;
+
+--! retire_expired_keys
+SELECT retire_service_account_keys();
\ No newline at end of file
@@ -1,7 +1,7 @@
 --! insert
 INSERT INTO 
-    users_vaults (user_id, vault_id, ecdh_public_key, encrypted_vault_key)
-VALUES(:user_id, :vault_id, :ecdh_public_key, :encrypted_vault_key);
+    users_vaults (user_id, vault_id, ecdh_public_key, encrypted_vault_key, role, key_version)
+VALUES(:user_id, :vault_id, :ecdh_public_key, :encrypted_vault_key, :role, :key_version);
 
 --! delete
 DELETE FROM
@@ -14,16 +14,17 @@ AND vault_id IN (SELECT vault_id FROM users_vaults WHERE user_id = :current_user
 
 --! get : UserVault()
 SELECT 
-    vault_id, user_id, encrypted_vault_key, ecdh_public_key 
+    vault_id, user_id, encrypted_vault_key, ecdh_public_key, key_version 
 FROM users_vaults 
 WHERE 
     user_id = :user_id AND vault_id = :vault_id;
 
---! get_users : VaultMember(environments?)
+--! get_users : VaultMember(environments?, environment_ids?)
 SELECT 
     uv.vault_id, 
     uv.user_id, 
     u.email,
+    uv.role,
     -- Creata a string showing the users environments for this vault
     (
         SELECT 
@@ -36,7 +37,21 @@ SELECT
         AND
             e.vault_id = uv.vault_id
     ) 
-    as environments 
+    as environments,
+    -- The same environments as a comma separated list of ids, so we can
+    -- tick them when editing the users access.
+    (
+        SELECT 
+            STRING_AGG(e.id::text, ',') 
+        FROM 
+            users_environments ue
+        JOIN environments e ON ue.environment_id = e.id 
+        WHERE 
+            ue.user_id = uv.user_id
+        AND
+            e.vault_id = uv.vault_id
+    ) 
+    as environment_ids 
 FROM users_vaults uv
 LEFT JOIN users u ON u.id = uv.user_id
 WHERE 
@@ -61,4 +76,71 @@ WHERE
     vault_id = :vault_id
 AND
     user_id = :user_id
-AND vault_id IN (SELECT vault_id FROM users_vaults WHERE user_id = :current_user);
\ No newline at end of file
+AND vault_id IN (SELECT vault_id FROM users_vaults WHERE user_id = :current_user);
+
+--! leave_environments
+DELETE FROM
+    users_environments
+WHERE
+    user_id = :user_id
+AND environment_id IN (
+    SELECT e.id FROM environments e
+    JOIN vaults v ON v.id = e.vault_id
+    WHERE v.organisation_id = :organisation_id
+);
+
+--! leave_vaults
+DELETE FROM
+    users_vaults
+WHERE
+    user_id = :user_id
+AND vault_id IN (SELECT id FROM vaults WHERE organisation_id = :organisation_id);
+
+--! role
+SELECT 
+    role
+FROM 
+    users_vaults 
+WHERE 
+    user_id = :user_id AND vault_id = :vault_id;
+
+--! set_role
+UPDATE
+    users_vaults
+SET
+    role = :role
+WHERE
+    user_id = :user_id
+AND
+    vault_id = :vault_id;
+
+-- A vault that still has members but nobody left to manage them.
+--! without_administrator
+SELECT
+    EXISTS (SELECT 1 FROM users_vaults WHERE vault_id = :vault_id)
+AND
+    NOT EXISTS (SELECT 1 FROM users_vaults WHERE vault_id = :vault_id AND role = 'Admin');
+
+-- The members of the team that need the new key when it's rotated.
+--! get_for_rotation : MemberKey()
+SELECT
+    uv.user_id,
+    u.ecdh_public_key
+FROM users_vaults uv
+JOIN users u ON u.id = uv.user_id
+WHERE
+    uv.vault_id = :vault_id
+AND
+    uv.user_id IN (SELECT user_id FROM organisation_users WHERE organisation_id = :organisation_id);
+
+--! rewrap
+UPDATE
+    users_vaults
+SET
+    encrypted_vault_key = :encrypted_vault_key,
+    ecdh_public_key = :ecdh_public_key,
+    key_version = :key_version
+WHERE
+    user_id = :user_id
+AND
+    vault_id = :vault_id;
@@ -6,23 +6,29 @@ INSERT INTO
 VALUES(:organisation_id, :name) 
 RETURNING id;
 
+-- Whoever creates a vault is its first Admin.
 --! insert_user_vaults
 INSERT INTO 
-    users_vaults (user_id, vault_id, ecdh_public_key, encrypted_vault_key)
+    users_vaults (user_id, vault_id, ecdh_public_key, encrypted_vault_key, role)
 VALUES(
     :user_id, 
     :vault_id, 
     :ecdh_public_key, 
-    :encrypted_vault_key
+    :encrypted_vault_key,
+    'Admin'
 );
 
 --! vault : Vault
 SELECT 
     id, 
+    organisation_id,
     name, 
     -- Convert times to ISO 8601 string.
     trim(both '"' from to_json(updated_at)::text) as updated_at, 
-    trim(both '"' from to_json(created_at)::text) as created_at
+    trim(both '"' from to_json(created_at)::text) as created_at,
+    key_version,
+    -- Someone who has lost access may still have the key.
+    COALESCE(member_removed_at > key_created_at, false) as key_rotation_needed
 FROM 
     vaults
 WHERE
@@ -31,10 +37,14 @@ WHERE
 --! get : Vault
 SELECT 
     id, 
+    organisation_id,
     name, 
     -- Convert times to ISO 8601 string.
     trim(both '"' from to_json(updated_at)::text) as updated_at, 
-    trim(both '"' from to_json(created_at)::text) as created_at
+    trim(both '"' from to_json(created_at)::text) as created_at,
+    key_version,
+    -- Someone who has lost access may still have the key.
+    COALESCE(member_removed_at > key_created_at, false) as key_rotation_needed
 FROM 
     vaults
 WHERE
@@ -51,10 +61,14 @@ IN
 --! get_all : Vault
 SELECT 
     v.id,
+    v.organisation_id,
     v.name, 
     -- Convert times to ISO 8601 string.
     trim(both '"' from to_json(v.updated_at)::text) as updated_at, 
-    trim(both '"' from to_json(v.created_at)::text) as created_at
+    trim(both '"' from to_json(v.created_at)::text) as created_at,
+    v.key_version,
+    -- Someone who has lost access may still have the key.
+    COALESCE(v.member_removed_at > v.key_created_at, false) as key_rotation_needed
 FROM 
     vaults v
 LEFT JOIN users_vaults uv ON uv.vault_id = v.id
@@ -93,4 +107,23 @@ DELETE FROM
 WHERE
     vault_id = :vault_id
 AND
-    :current_user_id IN (SELECT user_id FROM users_vaults WHERE vault_id = :vault_id);
\ No newline at end of file
+    :current_user_id IN (SELECT user_id FROM users_vaults WHERE vault_id = :vault_id);
+
+-- Only one rotation can win, the others find the version has moved on.
+--! start_key_rotation
+UPDATE
+    vaults
+SET
+    key_version = key_version + 1,
+    key_created_at = NOW()
+WHERE
+    id = :vault_id
+AND
+    key_version = :key_version
+RETURNING key_version;
+
+--! finish_key_rotation
+SELECT finish_key_rotation(:vault_id, :key_version);
+-- NULL if the user isn't a member of the vault.
+--! lock_key_version : (key_version?)
+SELECT lock_key_version(:vault_id) AS key_version;
@@ -0,0 +1,676 @@
+// Runs the migrations into a scratch database, creates two tenants and then
+// checks that as the application role tenant 1 can't read or change
+// anything that belongs to tenant 2, or to an environment it can't see.
+//
+// DATABASE_URL needs to be a superuser on a local Postgres i.e. the
+// postgres user in our dev container.
+use db::queries;
+use std::{env, fs, str::FromStr};
+use tokio_postgres::{error::SqlState, Client, Config, NoTls};
+
+const TEST_DATABASE: &str = "cloak_tenant_isolation";
+
+// Every statement uses $tenant which gets replaced with the tenant id.
+// Each tenant gets exactly one row per table, so in a fresh database the
+// ids match the tenant.
+const SEED: &str = "
+    INSERT INTO users (email, master_password_hash, protected_symmetric_key,
+        protected_ecdsa_private_key, ecdsa_public_key, protected_ecdh_private_key, ecdh_public_key)
+    VALUES ('user@tenant$tenant.com', 'hash', 'key', 'key', 'key', 'key', 'key');
+    INSERT INTO organisations (name, created_by_user_id) VALUES ('Tenant $tenant', $tenant);
+    INSERT INTO organisation_users (user_id, organisation_id, roles)
+    VALUES ($tenant, $tenant, '{Administrator}');
+    INSERT INTO team_roles (organisation_id, name, permissions)
+    VALUES ($tenant, 'Auditor', '{ViewAudit}');
+    INSERT INTO invitations (organisation_id, email, first_name, last_name, roles,
+        invitation_selector, invitation_verifier_hash)
+    VALUES ($tenant, 'invited@tenant$tenant.com', 'Invited', 'User', '{Collaborator}',
+        'selector$tenant', 'hash');
+    INSERT INTO vaults (organisation_id, name) VALUES ($tenant, 'Vault');
+    INSERT INTO users_vaults (user_id, vault_id, ecdh_public_key, encrypted_vault_key, role)
+    VALUES ($tenant, $tenant, 'key', 'key', 'Admin');
+    INSERT INTO environments (vault_id, name) VALUES ($tenant, 'Development');
+    INSERT INTO users_environments (environment_id, user_id) VALUES ($tenant, $tenant);
+    INSERT INTO secrets (vault_id, environment_id, name, secret, name_blind_index)
+    VALUES ($tenant, $tenant, 'name', 'secret', 'index');
+    INSERT INTO service_accounts (organisation_id, vault_id, environment_id, name,
+        encrypted_ecdh_private_key, ecdh_public_key)
+    VALUES ($tenant, $tenant, $tenant, 'Service Account', 'key', 'service-account-key-$tenant');
+    INSERT INTO service_account_secrets (service_account_id, name, secret, name_blind_index, ecdh_public_key,
+        service_account_ecdh_public_key)
+    VALUES ($tenant, 'name', 'secret', 'index', 'key', 'service-account-key-$tenant');
+    INSERT INTO service_account_challenges (service_account_id, nonce) VALUES ($tenant, 'nonce');
+    INSERT INTO audit_trail (user_id, organisation_id, access_type, action, description)
+    VALUES ($tenant, $tenant, 'Web', 'CreateVault', 'Created a vault');
+    INSERT INTO audit_trail_archive (id, user_id, organisation_id, access_type, action, description,
+        sequence, hash, created_at)
+    VALUES (1000 + $tenant, $tenant, $tenant, 'Web', 'CreateVault', 'Archived', 0, 'hash', NOW());
+    INSERT INTO audit_trail_checkpoints (organisation_id, sequence, hash) VALUES ($tenant, 0, 'hash');
+    INSERT INTO api_keys (user_id, organisation_id, name, api_key_hash)
+    VALUES ($tenant, $tenant, 'Key', 'hash$tenant');
+    INSERT INTO sessions (session_verifier, user_id, otp_code_encrypted)
+    VALUES ('verifier$tenant', $tenant, 'code');
+";
+
+struct Table {
+    name: &'static str,
+    // Selects the rows of a tenant.
+    rows: &'static str,
+    // A no-op change, we only care whether the row can be reached.
+    update: &'static str,
+    // Creates a row that belongs to the tenant.
+    insert: &'static str,
+    // Can a user see their own rows? Some tables are only for service accounts
+    // or the authentication role.
+    visible_to_owner: bool,
+}
+
+// roles_permissions and schema_migrations aren't tenant data so they're not here.
+const TABLES: &[Table] = &[
+    Table {
+        name: "users",
+        rows: "id = $tenant",
+        update: "first_name = first_name",
+        insert: "INSERT INTO users (email, master_password_hash, protected_symmetric_key,
+            protected_ecdsa_private_key, ecdsa_public_key, protected_ecdh_private_key, ecdh_public_key)
+            VALUES ('intruder@tenant$tenant.com', 'hash', 'key', 'key', 'key', 'key', 'key')",
+        visible_to_owner: true,
+    },
+    Table {
+        name: "organisations",
+        rows: "id = $tenant",
+        update: "name = name",
+        insert: "INSERT INTO organisations (name, created_by_user_id) VALUES ('Intruder', $tenant)",
+        visible_to_owner: true,
+    },
+    Table {
+        name: "organisation_users",
+        rows: "organisation_id = $tenant",
+        update: "roles = roles",
+        insert: "INSERT INTO organisation_users (user_id, organisation_id, roles)
+            VALUES (1, $tenant, '{Administrator}')",
+        visible_to_owner: true,
+    },
+    Table {
+        name: "invitations",
+        rows: "organisation_id = $tenant",
+        update: "first_name = first_name",
+        insert: "INSERT INTO invitations (organisation_id, email, first_name, last_name, roles,
+            invitation_selector, invitation_verifier_hash)
+            VALUES ($tenant, 'intruder@tenant1.com', 'Intruder', 'User', '{Administrator}',
+            'intruder', 'hash')",
+        visible_to_owner: true,
+    },
+    Table {
+        name: "vaults",
+        rows: "organisation_id = $tenant",
+        update: "name = name",
+        insert: "INSERT INTO vaults (organisation_id, name) VALUES ($tenant, 'Intruder')",
+        visible_to_owner: true,
+    },
+    Table {
+        name: "users_vaults",
+        rows: "vault_id = $tenant",
+        update: "encrypted_vault_key = encrypted_vault_key",
+        insert: "INSERT INTO users_vaults (user_id, vault_id, ecdh_public_key, encrypted_vault_key, role)
+            VALUES (1, $tenant, 'key', 'key', 'Admin')",
+        visible_to_owner: true,
+    },
+    Table {
+        name: "environments",
+        rows: "vault_id = $tenant",
+        update: "name = name",
+        insert: "INSERT INTO environments (vault_id, name) VALUES ($tenant, 'Intruder')",
+        visible_to_owner: true,
+    },
+    Table {
+        name: "users_environments",
+        rows: "environment_id = $tenant",
+        update: "user_id = user_id",
+        insert: "INSERT INTO users_environments (environment_id, user_id) VALUES ($tenant, 1)",
+        visible_to_owner: true,
+    },
+    Table {
+        name: "secrets",
+        rows: "vault_id = $tenant",
+        update: "name = name",
+        insert: "INSERT INTO secrets (vault_id, environment_id, name, secret, name_blind_index)
+            VALUES ($tenant, $tenant, 'name', 'secret', 'intruder')",
+        visible_to_owner: true,
+    },
+    Table {
+        name: "secret_versions",
+        rows: "secret_id = $tenant",
+        update: "version = version",
+        insert: "INSERT INTO secret_versions (secret_id, version, name, secret)
+            VALUES ($tenant, 100, 'name', 'secret')",
+        visible_to_owner: true,
+    },
+    Table {
+        name: "service_accounts",
+        rows: "organisation_id = $tenant",
+        update: "name = name",
+        insert: "INSERT INTO service_accounts (organisation_id, name, encrypted_ecdh_private_key, ecdh_public_key)
+            VALUES ($tenant, 'Intruder', 'key', 'intruder-service-account-key')",
+        visible_to_owner: true,
+    },
+    Table {
+        name: "service_account_secrets",
+        rows: "service_account_id = $tenant",
+        update: "name = name",
+        insert: "INSERT INTO service_account_secrets (service_account_id, name, secret, name_blind_index, ecdh_public_key,
+                service_account_ecdh_public_key)
+            VALUES ($tenant, 'name', 'secret', 'intruder', 'key', 'intruder-service-account-key')",
+        visible_to_owner: true,
+    },
+    Table {
+        name: "service_account_challenges",
+        rows: "service_account_id = $tenant",
+        update: "nonce = nonce",
+        insert: "INSERT INTO service_account_challenges (service_account_id, nonce)
+            VALUES ($tenant, 'intruder')",
+        visible_to_owner: false,
+    },
+    Table {
+        name: "audit_trail",
+        rows: "organisation_id = $tenant",
+        update: "description = description",
+        insert: "INSERT INTO audit_trail (user_id, organisation_id, access_type, action, description)
+            VALUES (1, $tenant, 'Web', 'CreateVault', 'Intruder')",
+        visible_to_owner: true,
+    },
+    Table {
+        name: "audit_trail_archive",
+        rows: "organisation_id = $tenant",
+        update: "description = description",
+        insert: "INSERT INTO audit_trail_archive (id, user_id, organisation_id, access_type, action,
+            description, sequence, hash, created_at)
+            VALUES (2000, 1, $tenant, 'Web', 'CreateVault', 'Intruder', 0, 'hash', NOW())",
+        visible_to_owner: true,
+    },
+    Table {
+        name: "audit_trail_checkpoints",
+        rows: "organisation_id = $tenant",
+        update: "hash = hash",
+        insert: "INSERT INTO audit_trail_checkpoints (organisation_id, sequence, hash)
+            VALUES ($tenant, 0, 'intruder')",
+        visible_to_owner: true,
+    },
+    // Filled by a trigger on the audit trail and only read by the forwarder.
+    Table {
+        name: "audit_outbox",
+        rows: "organisation_id = $tenant",
+        update: "attempts = attempts",
+        insert: "INSERT INTO audit_outbox (audit_id, organisation_id) VALUES (1, $tenant)",
+        visible_to_owner: false,
+    },
+    Table {
+        name: "team_roles",
+        rows: "organisation_id = $tenant",
+        update: "name = name",
+        insert: "INSERT INTO team_roles (organisation_id, name, permissions)
+            VALUES ($tenant, 'Intruder', '{ManageTeam}')",
+        visible_to_owner: true,
+    },
+    Table {
+        name: "api_keys",
+        rows: "organisation_id = $tenant",
+        update: "name = name",
+        insert: "INSERT INTO api_keys (user_id, organisation_id, name, api_key_hash)
+            VALUES (1, $tenant, 'Intruder', 'intruder')",
+        visible_to_owner: true,
+    },
+    Table {
+        name: "sessions",
+        rows: "user_id = $tenant",
+        update: "otp_code_attempts = otp_code_attempts",
+        insert: "INSERT INTO sessions (session_verifier, user_id, otp_code_encrypted)
+            VALUES ('intruder', $tenant, 'code')",
+        visible_to_owner: false,
+    },
+];
+
+// Tenant 1 also has an environment the user hasn't been given access to,
+// like a contractor that should only see Development.
+const RESTRICTED_SEED: &str = "
+    INSERT INTO environments (vault_id, name) VALUES (1, 'Production');
+    INSERT INTO secrets (vault_id, environment_id, name, secret, name_blind_index)
+    VALUES (1, 3, 'name', 'secret', 'production');
+";
+
+const RESTRICTED_TABLES: &[Table] = &[
+    Table {
+        name: "environments",
+        rows: "id = 3",
+        update: "name = name",
+        insert: "INSERT INTO users_environments (environment_id, user_id) VALUES (3, 1)",
+        visible_to_owner: false,
+    },
+    Table {
+        name: "secrets",
+        rows: "environment_id = 3",
+        update: "secret = secret",
+        insert: "INSERT INTO secrets (vault_id, environment_id, name, secret, name_blind_index)
+            VALUES (1, 3, 'name', 'secret', 'intruder')",
+        visible_to_owner: false,
+    },
+    Table {
+        name: "secret_versions",
+        rows: "secret_id = 3",
+        update: "version = version",
+        insert: "INSERT INTO secret_versions (secret_id, version, name, secret)
+            VALUES (3, 100, 'name', 'secret')",
+        visible_to_owner: false,
+    },
+];
+
+// Tenant 1 can also read a vault where it's only a Viewer.
+const VIEWER_SEED: &str = "
+    INSERT INTO vaults (id, organisation_id, name) VALUES (100, 1, 'Shared');
+    INSERT INTO users_vaults (user_id, vault_id, ecdh_public_key, encrypted_vault_key, role)
+    VALUES (1, 100, 'key', 'key', 'Viewer');
+    INSERT INTO environments (id, vault_id, name) VALUES (100, 100, 'Development');
+    INSERT INTO users_environments (environment_id, user_id) VALUES (100, 1);
+    INSERT INTO secrets (id, vault_id, environment_id, name, secret, name_blind_index)
+    VALUES (100, 100, 100, 'name', 'secret', 'shared');
+    INSERT INTO service_accounts (id, organisation_id, vault_id, environment_id, name,
+        encrypted_ecdh_private_key, ecdh_public_key)
+    VALUES (100, 1, 100, 100, 'Shared Service Account', 'key', 'service-account-key-100');
+    INSERT INTO service_account_secrets (service_account_id, name, secret, name_blind_index,
+        ecdh_public_key, service_account_ecdh_public_key)
+    VALUES (100, 'name', 'secret', 'shared', 'key', 'service-account-key-100');
+";
+
+// None of which a Viewer is allowed to do.
+const VIEWER_WRITES: &[&str] = &[
+    "UPDATE secrets SET secret = secret WHERE vault_id = 100",
+    "DELETE FROM secrets WHERE vault_id = 100",
+    "INSERT INTO secrets (vault_id, environment_id, name, secret, name_blind_index)
+        VALUES (100, 100, 'name', 'secret', 'viewer')",
+    "INSERT INTO secret_versions (secret_id, version, name, secret) VALUES (100, 100, 'name', 'secret')",
+    "INSERT INTO environments (vault_id, name) VALUES (100, 'Intruder')",
+    "UPDATE environments SET name = name WHERE vault_id = 100",
+    "DELETE FROM environments WHERE vault_id = 100",
+    "INSERT INTO service_account_secrets (service_account_id, name, secret, name_blind_index,
+        ecdh_public_key, service_account_ecdh_public_key)
+        VALUES (100, 'name', 'secret', 'viewer', 'key', 'service-account-key-100')",
+    "UPDATE service_account_secrets SET secret = secret WHERE service_account_id = 100",
+    "DELETE FROM service_account_secrets WHERE service_account_id = 100",
+    "UPDATE users_vaults SET role = 'Admin' WHERE vault_id = 100",
+    "DELETE FROM vaults WHERE id = 100",
+    "UPDATE vaults SET key_version = key_version + 1 WHERE id = 100",
+    "UPDATE secret_versions SET key_version = 2
+        WHERE secret_id IN (SELECT id FROM secrets WHERE vault_id = 100)",
+    "UPDATE users_vaults SET key_version = 2 WHERE vault_id = 100",
+    "SELECT finish_key_rotation(100, 2)",
+];
+
+// Who tenant 1 is when it talks to the database.
+#[derive(Clone, Copy, Debug)]
+enum Identity {
+    User,
+    ServiceAccount,
+}
+
+enum Outcome {
+    Rows(u64),
+    Denied,
+    Failed(String),
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn tenants_are_isolated() {
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL not set");
+    let admin_config = Config::from_str(&database_url).expect("DATABASE_URL is not valid");
+    let admin = connect(&admin_config).await;
+
+    // Each on its own as they can't run in the implicit transaction of a batch.
+    admin
+        .batch_execute(&format!(
+            "DROP DATABASE IF EXISTS {TEST_DATABASE} WITH (FORCE)"
+        ))
+        .await
+        .expect("Couldn't drop the old test database");
+    admin
+        .batch_execute(&format!("CREATE DATABASE {TEST_DATABASE}"))
+        .await
+        .expect("Couldn't create the test database");
+
+    let mut test_config = admin_config.clone();
+    test_config.dbname(TEST_DATABASE);
+    let mut client = connect(&test_config).await;
+
+    migrate(&client).await;
+    for tenant in ["1", "2"] {
+        client
+            .batch_execute(&SEED.replace("$tenant", tenant))
+            .await
+            .expect("Couldn't seed the tenants");
+    }
+    client
+        .batch_execute(RESTRICTED_SEED)
+        .await
+        .expect("Couldn't seed the restricted environment");
+
+    let mut failures = Vec::new();
+    for table in TABLES {
+        check_table(&mut client, table, Identity::User, &mut failures).await;
+    }
+    // The API connects service accounts by their public key rather than a user.
+    for table in TABLES
+        .iter()
+        .filter(|table| table.name.starts_with("service_account"))
+    {
+        check_table(&mut client, table, Identity::ServiceAccount, &mut failures).await;
+    }
+    for table in RESTRICTED_TABLES {
+        check_table(&mut client, table, Identity::User, &mut failures).await;
+    }
+    check_new_environment(&mut client, &mut failures).await;
+    check_write_after_rotation(&mut client, &mut failures).await;
+    // After the checks above so they still find one vault per tenant.
+    client
+        .batch_execute(VIEWER_SEED)
+        .await
+        .expect("Couldn't seed the shared vault");
+    check_viewer(&mut client, &mut failures).await;
+    check_delete_organisation(&mut client, &mut failures).await;
+
+    drop(client);
+    admin
+        .batch_execute(&format!("DROP DATABASE {TEST_DATABASE} WITH (FORCE)"))
+        .await
+        .expect("Couldn't drop the test database");
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+async fn connect(config: &Config) -> Client {
+    let (client, connection) = config
+        .connect(NoTls)
+        .await
+        .expect("Couldn't connect to the database");
+    // Any errors also show up on the client so we don't need these.
+    tokio::spawn(connection);
+    client
+}
+
+// Runs the up part of each dbmate migration in order.
+async fn migrate(client: &Client) {
+    let folder = concat!(env!("CARGO_MANIFEST_DIR"), "/migrations");
+    let mut migrations: Vec<_> = fs::read_dir(folder)
+        .expect("Couldn't read the migrations")
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    migrations.sort();
+
+    // dbmate creates this and the first migration grants access to it.
+    client
+        .batch_execute("CREATE TABLE schema_migrations (version VARCHAR PRIMARY KEY)")
+        .await
+        .unwrap();
+
+    for migration in migrations {
+        let sql = fs::read_to_string(&migration).unwrap();
+        let up = sql
+            .split("-- migrate:down")
+            .next()
+            .unwrap()
+            .replace("-- migrate:up", "");
+        client
+            .batch_execute(&up)
+            .await
+            .unwrap_or_else(|e| panic!("{} failed: {}", migration.display(), message(&e)));
+    }
+}
+
+async fn check_table(
+    client: &mut Client,
+    table: &Table,
+    identity: Identity,
+    failures: &mut Vec<String>,
+) {
+    let name = table.name;
+    let theirs = table.rows.replace("$tenant", "2");
+
+    if table.visible_to_owner && matches!(identity, Identity::User) {
+        // Otherwise a broken setup would pass by not seeing anything at all.
+        let ours = table.rows.replace("$tenant", "1");
+        let sql = format!("SELECT COUNT(*) FROM {name} WHERE {ours}");
+        match as_tenant_one(client, identity, &sql).await {
+            Outcome::Rows(1) => {}
+            Outcome::Rows(n) => failures.push(format!("{name}: tenant sees {n} of its own rows")),
+            Outcome::Denied => failures.push(format!("{name}: tenant can't see its own rows")),
+            Outcome::Failed(e) => failures.push(format!("{name}: {e}")),
+        }
+    }
+
+    let statements = [
+        (
+            "SELECT",
+            format!("SELECT COUNT(*) FROM {name} WHERE {theirs}"),
+        ),
+        (
+            "UPDATE",
+            format!("UPDATE {name} SET {} WHERE {theirs}", table.update),
+        ),
+        ("DELETE", format!("DELETE FROM {name} WHERE {theirs}")),
+    ];
+    for (command, sql) in statements {
+        match as_tenant_one(client, identity, &sql).await {
+            Outcome::Rows(0) | Outcome::Denied => {}
+            Outcome::Rows(n) => failures.push(format!(
+                "{name}: {identity:?} {command} reached {n} rows it shouldn't"
+            )),
+            Outcome::Failed(e) => failures.push(format!("{name}: {identity:?} {command} {e}")),
+        }
+    }
+
+    let sql = table.insert.replace("$tenant", "2");
+    match as_tenant_one(client, identity, &sql).await {
+        Outcome::Denied => {}
+        Outcome::Rows(_) => failures.push(format!(
+            "{name}: {identity:?} INSERT created a row it shouldn't"
+        )),
+        Outcome::Failed(e) => failures.push(format!("{name}: {identity:?} INSERT {e}")),
+    }
+}
+
+async fn check_viewer(client: &mut Client, failures: &mut Vec<String>) {
+    let sql = "SELECT COUNT(*) FROM secrets WHERE vault_id = 100";
+    match as_tenant_one(client, Identity::User, sql).await {
+        Outcome::Rows(1) => {}
+        Outcome::Rows(n) => failures.push(format!("viewer: sees {n} secrets")),
+        Outcome::Denied => failures.push("viewer: can't see the secrets".to_string()),
+        Outcome::Failed(e) => failures.push(format!("viewer: {e}")),
+    }
+
+    for sql in VIEWER_WRITES {
+        match as_tenant_one(client, Identity::User, sql).await {
+            Outcome::Rows(0) | Outcome::Denied => {}
+            Outcome::Rows(n) => failures.push(format!("viewer: {sql} reached {n} rows")),
+            Outcome::Failed(e) => failures.push(format!("viewer: {sql} {e}")),
+        }
+    }
+}
+
+// Deleting a team has to take its audit trail with it, nothing cascades from
+// organisations to the audit tables.
+async fn check_delete_organisation(client: &mut Client, failures: &mut Vec<String>) {
+    let transaction = client.transaction().await.unwrap();
+    transaction
+        .batch_execute(
+            "SET LOCAL ROLE application;
+            SELECT set_config('row_level_security.user_id', '1', true);",
+        )
+        .await
+        .unwrap();
+    match transaction
+        .query_one("SELECT delete_organisation(1)", &[])
+        .await
+    {
+        Ok(row) if row.get::<_, i32>(0) == 1 => {}
+        Ok(_) => failures.push("delete organisation: the owner can't delete it".to_string()),
+        Err(e) => {
+            failures.push(format!("delete organisation: {}", message(&e)));
+            return;
+        }
+    }
+
+    // Row level security would hide what's left, so count as ourselves.
+    transaction.batch_execute("RESET ROLE").await.unwrap();
+    for table in [
+        "audit_trail",
+        "audit_outbox",
+        "audit_trail_archive",
+        "audit_trail_checkpoints",
+    ] {
+        let sql = format!("SELECT COUNT(*) FROM {table} WHERE organisation_id = 1");
+        let left: i64 = transaction.query_one(&sql, &[]).await.unwrap().get(0);
+        if left > 0 {
+            failures.push(format!("delete organisation: {left} rows left in {table}"));
+        }
+    }
+    transaction.rollback().await.unwrap();
+}
+
+// The denials above would also pass if nobody could create anything, so check
+// a vault Admin can create an environment and then see it. Like the
+// application we look it up afterwards, the trigger that grants access to the
+// creator hasn't run when RETURNING is checked.
+async fn check_new_environment(client: &mut Client, failures: &mut Vec<String>) {
+    let insert = "INSERT INTO environments (vault_id, name) VALUES (1, 'Created')";
+    let sql = "SELECT COUNT(*) FROM environments WHERE vault_id = 1 AND name = 'Created'";
+    match as_tenant_one_after(client, Identity::User, Some(insert), sql).await {
+        Outcome::Rows(1) => {}
+        Outcome::Rows(n) => failures.push(format!("new environment: sees {n} rows")),
+        Outcome::Denied => failures.push("new environment: can't create it".to_string()),
+        Outcome::Failed(e) => failures.push(format!("new environment: {e}")),
+    }
+}
+
+// A browser that loaded the vault before its key was rotated still holds the
+// old key. The handlers compare the version it sends with the vault's, and an
+// edit it started before the rotation conflicts, otherwise nobody could
+// decrypt what it writes.
+async fn check_write_after_rotation(client: &mut Client, failures: &mut Vec<String>) {
+    let transaction = client.transaction().await.unwrap();
+    transaction
+        .batch_execute(
+            "SET LOCAL ROLE application;
+            SELECT set_config('row_level_security.user_id', '1', true);",
+        )
+        .await
+        .unwrap();
+
+    let secret = queries::secrets::get()
+        .bind(&transaction, &1)
+        .one()
+        .await
+        .unwrap();
+    let key_version = queries::vaults::start_key_rotation()
+        .bind(&transaction, &1, &1)
+        .one()
+        .await
+        .unwrap();
+    queries::secrets::reencrypt()
+        .bind(
+            &transaction,
+            &secret.name.as_str(),
+            &"reencrypted",
+            &key_version,
+            &secret.id,
+            &1,
+        )
+        .await
+        .unwrap();
+
+    match queries::vaults::lock_key_version()
+        .bind(&transaction, &1)
+        .one()
+        .await
+    {
+        Ok(Some(current)) if current == key_version => {}
+        Ok(current) => failures.push(format!(
+            "write after rotation: the vault is on key {current:?} not {key_version}"
+        )),
+        Err(e) => failures.push(format!("write after rotation: {}", message(&e))),
+    }
+
+    match queries::secrets::update_secret()
+        .bind(
+            &transaction,
+            &"stale",
+            &1,
+            &secret.id,
+            &secret.updated_at.as_str(),
+        )
+        .await
+    {
+        Ok(0) => {}
+        Ok(_) => failures.push("write after rotation: an edit with the old key was saved".into()),
+        Err(e) => failures.push(format!("write after rotation: {}", message(&e))),
+    }
+    transaction.rollback().await.unwrap();
+}
+
+// Runs the statement the same way the application does and always rolls back
+// so every check starts from the seeded data.
+async fn as_tenant_one(client: &mut Client, identity: Identity, sql: &str) -> Outcome {
+    as_tenant_one_after(client, identity, None, sql).await
+}
+
+// As above but runs `before` in the same transaction first.
+async fn as_tenant_one_after(
+    client: &mut Client,
+    identity: Identity,
+    before: Option<&str>,
+    sql: &str,
+) -> Outcome {
+    let transaction = client.transaction().await.unwrap();
+    let (setting, value) = match identity {
+        Identity::User => ("row_level_security.user_id", "1"),
+        Identity::ServiceAccount => (
+            "row_level_security.ecdh_public_key",
+            "service-account-key-1",
+        ),
+    };
+    transaction
+        .batch_execute("SET LOCAL ROLE application")
+        .await
+        .unwrap();
+    transaction
+        .query("SELECT set_config($1, $2, true)", &[&setting, &value])
+        .await
+        .unwrap();
+
+    let before = match before {
+        Some(before) => transaction.execute(before, &[]).await,
+        None => Ok(0),
+    };
+
+    let result = if let Err(e) = before {
+        Err(e)
+    } else if sql.starts_with("SELECT") {
+        transaction
+            .query_one(sql, &[])
+            .await
+            .map(|row| row.get::<_, i64>(0) as u64)
+    } else {
+        transaction.execute(sql, &[]).await
+    };
+
+    match result {
+        Ok(rows) => Outcome::Rows(rows),
+        // Both missing grants and row level security violations end up here.
+        Err(e) if e.code() == Some(&SqlState::INSUFFICIENT_PRIVILEGE) => Outcome::Denied,
+        Err(e) => Outcome::Failed(message(&e)),
+    }
+}
+
+fn message(e: &tokio_postgres::Error) -> String {
+    match e.as_db_error() {
+        Some(db_error) => db_error.message().to_string(),
+        None => e.to_string(),
+    }
+}
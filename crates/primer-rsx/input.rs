@@ -6,6 +6,8 @@ pub enum InputType {
     Text,
     Email,
     Password,
+    Number,
+    Date,
 }
 
 impl InputType {
@@ -14,6 +16,8 @@ impl InputType {
             InputType::Text => "text",
             InputType::Email => "email",
             InputType::Password => "password",
+            InputType::Number => "number",
+            InputType::Date => "date",
         }
     }
 }
@@ -3,8 +3,9 @@ use dioxus::prelude::*;
 
 #[derive(Props)]
 pub struct PaginationProps<'a> {
-    next_page_url: Option<&'a str>,
-    prev_page_url: Option<&'a str>,
+    // None when there's no page in that direction.
+    next_page_url: &'a Option<String>,
+    prev_page_url: &'a Option<String>,
 }
 
 pub fn Pagination<'a>(cx: Scope<'a, PaginationProps<'a>>) -> Element {
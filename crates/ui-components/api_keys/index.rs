@@ -0,0 +1,91 @@
+use crate::cloak_layout::{CloakLayout, SideBar};
+use assets::files::{button_plus_svg, empty_api_keys_svg};
+use db::ApiKey;
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+#[derive(Props, PartialEq)]
+struct ApiKeysProps {
+    organisation_id: i32,
+    api_keys: Vec<ApiKey>,
+    new_api_key: Option<String>,
+}
+
+pub fn index(organisation_id: i32, api_keys: Vec<ApiKey>, new_api_key: Option<String>) -> String {
+    fn app(cx: Scope<ApiKeysProps>) -> Element {
+        cx.render(rsx! {
+
+            CloakLayout {
+                selected_item: SideBar::ApiKeys,
+                team_id: cx.props.organisation_id,
+                title: "API Keys"
+                header: cx.render(rsx!(
+                    h3 { "API Keys" }
+
+                    if ! cx.props.api_keys.is_empty() {
+                        cx.render(rsx! {
+                            Button {
+                                prefix_image_src: "{button_plus_svg.name}",
+                                drawer_trigger: super::new_api_key::DRAW_TRIGGER,
+                                button_scheme: ButtonScheme::Primary,
+                                "Create A New API Key"
+                            }
+                        })
+                    } else {
+                        None
+                    }
+                ))
+
+                if let Some(api_key) = &cx.props.new_api_key {
+                    cx.render(rsx! {
+                        Alert {
+                            alert_color: AlertColor::Success,
+                            class: "mb-3",
+                            h4 {
+                                "Copy your new API key now, you won't be able to see it again."
+                            }
+                            code {
+                                "{api_key}"
+                            }
+                        }
+                    })
+                } else {
+                    None
+                }
+
+                if cx.props.api_keys.is_empty() {
+                    cx.render(rsx! {
+                        BlankSlate {
+                            heading: "You don't have any API keys yet",
+                            visual: empty_api_keys_svg.name,
+                            description: "API keys let your automation call the API as you.",
+                            primary_action_drawer: ("Create A New API Key", super::new_api_key::DRAW_TRIGGER)
+                        }
+                    })
+                } else {
+                    cx.render(rsx! {
+                        super::table::ApiKeyTable {
+                            api_keys: &cx.props.api_keys,
+                            organisation_id: cx.props.organisation_id
+                        }
+                    })
+                }
+
+                super::new_api_key::NewApiKeyForm {
+                    submit_action: crate::routes::api_keys::new_route(cx.props.organisation_id)
+                }
+            }
+        })
+    }
+
+    let mut app = VirtualDom::new_with_props(
+        app,
+        ApiKeysProps {
+            organisation_id,
+            api_keys,
+            new_api_key,
+        },
+    );
+    let _ = app.rebuild();
+    dioxus::ssr::render_vdom(&app)
+}
@@ -0,0 +1,4 @@
+pub mod index;
+pub mod new_api_key;
+pub mod revoke;
+pub mod table;
@@ -0,0 +1,71 @@
+#![allow(non_snake_case)]
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+pub static DRAW_TRIGGER: &str = "new-api-key-drawer";
+
+#[derive(Props, PartialEq, Eq)]
+pub struct NewApiKeyFormProps {
+    submit_action: String,
+}
+
+pub fn NewApiKeyForm(cx: Scope<NewApiKeyFormProps>) -> Element {
+    cx.render(rsx! {
+        form {
+            method: "post",
+            action: "{cx.props.submit_action}",
+            Drawer {
+                label: "Add API Key",
+                trigger_id: DRAW_TRIGGER,
+                DrawerBody {
+                    div {
+                        class: "d-flex flex-column",
+                        Alert {
+                            alert_color: AlertColor::Success,
+                            class: "mb-3",
+                            "An API key has the same access as you do in this team, and only
+                            works here. Set it as the api-key header when calling the API
+                            from scripts or pipelines."
+                        }
+                        Input {
+                            input_type: InputType::Text,
+                            help_text: "Give your API key a name"
+                            required: true,
+                            label: "Name",
+                            name: "name"
+                        }
+                        Select {
+                            name: "expires_in_days",
+                            label: "Expiry",
+                            help_text: "When should the key stop working?",
+                            value: "90",
+                            option {
+                                value: "30",
+                                "30 days"
+                            }
+                            option {
+                                value: "90",
+                                "90 days"
+                            }
+                            option {
+                                value: "365",
+                                "1 year"
+                            }
+                            option {
+                                value: "0",
+                                "Never"
+                            }
+                        }
+                    }
+                }
+                DrawerFooter {
+                    Button {
+                        button_type: ButtonType::Submit,
+                        button_scheme: ButtonScheme::Primary,
+                        "Create API Key"
+                    }
+                }
+            }
+        }
+    })
+}
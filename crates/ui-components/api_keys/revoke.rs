@@ -0,0 +1,50 @@
+#![allow(non_snake_case)]
+use db::ApiKey;
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+#[derive(Props, PartialEq)]
+pub struct DrawerProps<'a> {
+    organisation_id: i32,
+    api_key: &'a ApiKey,
+    trigger_id: String,
+}
+
+pub fn RevokeApiKeyDrawer<'a>(cx: Scope<'a, DrawerProps<'a>>) -> Element {
+    cx.render(rsx! {
+        Drawer {
+            submit_action: crate::routes::api_keys::revoke_route(cx.props.organisation_id),
+            label: "Revoke API Key ?",
+            trigger_id: &cx.props.trigger_id,
+            DrawerBody {
+                div {
+                    class: "d-flex flex-column",
+                    Alert {
+                        alert_color: AlertColor::Warn,
+                        class: "mb-3",
+                        h4 {
+                            "Are you sure you want to revoke "
+                            strong {
+                                "{cx.props.api_key.name}"
+                            }
+                            "?"
+                        }
+                        "Anything still using this key will no longer be able to call the API."
+                    }
+                    input {
+                        "type": "hidden",
+                        "name": "api_key_id",
+                        "value": "{cx.props.api_key.id}"
+                    }
+                }
+            }
+            DrawerFooter {
+                Button {
+                    button_type: ButtonType::Submit,
+                    button_scheme: ButtonScheme::Danger,
+                    "Revoke API Key"
+                }
+            }
+        }
+    })
+}
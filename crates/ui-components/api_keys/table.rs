@@ -0,0 +1,93 @@
+#![allow(non_snake_case)]
+use db::ApiKey;
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+#[derive(Props, PartialEq)]
+pub struct TableProps<'a> {
+    api_keys: &'a Vec<ApiKey>,
+    organisation_id: i32,
+}
+
+pub fn ApiKeyTable<'a>(cx: Scope<'a, TableProps<'a>>) -> Element {
+    cx.render(rsx!(
+        Box {
+            BoxHeader {
+                title: "Your API Keys"
+            }
+            BoxBody {
+                DataTable {
+                    table {
+                        thead {
+                            tr {
+                                th { "Name" }
+                                th { "Created" }
+                                th { "Expires" }
+                                th {
+                                    class: "text-right",
+                                    "Action"
+                                }
+                            }
+                        }
+                        tbody {
+                            cx.props.api_keys.iter().map(|api_key| rsx!(
+                                tr {
+                                    td {
+                                        strong {
+                                            "{api_key.name}"
+                                        }
+                                    }
+                                    td {
+                                        RelativeTime {
+                                            format: RelativeTimeFormat::Datetime,
+                                            datetime: &api_key.created_at
+                                        }
+                                    }
+                                    td {
+                                        if let Some(expires_at) = &api_key.expires_at {
+                                            cx.render(rsx!(
+                                                RelativeTime {
+                                                    format: RelativeTimeFormat::Relative,
+                                                    datetime: expires_at
+                                                }
+                                            ))
+                                        } else {
+                                            cx.render(rsx!(
+                                                Label {
+                                                    "Never"
+                                                }
+                                            ))
+                                        }
+                                    }
+                                    td {
+                                        class: "text-right",
+                                        DropDown {
+                                            direction: Direction::SouthWest,
+                                            button_text: "...",
+                                            DropDownLink {
+                                                drawer_trigger: format!("revoke-api-key-trigger-{}", api_key.id),
+                                                href: "#",
+                                                target: "_top",
+                                                "Revoke API Key"
+                                            }
+                                        }
+                                    }
+                                }
+                            ))
+                        }
+                    }
+                }
+                // Create all the revoke drawers
+                cx.props.api_keys.iter().map(|api_key| {
+                    cx.render(rsx!(
+                        super::revoke::RevokeApiKeyDrawer {
+                            organisation_id: cx.props.organisation_id,
+                            api_key: api_key,
+                            trigger_id: format!("revoke-api-key-trigger-{}", api_key.id),
+                        }
+                    ))
+                })
+            }
+        }
+    ))
+}
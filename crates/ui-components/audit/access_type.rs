@@ -32,5 +32,12 @@ pub fn AuditAccessType<'a>(cx: Scope<'a, Props<'a>>) -> Element {
                 "Web App"
             }
         )),
+        AuditAccessType::API => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Attention,
+                "API"
+            }
+        )),
     }
 }
@@ -95,5 +95,173 @@ pub fn AuditAction<'a>(cx: Scope<'a, Props<'a>>) -> Element {
                 "Remove Team Member"
             }
         )),
+        AuditAction::CreateApiKey => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Create API Key"
+            }
+        )),
+        AuditAction::RevokeApiKey => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Revoke API Key"
+            }
+        )),
+        AuditAction::RollbackSecret => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Rollback Secret"
+            }
+        )),
+        AuditAction::UpdateSecret => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Update Secret"
+            }
+        )),
+        AuditAction::ChangeEnvironmentAccess => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Change Environment Access"
+            }
+        )),
+        AuditAction::ChangeAuditRetention => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Change Audit Retention"
+            }
+        )),
+        AuditAction::ExportAuditTrail => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Export Audit Trail"
+            }
+        )),
+        AuditAction::AcceptInvite => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Accept Invite"
+            }
+        )),
+        AuditAction::RenameTeam => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Rename Team"
+            }
+        )),
+        AuditAction::CreateTeam => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Create Team"
+            }
+        )),
+        AuditAction::UpdateProfile => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Update Profile"
+            }
+        )),
+        AuditAction::CreateEnvironment => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Create Environment"
+            }
+        )),
+        AuditAction::RenameEnvironment => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Rename Environment"
+            }
+        )),
+        AuditAction::DeleteEnvironment => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Delete Environment"
+            }
+        )),
+        AuditAction::ShareSecrets => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Share Secrets"
+            }
+        )),
+        AuditAction::CreateRole => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Create Role"
+            }
+        )),
+        AuditAction::DeleteRole => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Delete Role"
+            }
+        )),
+        AuditAction::ChangeRole => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Change Role"
+            }
+        )),
+        AuditAction::TransferTeam => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Transfer Team"
+            }
+        )),
+        AuditAction::LeaveTeam => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Leave Team"
+            }
+        )),
+        AuditAction::RotateVaultKey => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Rotate Vault Key"
+            }
+        )),
+        AuditAction::RotateServiceAccountKey => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Rotate Service Account Key"
+            }
+        )),
+        AuditAction::RetireServiceAccountKey => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Retire Service Account Key"
+            }
+        )),
+        AuditAction::DeleteTeam => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                label_color: LabelColor::Done,
+                "Delete Team"
+            }
+        )),
     }
 }
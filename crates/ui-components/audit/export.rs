@@ -0,0 +1,62 @@
+#![allow(non_snake_case)]
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+pub static DRAW_TRIGGER: &str = "export-audit-drawer";
+
+#[derive(Props, PartialEq, Eq)]
+pub struct ExportDrawerProps {
+    submit_action: String,
+}
+
+pub fn ExportDrawer(cx: Scope<ExportDrawerProps>) -> Element {
+    cx.render(rsx! {
+        form {
+            method: "get",
+            "data-turbo": "false",
+            action: "{cx.props.submit_action}",
+            Drawer {
+                label: "Export",
+                trigger_id: DRAW_TRIGGER,
+                DrawerBody {
+                    div {
+                        class: "d-flex flex-column",
+                        Input {
+                            input_type: InputType::Date,
+                            required: true,
+                            label: "From",
+                            name: "from"
+                        }
+                        Input {
+                            input_type: InputType::Date,
+                            help_text: "Entries made on this day are included",
+                            required: true,
+                            label: "To",
+                            name: "to"
+                        }
+                        Select {
+                            label: "Format",
+                            help_text: "JSON Lines has one entry per line",
+                            name: "format",
+                            option {
+                                value: "csv",
+                                "CSV"
+                            }
+                            option {
+                                value: "jsonl",
+                                "JSON Lines"
+                            }
+                        }
+                    }
+                }
+                DrawerFooter {
+                    Button {
+                        button_type: ButtonType::Submit,
+                        button_scheme: ButtonScheme::Primary,
+                        "Download"
+                    }
+                }
+            }
+        }
+    })
+}
@@ -1,26 +1,85 @@
 #![allow(non_snake_case)]
-use db::Member;
+use db::{Member, Vault};
 use dioxus::prelude::*;
 use primer_rsx::*;
 
 pub static DRAW_TRIGGER: &str = "filter-audit-drawer";
 
+// What the audit trail is filtered by, 0 or empty for anything.
+#[derive(PartialEq, Eq, Clone, Default, Debug)]
+pub struct AuditFilter {
+    pub user: i32,
+    pub access_type: u32,
+    pub action: u32,
+    pub vault: i32,
+    pub target: i32,
+    pub from: String,
+    pub to: String,
+    pub search: String,
+}
+
+// The values match audit::filter::Filter in the server.
+const ACCESS_TYPES: &[(u32, &str)] = &[
+    (1, "User Interface"),
+    (2, "CLI"),
+    (3, "Service Account"),
+    (4, "API"),
+];
+
+const ACTIONS: &[(u32, &str)] = &[
+    (1, "Add Member"),
+    (2, "Delete Member"),
+    (3, "Add Secret"),
+    (4, "Delete Secret"),
+    (5, "Access Secret"),
+    (6, "New Service Account"),
+    (7, "Delete Service Account"),
+    (8, "Connect Service Account"),
+    (9, "Create Invite"),
+    (10, "Remove Team Member"),
+    (11, "Create Vault"),
+    (12, "Delete Vault"),
+    (13, "Create API Key"),
+    (14, "Revoke API Key"),
+    (15, "Rollback Secret"),
+    (16, "Update Secret"),
+    (17, "Change Environment Access"),
+    (18, "Change Audit Retention"),
+    (19, "Export Audit Trail"),
+    (20, "Accept Invite"),
+    (21, "Rename Team"),
+    (22, "Create Team"),
+    (23, "Update Profile"),
+    (24, "Create Environment"),
+    (25, "Rename Environment"),
+    (26, "Delete Environment"),
+    (27, "Share Secrets"),
+    (28, "Create Role"),
+    (29, "Delete Role"),
+    (30, "Change Role"),
+    (31, "Transfer Team"),
+    (32, "Leave Team"),
+    (33, "Rotate Vault Key"),
+    (34, "Rotate Service Account Key"),
+    (35, "Retire Service Account Key"),
+    (36, "Delete Team"),
+];
+
 #[derive(Props, PartialEq)]
 pub struct FilterDrawerProps {
     team_users: Vec<Member>,
-    organisation_id: i32,
-    reset_search: bool,
+    vaults: Vec<Vault>,
+    filter: AuditFilter,
     submit_action: String,
 }
 
 pub fn FilterDrawer(cx: Scope<FilterDrawerProps>) -> Element {
+    let filter = &cx.props.filter;
     cx.render(rsx! {
+        // A get so the filter ends up in the URL.
         form {
-            class: "remember",
-            method: "post",
+            method: "get",
             "data-turbo": "false",
-            "data-remember-reset": "{cx.props.reset_search}",
-            "data-remember-name": "audit",
             id: "filter-form",
             action: "{cx.props.submit_action}",
 
@@ -39,36 +98,64 @@ pub fn FilterDrawer(cx: Scope<FilterDrawerProps>) -> Element {
                                 value: "0",
                                 "Any"
                             }
-                            cx.props.team_users.iter().map(|user| {
-                                cx.render(rsx! {
-                                    option {
-                                        value: "{user.id}",
-                                        "{user.email}"
-                                    }
-                                })
-                            })
+                            cx.props.team_users.iter().map(|user| rsx!(
+                                FilterOption {
+                                    value: user.id as i64,
+                                    label: user.email.clone(),
+                                    selected: user.id == filter.user
+                                }
+                            ))
                         }
 
                         Select {
-                            label: "Access Type",
-                            help_text: "Split between user interface and CLI usage.",
-                            name: "access_type",
+                            label: "Vault",
+                            help_text: "Which vault was the action carried out on",
+                            name: "vault",
                             option {
                                 value: "0",
                                 "Any"
                             }
+                            cx.props.vaults.iter().map(|vault| rsx!(
+                                FilterOption {
+                                    value: vault.id as i64,
+                                    label: vault.name.clone(),
+                                    selected: vault.id == filter.vault
+                                }
+                            ))
+                        }
+
+                        Select {
+                            label: "Member",
+                            help_text: "Which member was the action carried out on",
+                            name: "target",
                             option {
-                                value: "1",
-                                "User Interface"
-                            }
-                            option {
-                                value: "2",
-                                "CLI"
+                                value: "0",
+                                "Any"
                             }
+                            cx.props.team_users.iter().map(|user| rsx!(
+                                FilterOption {
+                                    value: user.id as i64,
+                                    label: user.email.clone(),
+                                    selected: user.id == filter.target
+                                }
+                            ))
+                        }
+
+                        Select {
+                            label: "Access Type",
+                            help_text: "Split between user interface and CLI usage.",
+                            name: "access_type",
                             option {
-                                value: "3",
-                                "Service Account"
+                                value: "0",
+                                "Any"
                             }
+                            ACCESS_TYPES.iter().map(|(value, label)| rsx!(
+                                FilterOption {
+                                    value: *value as i64,
+                                    label: label.to_string(),
+                                    selected: *value == filter.access_type
+                                }
+                            ))
                         }
 
                         Select {
@@ -79,61 +166,35 @@ pub fn FilterDrawer(cx: Scope<FilterDrawerProps>) -> Element {
                                 value: "0",
                                 "Any"
                             }
-                            option {
-                                value: "1",
-                                "Add Member"
-                            }
-                            option {
-                                value: "2",
-                                "Delete Member"
-                            }
-                            option {
-                                value: "3",
-                                "Add Secret"
-                            }
-                            option {
-                                value: "4",
-                                "Delete Secret"
-                            }
-                            option {
-                                value: "5",
-                                "Access Secret"
-                            }
-                            option {
-                                value: "6",
-                                "New Service Account"
-                            }
-                            option {
-                                value: "7",
-                                "Delete Service Account"
-                            }
-                            option {
-                                value: "8",
-                                "Connect Service Account"
-                            }
-                            option {
-                                value: "9",
-                                "Create Invite"
-                            }
-                            option {
-                                value: "10",
-                                "Remove Team Member"
-                            }
-                            option {
-                                value: "11",
-                                "Create Vault"
-                            }
-                            option {
-                                value: "12",
-                                "Delete Vault"
-                            }
+                            ACTIONS.iter().map(|(value, label)| rsx!(
+                                FilterOption {
+                                    value: *value as i64,
+                                    label: label.to_string(),
+                                    selected: *value == filter.action
+                                }
+                            ))
+                        }
+
+                        Input {
+                            input_type: InputType::Date,
+                            label: "From",
+                            name: "from",
+                            value: &filter.from
+                        }
+
+                        Input {
+                            input_type: InputType::Date,
+                            help_text: "Entries made on this day are included",
+                            label: "To",
+                            name: "to",
+                            value: &filter.to
                         }
 
-                        input {
-                            "type": "hidden",
-                            name: "id",
-                            id: "last-row-id",
-                            value: "0"
+                        Input {
+                            label: "Description",
+                            help_text: "Only entries whose description contains this",
+                            name: "search",
+                            value: &filter.search
                         }
                     }
                 }
@@ -148,3 +209,30 @@ pub fn FilterDrawer(cx: Scope<FilterDrawerProps>) -> Element {
         }
     })
 }
+
+#[derive(Props, PartialEq, Eq)]
+struct FilterOptionProps {
+    value: i64,
+    label: String,
+    selected: bool,
+}
+
+// Shows what we're currently filtering by when the drawer opens.
+fn FilterOption(cx: Scope<FilterOptionProps>) -> Element {
+    if cx.props.selected {
+        cx.render(rsx! {
+            option {
+                value: "{cx.props.value}",
+                selected: "selected",
+                "{cx.props.label}"
+            }
+        })
+    } else {
+        cx.render(rsx! {
+            option {
+                value: "{cx.props.value}",
+                "{cx.props.label}"
+            }
+        })
+    }
+}
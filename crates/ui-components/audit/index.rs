@@ -1,17 +1,30 @@
 use crate::cloak_layout::{CloakLayout, SideBar};
-use db::{Audit, Member};
+use db::{Audit, AuditRetention, AuditRetentionSettings, Member, Vault};
 use dioxus::prelude::*;
 use primer_rsx::*;
 
 #[derive(Props, PartialEq)]
 pub struct AuditProps {
     team_users: Vec<Member>,
+    vaults: Vec<Vault>,
     audits: Vec<Audit>,
     organisation_id: i32,
-    reset_search: bool,
+    filter: super::filter::AuditFilter,
+    prev_page_url: Option<String>,
+    next_page_url: Option<String>,
+    retention: AuditRetentionSettings,
 }
 
-pub fn index(organisation_id: i32, team_users: Vec<Member>, audits: Vec<Audit>) -> String {
+pub fn index(
+    organisation_id: i32,
+    team_users: Vec<Member>,
+    vaults: Vec<Vault>,
+    audits: Vec<Audit>,
+    filter: super::filter::AuditFilter,
+    prev_page_url: Option<String>,
+    next_page_url: Option<String>,
+    retention: AuditRetentionSettings,
+) -> String {
     fn app(cx: Scope<AuditProps>) -> Element {
         cx.render(rsx! {
 
@@ -21,21 +34,60 @@ pub fn index(organisation_id: i32, team_users: Vec<Member>, audits: Vec<Audit>)
                 title: "Audit Trail"
                 header: cx.render(rsx!(
                     h3 { "Audit Trail" }
-                    Button {
-                        drawer_trigger: super::filter::DRAW_TRIGGER,
-                        button_scheme: ButtonScheme::Default,
-                        "Filter"
+                    div {
+                        class: "d-flex",
+                        form {
+                            class: "mr-2",
+                            method: "post",
+                            action: "{crate::routes::audit::verify_route(cx.props.organisation_id)}",
+                            Button {
+                                button_type: ButtonType::Submit,
+                                button_scheme: ButtonScheme::Default,
+                                "Verify"
+                            }
+                        }
+                        Button {
+                            class: "mr-2",
+                            drawer_trigger: super::export::DRAW_TRIGGER,
+                            button_scheme: ButtonScheme::Default,
+                            "Export"
+                        }
+                        Button {
+                            class: "mr-2",
+                            drawer_trigger: super::retention::DRAW_TRIGGER,
+                            button_scheme: ButtonScheme::Default,
+                            "Retention"
+                        }
+                        Button {
+                            drawer_trigger: super::filter::DRAW_TRIGGER,
+                            button_scheme: ButtonScheme::Default,
+                            "Filter"
+                        }
                     }
                 ))
                 super::table::AuditTable {
                     audits: &cx.props.audits
                 }
+                Pagination {
+                    prev_page_url: &cx.props.prev_page_url,
+                    next_page_url: &cx.props.next_page_url
+                }
                 super::filter::FilterDrawer {
                     team_users: cx.props.team_users.clone(),
-                    organisation_id: cx.props.organisation_id,
-                    reset_search: cx.props.reset_search,
+                    vaults: cx.props.vaults.clone(),
+                    filter: cx.props.filter.clone(),
                     submit_action: crate::routes::audit::index_route(cx.props.organisation_id)
                 }
+                super::export::ExportDrawer {
+                    submit_action: crate::routes::audit::export_route(cx.props.organisation_id)
+                }
+                super::retention::RetentionDrawer {
+                    days: cx.props.retention.audit_retention_days
+                        .map(|days| days.to_string())
+                        .unwrap_or_default(),
+                    purge: cx.props.retention.audit_retention == AuditRetention::Purge,
+                    submit_action: crate::routes::audit::retention_route(cx.props.organisation_id)
+                }
             }
         })
     }
@@ -44,9 +96,13 @@ pub fn index(organisation_id: i32, team_users: Vec<Member>, audits: Vec<Audit>)
         app,
         AuditProps {
             team_users,
+            vaults,
             audits,
-            reset_search: true,
             organisation_id,
+            filter,
+            prev_page_url,
+            next_page_url,
+            retention,
         },
     );
     let _ = app.rebuild();
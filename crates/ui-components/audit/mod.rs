@@ -1,5 +1,7 @@
 pub mod access_type;
 pub mod audit_action;
+pub mod export;
 pub mod filter;
 pub mod index;
+pub mod retention;
 pub mod table;
@@ -0,0 +1,80 @@
+#![allow(non_snake_case)]
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+pub static DRAW_TRIGGER: &str = "retention-audit-drawer";
+
+#[derive(Props, PartialEq, Eq)]
+pub struct RetentionDrawerProps {
+    // Empty when the audit trail is kept forever.
+    days: String,
+    purge: bool,
+    submit_action: String,
+}
+
+pub fn RetentionDrawer(cx: Scope<RetentionDrawerProps>) -> Element {
+    cx.render(rsx! {
+        form {
+            method: "post",
+            action: "{cx.props.submit_action}",
+            Drawer {
+                label: "Retention",
+                trigger_id: DRAW_TRIGGER,
+                DrawerBody {
+                    div {
+                        class: "d-flex flex-column",
+                        Alert {
+                            alert_color: AlertColor::Warn,
+                            class: "mb-3",
+                            "Purged entries are gone for good. Export them first if you need to keep them."
+                        }
+                        Input {
+                            input_type: InputType::Number,
+                            help_text: "Leave empty to keep the audit trail forever",
+                            label: "Days",
+                            name: "days",
+                            value: &cx.props.days
+                        }
+                        Select {
+                            label: "After that",
+                            help_text: "Archived entries can still be exported",
+                            name: "retention",
+                            if cx.props.purge {
+                                cx.render(rsx!(
+                                    option {
+                                        value: "1",
+                                        "Archive"
+                                    }
+                                    option {
+                                        value: "2",
+                                        selected: "selected",
+                                        "Purge"
+                                    }
+                                ))
+                            } else {
+                                cx.render(rsx!(
+                                    option {
+                                        value: "1",
+                                        selected: "selected",
+                                        "Archive"
+                                    }
+                                    option {
+                                        value: "2",
+                                        "Purge"
+                                    }
+                                ))
+                            }
+                        }
+                    }
+                }
+                DrawerFooter {
+                    Button {
+                        button_type: ButtonType::Submit,
+                        button_scheme: ButtonScheme::Primary,
+                        "Save"
+                    }
+                }
+            }
+        }
+    })
+}
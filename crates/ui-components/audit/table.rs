@@ -22,7 +22,9 @@ pub fn AuditTable<'a>(cx: Scope<'a, TableProps<'a>>) -> Element {
                             th { "User" }
                             th { "Access Type" }
                             th { "Action" }
+                            th { "About" }
                             th { "Description" }
+                            th { "Source" }
                         }
                         tbody {
                             cx.props.audits.iter().map(|audit| rsx!(
@@ -34,7 +36,9 @@ pub fn AuditTable<'a>(cx: Scope<'a, TableProps<'a>>) -> Element {
                                         }
                                     }
                                     td {
-                                        "{audit.email}"
+                                        AuditActor {
+                                            audit: audit
+                                        }
                                     }
                                     td {
                                         super::access_type::AuditAccessType {
@@ -46,9 +50,19 @@ pub fn AuditTable<'a>(cx: Scope<'a, TableProps<'a>>) -> Element {
                                             audit_action: &audit.action
                                         }
                                     }
+                                    td {
+                                        AuditSubject {
+                                            audit: audit
+                                        }
+                                    }
                                     td {
                                         "{audit.description}"
                                     }
+                                    td {
+                                        AuditSource {
+                                            audit: audit
+                                        }
+                                    }
                                 }
                             ))
                         }
@@ -58,3 +72,71 @@ pub fn AuditTable<'a>(cx: Scope<'a, TableProps<'a>>) -> Element {
         }
     ))
 }
+
+#[derive(Props, PartialEq)]
+pub struct AuditProps<'a> {
+    audit: &'a Audit,
+}
+
+// Audits are from either a user or a service account.
+fn AuditActor<'a>(cx: Scope<'a, AuditProps<'a>>) -> Element {
+    match (&cx.props.audit.email, &cx.props.audit.service_account_name) {
+        (Some(email), _) => cx.render(rsx!("{email}")),
+        (None, Some(name)) => cx.render(rsx!(
+            Label {
+                class: "mr-2",
+                "Service Account"
+            }
+            "{name}"
+        )),
+        (None, None) => cx.render(rsx!("Deleted Service Account")),
+    }
+}
+
+// What the action was carried out on. Anything deleted since has lost its name.
+fn AuditSubject<'a>(cx: Scope<'a, AuditProps<'a>>) -> Element {
+    let audit = cx.props.audit;
+    let mut subjects: Vec<(&str, String)> = Vec::new();
+    if audit.vault_id.is_some() {
+        let name = audit
+            .vault_name
+            .clone()
+            .unwrap_or_else(|| "Deleted Vault".to_string());
+        subjects.push(("Vault", name));
+    }
+    if let Some(name) = &audit.environment_name {
+        subjects.push(("Environment", name.clone()));
+    }
+    if let Some(secret_id) = audit.secret_id {
+        // Secret names are encrypted so all we have is the id.
+        subjects.push(("Secret", format!("#{}", secret_id)));
+    }
+    if let Some(email) = &audit.target_email {
+        subjects.push(("Member", email.clone()));
+    }
+    if let Some(name) = &audit.target_service_account_name {
+        subjects.push(("Service Account", name.clone()));
+    }
+    cx.render(rsx! {
+        subjects.iter().map(|(label, name)| rsx!(
+            div {
+                Label {
+                    class: "mr-2",
+                    "{label}"
+                }
+                "{name}"
+            }
+        ))
+    })
+}
+
+fn AuditSource<'a>(cx: Scope<'a, AuditProps<'a>>) -> Element {
+    let ip_address = cx.props.audit.ip_address.clone().unwrap_or_default();
+    let user_agent = cx.props.audit.user_agent.clone().unwrap_or_default();
+    cx.render(rsx!(
+        span {
+            title: "{user_agent}",
+            "{ip_address}"
+        }
+    ))
+}
@@ -11,7 +11,9 @@ pub enum SideBar {
     Vaults,
     Secrets,
     Members,
+    Environments,
     ServiceAccounts,
+    ApiKeys,
     Team,
     Profile,
     Switch,
@@ -70,6 +72,12 @@ pub fn CloakLayout<'a>(cx: Scope<'a, CloakLayoutProps<'a>>) -> Element {
                                             href: super::routes::members::member_route(vault_id, cx.props.team_id),
                                             title: "Members"
                                         }
+                                        NavSubItem {
+                                            id: SideBar::Environments.to_string(),
+                                            selected_item_id: cx.props.selected_item.to_string(),
+                                            href: super::routes::environments::index_route(vault_id, cx.props.team_id),
+                                            title: "Environments"
+                                        }
                                     }
                                 }
                             ))
@@ -91,6 +99,13 @@ pub fn CloakLayout<'a>(cx: Scope<'a, CloakLayoutProps<'a>>) -> Element {
                             icon: nav_api_keys_svg.name,
                             title: "Service Accounts"
                         }
+                        NavItem {
+                            id: SideBar::ApiKeys.to_string(),
+                            selected_item_id: cx.props.selected_item.to_string(),
+                            href: super::routes::api_keys::index_route(cx.props.team_id),
+                            icon: nav_api_keys_svg.name,
+                            title: "API Keys"
+                        }
                         NavItem {
                             id: SideBar::Audit.to_string(),
                             selected_item_id: cx.props.selected_item.to_string(),
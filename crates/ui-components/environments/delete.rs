@@ -0,0 +1,60 @@
+#![allow(non_snake_case)]
+use db::Environment;
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+#[derive(Props, PartialEq)]
+pub struct DrawerProps<'a> {
+    organisation_id: i32,
+    vault_id: i32,
+    environment: &'a Environment,
+    trigger_id: String,
+}
+
+pub fn DeleteEnvironmentDrawer<'a>(cx: Scope<'a, DrawerProps<'a>>) -> Element {
+    cx.render(rsx! {
+        Drawer {
+            submit_action: crate::routes::environments::delete_route(
+                cx.props.vault_id, cx.props.organisation_id),
+            label: "Delete Environment ?",
+            trigger_id: &cx.props.trigger_id,
+            DrawerBody {
+                div {
+                    class: "d-flex flex-column",
+                    Alert {
+                        alert_color: AlertColor::Warn,
+                        class: "mb-3",
+                        h4 {
+                            "Are you sure you want to delete this environment?"
+                        }
+                        "All of its secrets will be deleted and any service accounts
+                        connected to it will be disconnected. If so then type the name of the environment "
+                        strong {
+                            "{cx.props.environment.name}"
+                        }
+                        " into the input field"
+                    }
+                    Input {
+                        input_type: InputType::Text,
+                        help_text: "Please confirm the name of the environment you wish to delete"
+                        required: true,
+                        label: "Name",
+                        name: "name"
+                    }
+                    input {
+                        "type": "hidden",
+                        "name": "environment_id",
+                        "value": "{cx.props.environment.id}"
+                    }
+                }
+            }
+            DrawerFooter {
+                Button {
+                    button_type: ButtonType::Submit,
+                    button_scheme: ButtonScheme::Danger,
+                    "Delete Environment"
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,70 @@
+use crate::cloak_layout::{CloakLayout, SideBar};
+use assets::files::{button_plus_svg, empty_api_keys_svg};
+use db::Environment;
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+#[derive(Props, PartialEq)]
+struct EnvironmentsProps {
+    organisation_id: i32,
+    vault_id: i32,
+    environments: Vec<Environment>,
+}
+
+pub fn index(organisation_id: i32, vault_id: i32, environments: Vec<Environment>) -> String {
+    fn app(cx: Scope<EnvironmentsProps>) -> Element {
+        cx.render(rsx! {
+
+            CloakLayout {
+                selected_item: SideBar::Environments,
+                team_id: cx.props.organisation_id,
+                title: "Environments",
+                vault_id: cx.props.vault_id
+                header: cx.render(rsx!(
+                    h3 { "Environments" }
+                    Button {
+                        prefix_image_src: "{button_plus_svg.name}",
+                        drawer_trigger: super::new_environment::DRAW_TRIGGER,
+                        button_scheme: ButtonScheme::Primary,
+                        "Add Environment"
+                    }
+                ))
+
+                if cx.props.environments.is_empty() {
+                    cx.render(rsx! {
+                        BlankSlate {
+                            heading: "You don't have access to any environments in this vault",
+                            visual: empty_api_keys_svg.name,
+                            description: "Environments such as Development or Production split up the secrets in a vault.",
+                            primary_action_drawer: ("Add Environment", super::new_environment::DRAW_TRIGGER)
+                        }
+                    })
+                } else {
+                    cx.render(rsx! {
+                        super::table::EnvironmentTable {
+                            environments: &cx.props.environments,
+                            organisation_id: cx.props.organisation_id,
+                            vault_id: cx.props.vault_id
+                        }
+                    })
+                }
+
+                super::new_environment::NewEnvironmentForm {
+                    submit_action: crate::routes::environments::new_route(
+                        cx.props.vault_id, cx.props.organisation_id)
+                }
+            }
+        })
+    }
+
+    let mut app = VirtualDom::new_with_props(
+        app,
+        EnvironmentsProps {
+            organisation_id,
+            vault_id,
+            environments,
+        },
+    );
+    let _ = app.rebuild();
+    dioxus::ssr::render_vdom(&app)
+}
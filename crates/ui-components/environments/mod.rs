@@ -0,0 +1,5 @@
+pub mod delete;
+pub mod index;
+pub mod new_environment;
+pub mod rename;
+pub mod table;
@@ -0,0 +1,48 @@
+#![allow(non_snake_case)]
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+pub static DRAW_TRIGGER: &str = "new-environment-drawer";
+
+#[derive(Props, PartialEq, Eq)]
+pub struct NewEnvironmentFormProps {
+    submit_action: String,
+}
+
+pub fn NewEnvironmentForm(cx: Scope<NewEnvironmentFormProps>) -> Element {
+    cx.render(rsx! {
+        form {
+            method: "post",
+            action: "{cx.props.submit_action}",
+            Drawer {
+                label: "Add Environment",
+                trigger_id: DRAW_TRIGGER,
+                DrawerBody {
+                    div {
+                        class: "d-flex flex-column",
+                        Alert {
+                            alert_color: AlertColor::Success,
+                            class: "mb-3",
+                            "You will have access to the new environment.
+                            Other members of the vault need to be given access to it."
+                        }
+                        Input {
+                            input_type: InputType::Text,
+                            help_text: "For example QA or eu-prod"
+                            required: true,
+                            label: "Name",
+                            name: "name"
+                        }
+                    }
+                }
+                DrawerFooter {
+                    Button {
+                        button_type: ButtonType::Submit,
+                        button_scheme: ButtonScheme::Primary,
+                        "Create"
+                    }
+                }
+            }
+        }
+    })
+}
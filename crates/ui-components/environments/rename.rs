@@ -0,0 +1,48 @@
+#![allow(non_snake_case)]
+use db::Environment;
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+#[derive(Props, PartialEq)]
+pub struct DrawerProps<'a> {
+    organisation_id: i32,
+    vault_id: i32,
+    environment: &'a Environment,
+    trigger_id: String,
+}
+
+pub fn RenameEnvironmentDrawer<'a>(cx: Scope<'a, DrawerProps<'a>>) -> Element {
+    cx.render(rsx! {
+        Drawer {
+            submit_action: crate::routes::environments::rename_route(
+                cx.props.vault_id, cx.props.organisation_id),
+            label: "Rename Environment",
+            trigger_id: &cx.props.trigger_id,
+            DrawerBody {
+                div {
+                    class: "d-flex flex-column",
+                    Input {
+                        input_type: InputType::Text,
+                        help_text: "Give the environment a new name"
+                        required: true,
+                        label: "Name",
+                        name: "name",
+                        value: &cx.props.environment.name
+                    }
+                    input {
+                        "type": "hidden",
+                        "name": "environment_id",
+                        "value": "{cx.props.environment.id}"
+                    }
+                }
+            }
+            DrawerFooter {
+                Button {
+                    button_type: ButtonType::Submit,
+                    button_scheme: ButtonScheme::Primary,
+                    "Rename Environment"
+                }
+            }
+        }
+    })
+}
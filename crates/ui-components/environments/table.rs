@@ -0,0 +1,79 @@
+#![allow(non_snake_case)]
+use db::Environment;
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+#[derive(Props, PartialEq)]
+pub struct TableProps<'a> {
+    environments: &'a Vec<Environment>,
+    organisation_id: i32,
+    vault_id: i32,
+}
+
+pub fn EnvironmentTable<'a>(cx: Scope<'a, TableProps<'a>>) -> Element {
+    cx.render(rsx!(
+        Box {
+            BoxHeader {
+                title: "Environments"
+            }
+            BoxBody {
+                DataTable {
+                    table {
+                        thead {
+                            tr {
+                                th { "Name" }
+                                th {
+                                    class: "text-right",
+                                    "Action"
+                                }
+                            }
+                        }
+                        tbody {
+                            cx.props.environments.iter().map(|environment| rsx!(
+                                tr {
+                                    td {
+                                        "{environment.name}"
+                                    }
+                                    td {
+                                        class: "text-right",
+                                        DropDown {
+                                            direction: Direction::SouthWest,
+                                            button_text: "...",
+                                            DropDownLink {
+                                                drawer_trigger: format!("rename-environment-trigger-{}", environment.id),
+                                                href: "#",
+                                                "Rename Environment"
+                                            }
+                                            DropDownLink {
+                                                drawer_trigger: format!("delete-environment-trigger-{}", environment.id),
+                                                href: "#",
+                                                "Delete Environment"
+                                            }
+                                        }
+                                    }
+                                }
+                            ))
+                        }
+                    }
+                }
+            }
+        }
+        // Create all the rename and delete drawers
+        cx.props.environments.iter().map(|environment| {
+            cx.render(rsx!(
+                super::rename::RenameEnvironmentDrawer {
+                    organisation_id: cx.props.organisation_id,
+                    vault_id: cx.props.vault_id,
+                    environment: environment,
+                    trigger_id: format!("rename-environment-trigger-{}", environment.id),
+                }
+                super::delete::DeleteEnvironmentDrawer {
+                    organisation_id: cx.props.organisation_id,
+                    vault_id: cx.props.vault_id,
+                    environment: environment,
+                    trigger_id: format!("delete-environment-trigger-{}", environment.id),
+                }
+            ))
+        })
+    ))
+}
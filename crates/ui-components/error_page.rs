@@ -0,0 +1,83 @@
+use assets::files::*;
+use dioxus::prelude::*;
+
+struct ErrorPageProps {
+    status: u16,
+    title: String,
+    message: String,
+}
+
+// Shown when a web route fails. We don't know which team the user was in
+// so there's no side bar, just a way back to the app.
+pub fn error_page(status: u16, title: &str, message: &str) -> String {
+    fn app(cx: Scope<ErrorPageProps>) -> Element {
+        cx.render(rsx! {
+            {
+                LazyNodes::new(|f| f.text(format_args!("<!DOCTYPE html><html lang='en'>")))
+            }
+            head {
+                title {
+                    "{cx.props.title}"
+                }
+                meta {
+                    charset: "utf-8"
+                }
+                meta {
+                    name: "viewport",
+                    content: "width=device-width, initial-scale=1"
+                }
+                link {
+                    rel: "stylesheet",
+                    href: "{primer_view_components_css.name}",
+                    "type": "text/css"
+                }
+                link {
+                    rel: "stylesheet",
+                    href: "{index_css.name}",
+                    "type": "text/css"
+                }
+                link {
+                    rel: "icon",
+                    "type": "image/svg+xml",
+                    href: "{favicon_svg.name}"
+                }
+            }
+            body {
+                div {
+                    class: "container-md mt-6 p-4",
+                    div {
+                        class: "blankslate",
+                        h2 {
+                            class: "blankslate-heading",
+                            "{cx.props.status} {cx.props.title}"
+                        }
+                        p {
+                            "{cx.props.message}"
+                        }
+                        div {
+                            class: "blankslate-action",
+                            a {
+                                href: "/app/post_registration",
+                                "Back to Cloak"
+                            }
+                        }
+                    }
+                }
+            }
+            {
+                LazyNodes::new(|f| f.text(format_args!("</html>")))
+            }
+        })
+    }
+
+    let mut app = VirtualDom::new_with_props(
+        app,
+        ErrorPageProps {
+            status,
+            title: title.to_string(),
+            message: message.to_string(),
+        },
+    );
+    let _ = app.rebuild();
+    dioxus::ssr::render_vdom(&app)
+}
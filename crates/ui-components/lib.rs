@@ -1,5 +1,8 @@
+pub mod api_keys;
 pub mod audit;
 pub mod cloak_layout;
+pub mod environments;
+pub mod error_page;
 pub mod logout_form;
 pub mod members;
 pub mod profile;
@@ -30,6 +33,57 @@ pub mod routes {
                 organisation_id, vault_id
             )
         }
+
+        pub fn environments_route(vault_id: i32, organisation_id: i32) -> String {
+            format!(
+                "/app/team/{}/vault/{}/members/environments",
+                organisation_id, vault_id
+            )
+        }
+
+        pub fn set_role_route(vault_id: i32, organisation_id: i32) -> String {
+            format!(
+                "/app/team/{}/vault/{}/members/set_role",
+                organisation_id, vault_id
+            )
+        }
+
+        pub fn rotate_key_route(vault_id: i32, organisation_id: i32) -> String {
+            format!(
+                "/app/team/{}/vault/{}/members/rotate_key",
+                organisation_id, vault_id
+            )
+        }
+    }
+
+    pub mod environments {
+        pub fn index_route(vault_id: i32, organisation_id: i32) -> String {
+            format!(
+                "/app/team/{}/vault/{}/environments",
+                organisation_id, vault_id
+            )
+        }
+
+        pub fn new_route(vault_id: i32, organisation_id: i32) -> String {
+            format!(
+                "/app/team/{}/vault/{}/environments/new",
+                organisation_id, vault_id
+            )
+        }
+
+        pub fn rename_route(vault_id: i32, organisation_id: i32) -> String {
+            format!(
+                "/app/team/{}/vault/{}/environments/rename",
+                organisation_id, vault_id
+            )
+        }
+
+        pub fn delete_route(vault_id: i32, organisation_id: i32) -> String {
+            format!(
+                "/app/team/{}/vault/{}/environments/delete",
+                organisation_id, vault_id
+            )
+        }
     }
 
     pub mod secrets {
@@ -50,6 +104,20 @@ pub mod routes {
                 organisation_id, vault_id
             )
         }
+
+        pub fn rollback_route(vault_id: i32, organisation_id: i32) -> String {
+            format!(
+                "/app/team/{}/vault/{}/secrets/rollback",
+                organisation_id, vault_id
+            )
+        }
+
+        pub fn update_route(vault_id: i32, organisation_id: i32) -> String {
+            format!(
+                "/app/team/{}/vault/{}/secrets/update",
+                organisation_id, vault_id
+            )
+        }
     }
 
     pub mod vaults {
@@ -72,6 +140,18 @@ pub mod routes {
         pub fn index_route(organisation_id: i32) -> String {
             format!("/app/team/{}/audit", organisation_id)
         }
+
+        pub fn verify_route(organisation_id: i32) -> String {
+            format!("/app/team/{}/audit/verify", organisation_id)
+        }
+
+        pub fn export_route(organisation_id: i32) -> String {
+            format!("/app/team/{}/audit/export", organisation_id)
+        }
+
+        pub fn retention_route(organisation_id: i32) -> String {
+            format!("/app/team/{}/audit/retention", organisation_id)
+        }
     }
 
     pub mod service_accounts {
@@ -93,6 +173,10 @@ pub mod routes {
         pub fn new_route(organisation_id: i32) -> String {
             format!("/app/team/{}/service_accounts/new", organisation_id)
         }
+
+        pub fn rotate_key_route(organisation_id: i32) -> String {
+            format!("/app/team/{}/service_accounts/rotate_key", organisation_id)
+        }
     }
 
     pub mod team {
@@ -120,9 +204,33 @@ pub mod routes {
             format!("/app/team/{}/set_name", organisation_id)
         }
 
+        pub fn set_role_route(organisation_id: i32) -> String {
+            format!("/app/team/{}/set_role", organisation_id)
+        }
+
         pub fn new_team_route(organisation_id: i32) -> String {
             format!("/app/team/{}/new", organisation_id)
         }
+
+        pub fn new_role_route(organisation_id: i32) -> String {
+            format!("/app/team/{}/roles/new", organisation_id)
+        }
+
+        pub fn delete_role_route(organisation_id: i32) -> String {
+            format!("/app/team/{}/roles/delete", organisation_id)
+        }
+
+        pub fn transfer_route(organisation_id: i32) -> String {
+            format!("/app/team/{}/transfer", organisation_id)
+        }
+
+        pub fn delete_team_route(organisation_id: i32) -> String {
+            format!("/app/team/{}/delete_team", organisation_id)
+        }
+
+        pub fn leave_route(organisation_id: i32) -> String {
+            format!("/app/team/{}/leave", organisation_id)
+        }
     }
 
     pub mod profile {
@@ -151,5 +259,9 @@ pub mod routes {
         pub fn new_route(organisation_id: i32) -> String {
             format!("/app/team/{}/api_keys/new", organisation_id)
         }
+
+        pub fn revoke_route(organisation_id: i32) -> String {
+            format!("/app/team/{}/api_keys/revoke", organisation_id)
+        }
     }
 }
@@ -43,6 +43,20 @@ pub fn AddMemberForm(cx: Scope<AddMemberFormProps>) -> Element {
                                 ))
                             })
                         }
+                        Select {
+                            name: "role",
+                            label: "Role",
+                            help_text: "What the user can do in this vault",
+                            super::role::VAULT_ROLES.iter().map(|(_, value, label, _)| {
+                                cx.render(rsx! (
+                                    option {
+                                        value: "{value}",
+                                        "{label}"
+                                    }
+                                ))
+                            })
+                        }
+                        super::role::RoleHelp {}
                         label {
                             "Which environments do you want the user to have access to?"
                         }
@@ -75,6 +89,13 @@ pub fn AddMemberForm(cx: Scope<AddMemberFormProps>) -> Element {
                             id: "vault-id",
                             value: "{cx.props.user_vault.vault_id}"
                         }
+                        // The version of the key above, so the server can tell if
+                        // it has been rotated since the page loaded.
+                        input {
+                            "type": "hidden",
+                            name: "key_version",
+                            value: "{cx.props.user_vault.key_version}"
+                        }
                         //  These hidden fields are populated by the add_member.ts
                         input {
                             "type": "hidden",
@@ -0,0 +1,88 @@
+#![allow(non_snake_case)]
+use db::{Environment, VaultMember};
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+#[derive(Props, PartialEq)]
+pub struct DrawerProps<'a> {
+    organisation_id: i32,
+    vault_member: &'a VaultMember,
+    environments: &'a Vec<Environment>,
+    trigger_id: String,
+}
+
+pub fn MemberEnvironmentsDrawer<'a>(cx: Scope<'a, DrawerProps<'a>>) -> Element {
+    let granted: Vec<i32> = cx
+        .props
+        .vault_member
+        .environment_ids
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|id| id.parse::<i32>().ok())
+        .collect();
+
+    cx.render(rsx! {
+        Drawer {
+            submit_action: crate::routes::members::environments_route(
+                cx.props.vault_member.vault_id, cx.props.organisation_id),
+            label: "Edit Environments",
+            trigger_id: &cx.props.trigger_id,
+            component_name: "member-environments",
+            DrawerBody {
+                div {
+                    class: "d-flex flex-column",
+                    label {
+                        "Which environments should {cx.props.vault_member.email} have access to?"
+                    }
+                    cx.props.environments.iter().map(|env| {
+                        let id = format!("{}-{}", cx.props.trigger_id, env.id);
+                        if granted.contains(&env.id) {
+                            cx.render(rsx! (
+                                label {
+                                    "for": "{id}",
+                                    input {
+                                        "type": "checkbox",
+                                        id: "{id}",
+                                        value: "{env.id}",
+                                        checked: "true",
+                                        "{env.name}"
+                                    }
+                                }
+                            ))
+                        } else {
+                            cx.render(rsx! (
+                                label {
+                                    "for": "{id}",
+                                    input {
+                                        "type": "checkbox",
+                                        id: "{id}",
+                                        value: "{env.id}",
+                                        "{env.name}"
+                                    }
+                                }
+                            ))
+                        }
+                    })
+                    input {
+                        "type": "hidden",
+                        "name": "user_id",
+                        "value": "{cx.props.vault_member.user_id}"
+                    }
+                    //  This hidden field is populated by member-environments.ts
+                    input {
+                        "type": "hidden",
+                        "name": "environments"
+                    }
+                }
+            }
+            DrawerFooter {
+                Button {
+                    button_type: ButtonType::Submit,
+                    button_scheme: ButtonScheme::Primary,
+                    "Update Environments"
+                }
+            }
+        }
+    })
+}
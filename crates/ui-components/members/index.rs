@@ -1,3 +1,4 @@
+use super::rotate_key::KeyRotation;
 use crate::cloak_layout::{CloakLayout, SideBar};
 use assets::files::button_plus_svg;
 use db::{Environment, NonMember, UserVault, VaultMember};
@@ -11,6 +12,8 @@ struct MemberProps {
     members: Vec<VaultMember>,
     non_members: Vec<NonMember>,
     organisation_id: i32,
+    can_manage_members: bool,
+    rotation: KeyRotation,
 }
 
 pub fn index(
@@ -19,6 +22,8 @@ pub fn index(
     environments: Vec<Environment>,
     members: Vec<VaultMember>,
     non_members: Vec<NonMember>,
+    can_manage_members: bool,
+    rotation: KeyRotation,
 ) -> String {
     fn app(cx: Scope<MemberProps>) -> Element {
         cx.render(rsx! {
@@ -30,7 +35,7 @@ pub fn index(
                 vault_id: cx.props.user_vault.vault_id
                 header: cx.render(rsx!(
                     h3 { "Members" }
-                    if ! cx.props.non_members.is_empty() {
+                    if cx.props.can_manage_members && ! cx.props.non_members.is_empty() {
                         cx.render(rsx! {
                             Button {
                                 prefix_image_src: "{button_plus_svg.name}",
@@ -42,10 +47,36 @@ pub fn index(
                     } else {
                         None
                     }
+                    if cx.props.can_manage_members {
+                        cx.render(rsx! {
+                            Button {
+                                class: "ml-2",
+                                drawer_trigger: super::rotate_key::DRAW_TRIGGER,
+                                "Rotate Key"
+                            }
+                        })
+                    } else {
+                        None
+                    }
                 ))
+                if cx.props.rotation.needed {
+                    cx.render(rsx! {
+                        Alert {
+                            alert_color: AlertColor::Warn,
+                            class: "mb-3",
+                            "Someone has lost access to this vault since its key was created
+                            and they may still have a copy of it. A Vault Admin should rotate the key."
+                        }
+                    })
+                } else {
+                    None
+                }
                 super::table::MembersTable {
                     members: cx.props.members.clone(),
-                    organisation_id: cx.props.organisation_id
+                    environments: cx.props.environments.clone(),
+                    organisation_id: cx.props.organisation_id,
+                    user_id: cx.props.user_vault.user_id,
+                    can_manage_members: cx.props.can_manage_members
                 }
             }
             super::add_member::AddMemberForm {
@@ -56,6 +87,19 @@ pub fn index(
                 non_members: cx.props.non_members.clone(),
                 environments: cx.props.environments.clone(),
             }
+            if cx.props.can_manage_members {
+                cx.render(rsx! {
+                    super::rotate_key::RotateKeyForm {
+                        submit_action: crate::routes::members::rotate_key_route(
+                            cx.props.user_vault.vault_id,
+                            cx.props.organisation_id),
+                        user_vault: &cx.props.user_vault,
+                        rotation: &cx.props.rotation,
+                    }
+                })
+            } else {
+                None
+            }
         })
     }
 
@@ -67,6 +111,8 @@ pub fn index(
             environments,
             members,
             non_members,
+            can_manage_members,
+            rotation,
         },
     );
     let _ = app.rebuild();
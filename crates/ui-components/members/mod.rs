@@ -1,5 +1,8 @@
 pub mod add_member;
+pub mod environments;
 pub mod index;
 pub mod remove;
 pub mod remove_warning;
+pub mod role;
+pub mod rotate_key;
 pub mod table;
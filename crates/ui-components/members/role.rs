@@ -0,0 +1,151 @@
+#![allow(non_snake_case)]
+use db::{VaultMember, VaultRole};
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+// The form value, label and explanation of each vault role, least first.
+pub const VAULT_ROLES: &[(VaultRole, &str, &str, &str)] = &[
+    (
+        VaultRole::Viewer,
+        "Viewer",
+        "Viewer",
+        "Can decrypt the secrets of their environments",
+    ),
+    (
+        VaultRole::Editor,
+        "Editor",
+        "Editor",
+        "Can also add, change and delete secrets",
+    ),
+    (
+        VaultRole::Admin,
+        "Admin",
+        "Vault Admin",
+        "Can also manage members, environments and delete the vault",
+    ),
+];
+
+#[derive(Props, PartialEq, Eq)]
+pub struct RoleProps<'a> {
+    pub role: &'a VaultRole,
+}
+
+pub fn VaultRole<'a>(cx: Scope<'a, RoleProps<'a>>) -> Element {
+    match cx.props.role {
+        VaultRole::Admin => cx.render(rsx!(
+            Label {
+                label_color: LabelColor::Done,
+                label_contrast: LabelContrast::Primary,
+                "Vault Admin"
+            }
+        )),
+        VaultRole::Editor => cx.render(rsx!(
+            Label {
+                label_color: LabelColor::Attention,
+                "Editor"
+            }
+        )),
+        VaultRole::Viewer => cx.render(rsx!(
+            Label {
+                "Viewer"
+            }
+        )),
+    }
+}
+
+#[derive(Props, PartialEq)]
+pub struct ChangeRoleProps<'a> {
+    organisation_id: i32,
+    vault_member: &'a VaultMember,
+    trigger_id: String,
+}
+
+pub fn ChangeRoleDrawer<'a>(cx: Scope<'a, ChangeRoleProps<'a>>) -> Element {
+    let member = cx.props.vault_member;
+
+    cx.render(rsx! {
+        Drawer {
+            submit_action: crate::routes::members::set_role_route(
+                member.vault_id, cx.props.organisation_id),
+            label: "Change Vault Role",
+            trigger_id: &cx.props.trigger_id,
+            DrawerBody {
+                div {
+                    class: "d-flex flex-column",
+                    Select {
+                        label: "Role",
+                        help_text: "What they can do in this vault",
+                        name: "role",
+                        VAULT_ROLES.iter().map(|(role, value, label, _)| rsx!(
+                            RoleOption {
+                                value: value.to_string(),
+                                label: label.to_string(),
+                                selected: *role == member.role
+                            }
+                        ))
+                    }
+                    RoleHelp {}
+                    Alert {
+                        alert_color: AlertColor::Warn,
+                        class: "mb-3",
+                        "Every vault needs at least one Vault Admin."
+                    }
+                    input {
+                        "type": "hidden",
+                        "name": "user_id",
+                        "value": "{member.user_id}"
+                    }
+                }
+            }
+            DrawerFooter {
+                Button {
+                    button_type: ButtonType::Submit,
+                    button_scheme: ButtonScheme::Primary,
+                    "Change Role"
+                }
+            }
+        }
+    })
+}
+
+// What each role means, shown under the select.
+pub fn RoleHelp(cx: Scope) -> Element {
+    cx.render(rsx! {
+        ul {
+            class: "mb-3",
+            VAULT_ROLES.iter().map(|(_, _, label, description)| rsx!(
+                li {
+                    strong { "{label}" }
+                    " {description}"
+                }
+            ))
+        }
+    })
+}
+
+#[derive(Props, PartialEq, Eq)]
+struct RoleOptionProps {
+    value: String,
+    label: String,
+    selected: bool,
+}
+
+// Shows the role the member has now when the drawer opens.
+fn RoleOption(cx: Scope<RoleOptionProps>) -> Element {
+    if cx.props.selected {
+        cx.render(rsx! {
+            option {
+                value: "{cx.props.value}",
+                selected: "selected",
+                "{cx.props.label}"
+            }
+        })
+    } else {
+        cx.render(rsx! {
+            option {
+                value: "{cx.props.value}",
+                "{cx.props.label}"
+            }
+        })
+    }
+}
@@ -0,0 +1,144 @@
+#![allow(non_snake_case)]
+#![allow(unused_braces)]
+use db::{Ciphertext, MemberKey, UserVault};
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+pub static DRAW_TRIGGER: &str = "rotate-vault-key-drawer";
+
+// What an Admin needs to rotate the vault key. Everyone else gets
+// the ciphertext lists empty.
+#[derive(PartialEq, Default)]
+pub struct KeyRotation {
+    pub key_version: i32,
+    // Someone has lost access since the key was created.
+    pub needed: bool,
+    // Environments the Admin hasn't been given, they can't rotate without them.
+    pub hidden_environments: i64,
+    pub member_keys: Vec<MemberKey>,
+    pub secrets: Vec<Ciphertext>,
+    pub versions: Vec<Ciphertext>,
+}
+
+#[derive(Props, PartialEq)]
+pub struct RotateKeyFormProps<'a> {
+    submit_action: String,
+    user_vault: &'a UserVault,
+    rotation: &'a KeyRotation,
+}
+
+pub fn RotateKeyForm<'a>(cx: Scope<'a, RotateKeyFormProps<'a>>) -> Element {
+    let rotation = cx.props.rotation;
+
+    cx.render(rsx! {
+        form {
+            id: "rotate-key-form",
+            method: "post",
+            action: "{cx.props.submit_action}",
+            Drawer {
+                label: "Rotate Vault Key",
+                trigger_id: DRAW_TRIGGER,
+                component_name: "rotate-key",
+                DrawerBody {
+                    div {
+                        class: "d-flex flex-column",
+                        Alert {
+                            alert_color: AlertColor::Warn,
+                            class: "mb-3",
+                            "A new key will be created and every secret re-encrypted with it.
+                            Only the current members of the vault will be given the new key."
+                        }
+                        if rotation.hidden_environments > 0 {
+                            cx.render(rsx! {
+                                Alert {
+                                    alert_color: AlertColor::Error,
+                                    class: "mb-3",
+                                    "You don't have access to {rotation.hidden_environments} of the
+                                    environments in this vault, so you can't re-encrypt their secrets.
+                                    Ask another Admin to give you every environment or to rotate the key."
+                                }
+                            })
+                        } else {
+                            None
+                        }
+                        p {
+                            "This happens in your browser, keep this page open until it's done."
+                        }
+                        input {
+                            "type": "hidden",
+                            id: "encrypted-vault-key",
+                            value: "{cx.props.user_vault.encrypted_vault_key}"
+                        }
+                        input {
+                            "type": "hidden",
+                            id: "user-vault-ecdh-public-key",
+                            value: "{cx.props.user_vault.ecdh_public_key}"
+                        }
+                        input {
+                            "type": "hidden",
+                            name: "key_version",
+                            value: "{rotation.key_version}"
+                        }
+                        // What needs wrapping and re-encrypting, read by rotate-key.ts
+                        div {
+                            class: "d-none",
+                            rotation.member_keys.iter().map(|member| rsx!(
+                                span {
+                                    class: "rotate-member",
+                                    "data-user-id": "{member.user_id}",
+                                    "data-ecdh-pub-key": "{member.ecdh_public_key}"
+                                }
+                            ))
+                            rotation.secrets.iter().map(|secret| rsx!(
+                                span {
+                                    class: "rotate-secret",
+                                    "data-id": "{secret.id}",
+                                    "data-name": "{secret.name}",
+                                    "data-secret": "{secret.secret}"
+                                }
+                            ))
+                            rotation.versions.iter().map(|version| rsx!(
+                                span {
+                                    class: "rotate-version",
+                                    "data-id": "{version.id}",
+                                    "data-name": "{version.name}",
+                                    "data-secret": "{version.secret}"
+                                }
+                            ))
+                        }
+                        //  These hidden fields are populated by the rotate-key.ts
+                        input {
+                            "type": "hidden",
+                            id: "rotated-members",
+                            name: "members"
+                        }
+                        input {
+                            "type": "hidden",
+                            id: "rotated-secrets",
+                            name: "secrets"
+                        }
+                        input {
+                            "type": "hidden",
+                            id: "rotated-versions",
+                            name: "versions"
+                        }
+                    }
+                }
+                DrawerFooter {
+                    if rotation.hidden_environments == 0 {
+                        cx.render(rsx! {
+                            Button {
+                                id: "rotate-key-button",
+                                button_type: ButtonType::Submit,
+                                button_scheme: ButtonScheme::Danger,
+                                "Rotate Key"
+                            }
+                        })
+                    } else {
+                        None
+                    }
+                }
+            }
+        }
+    })
+}
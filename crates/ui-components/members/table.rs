@@ -1,12 +1,16 @@
 #![allow(non_snake_case)]
-use db::VaultMember;
+use db::{Environment, VaultMember};
 use dioxus::prelude::*;
 use primer_rsx::*;
 
 #[derive(Props, PartialEq)]
 pub struct TableProps {
     members: Vec<VaultMember>,
+    environments: Vec<Environment>,
     organisation_id: i32,
+    user_id: i32,
+    // Only vault Admins can change the members.
+    can_manage_members: bool,
 }
 
 pub fn MembersTable(cx: Scope<TableProps>) -> Element {
@@ -21,6 +25,7 @@ pub fn MembersTable(cx: Scope<TableProps>) -> Element {
                         thead {
                             th { "Name" }
                             th { "Environments" }
+                            th { "Role" }
                             th {
                                 class: "text-right",
                                 "Action" 
@@ -41,17 +46,54 @@ pub fn MembersTable(cx: Scope<TableProps>) -> Element {
                                             None
                                         }
                                     }
+                                    td {
+                                        super::role::VaultRole {
+                                            role: &member.role
+                                        }
+                                    }
                                     td {
                                         class: "text-right",
-                                        DropDown {
-                                            direction: Direction::SouthWest,
-                                            button_text: "...",
-                                            DropDownLink {
-                                                drawer_trigger: format!("delete-secret-trigger-{}-{}", 
-                                                    member.vault_id, member.user_id),
-                                                href: "#",
-                                                "Remove Member"
-                                            }
+                                        if cx.props.can_manage_members {
+                                            cx.render(rsx!(
+                                                DropDown {
+                                                    direction: Direction::SouthWest,
+                                                    button_text: "...",
+                                                    DropDownLink {
+                                                        drawer_trigger: format!("member-environments-trigger-{}-{}",
+                                                            member.vault_id, member.user_id),
+                                                        href: "#",
+                                                        "Edit Environments"
+                                                    }
+                                                    DropDownLink {
+                                                        drawer_trigger: format!("member-role-trigger-{}-{}",
+                                                            member.vault_id, member.user_id),
+                                                        href: "#",
+                                                        "Change Role"
+                                                    }
+                                                    DropDownLink {
+                                                        drawer_trigger: format!("delete-secret-trigger-{}-{}", 
+                                                            member.vault_id, member.user_id),
+                                                        href: "#",
+                                                        "Remove Member"
+                                                    }
+                                                }
+                                            ))
+                                        } else if member.user_id == cx.props.user_id {
+                                            // Anyone can leave a vault.
+                                            cx.render(rsx!(
+                                                DropDown {
+                                                    direction: Direction::SouthWest,
+                                                    button_text: "...",
+                                                    DropDownLink {
+                                                        drawer_trigger: format!("delete-secret-trigger-{}-{}", 
+                                                            member.vault_id, member.user_id),
+                                                        href: "#",
+                                                        "Leave Vault"
+                                                    }
+                                                }
+                                            ))
+                                        } else {
+                                            None
                                         }
                                     }
                                 }
@@ -61,6 +103,25 @@ pub fn MembersTable(cx: Scope<TableProps>) -> Element {
                 }
             }
         }
+        // Create all the environment drawers
+        cx.props.members.iter().map(|member| rsx!(
+            super::environments::MemberEnvironmentsDrawer {
+                organisation_id: cx.props.organisation_id,
+                vault_member: member,
+                environments: &cx.props.environments,
+                trigger_id: format!("member-environments-trigger-{}-{}",
+                    member.vault_id, member.user_id),
+            }
+        ))
+        // Create all the role drawers
+        cx.props.members.iter().map(|member| rsx!(
+            super::role::ChangeRoleDrawer {
+                organisation_id: cx.props.organisation_id,
+                vault_member: member,
+                trigger_id: format!("member-role-trigger-{}-{}",
+                    member.vault_id, member.user_id),
+            }
+        ))
         // Create all the delete drawers
         cx.props.members.iter().map(|member| {
             if cx.props.members.len() == 1 {
@@ -0,0 +1,95 @@
+#![allow(non_snake_case)]
+use db::{Secret, UserVault};
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+#[derive(Props, PartialEq)]
+pub struct DrawerProps<'a> {
+    organisation_id: i32,
+    secret: &'a Secret,
+    user_vault: &'a UserVault,
+    trigger_id: String,
+}
+
+pub fn EditSecretDrawer<'a>(cx: Scope<'a, DrawerProps<'a>>) -> Element {
+    cx.render(rsx! {
+        Drawer {
+            submit_action: crate::routes::secrets::update_route(cx.props.secret.vault_id, cx.props.organisation_id),
+            label: "Edit Secret",
+            trigger_id: &cx.props.trigger_id,
+            component_name: "edit-secret",
+            DrawerBody {
+                div {
+                    class: "d-flex flex-column",
+                    Alert {
+                        alert_color: AlertColor::Success,
+                        class: "mb-3",
+                        "All values entered on this form will be End 2 End encrypted before
+                        being sent to the server."
+                    }
+                    label {
+                        "Name"
+                    }
+                    strong {
+                        class: "mb-3",
+                        {LazyNodes::new(|f| f.text(format_args!(
+                            "<ecdh-cipher cipher='{}'
+                            wrapped-aes-key='{}' 
+                            ecdh-public-key='{}'></ecdh-cipher>",
+                            cx.props.secret.name,
+                            cx.props.user_vault.encrypted_vault_key,
+                            cx.props.user_vault.ecdh_public_key
+                        )))}
+                    }
+                    label {
+                        "for": "secret",
+                        "New Value"
+                    }
+                    textarea {
+                        rows: "10",
+                        autocomplete: "off",
+                        required: "",
+                        name: "secret"
+                    }
+                    input {
+                        "type": "hidden",
+                        "name": "secret_id",
+                        "value": "{cx.props.secret.id}"
+                    }
+                    input {
+                        "type": "hidden",
+                        "name": "updated_at",
+                        "value": "{cx.props.secret.updated_at}"
+                    }
+                    // The version of the vault key the new value is encrypted with.
+                    input {
+                        "type": "hidden",
+                        "name": "key_version",
+                        "value": "{cx.props.user_vault.key_version}"
+                    }
+                    input {
+                        "type": "hidden",
+                        "name": "service_account_secrets"
+                    }
+                    input {
+                        "type": "hidden",
+                        "data-vault-id": "{cx.props.secret.vault_id}",
+                        "data-environment-id": "{cx.props.secret.environment_id}",
+                        "data-cipher-name": "{cx.props.secret.name}",
+                        "data-encrypted-vault-key": "{cx.props.user_vault.encrypted_vault_key}",
+                        "data-ecdh-public-key": "{cx.props.user_vault.ecdh_public_key}",
+                        class: "secret-details"
+                    }
+                }
+            }
+            DrawerFooter {
+                Button {
+                    class: "update-secret",
+                    button_type: ButtonType::Submit,
+                    button_scheme: ButtonScheme::Primary,
+                    "Update Secret"
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,133 @@
+#![allow(non_snake_case)]
+use db::{Secret, SecretVersion, UserVault};
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+#[derive(Props, PartialEq)]
+pub struct DrawerProps<'a> {
+    organisation_id: i32,
+    secret: &'a Secret,
+    versions: Vec<&'a SecretVersion>,
+    user_vault: &'a UserVault,
+    trigger_id: String,
+}
+
+// The versions come newest first, so the first one is the current value.
+pub fn SecretHistoryDrawer<'a>(cx: Scope<'a, DrawerProps<'a>>) -> Element {
+    let current_version = cx.props.versions.first().map(|v| v.version);
+
+    cx.render(rsx! {
+        Drawer {
+            submit_action: crate::routes::secrets::rollback_route(cx.props.secret.vault_id, cx.props.organisation_id),
+            label: "Secret History",
+            trigger_id: &cx.props.trigger_id,
+            component_name: "rollback-secret",
+            DrawerBody {
+                div {
+                    class: "d-flex flex-column",
+                    Alert {
+                        alert_color: AlertColor::Default,
+                        class: "mb-3",
+                        "Rolling back restores an old value as a new version and updates
+                        any connected service accounts."
+                    }
+                    DataTable {
+                        table {
+                            thead {
+                                th { "Version" }
+                                th { "Value" }
+                                th { "Changed By" }
+                                th { "When" }
+                                th {
+                                    class: "text-right",
+                                    "Action"
+                                }
+                            }
+                            tbody {
+                                cx.props.versions.iter().map(|version| rsx!(
+                                    tr {
+                                        td {
+                                            "{version.version}"
+                                        }
+                                        td {
+                                            {LazyNodes::new(|f| f.text(format_args!(
+                                                "<ecdh-cipher cipher='{}'
+                                                wrapped-aes-key='{}' 
+                                                ecdh-public-key='{}'></ecdh-cipher>",
+                                                version.secret,
+                                                cx.props.user_vault.encrypted_vault_key,
+                                                cx.props.user_vault.ecdh_public_key
+                                            )))}
+                                        }
+                                        td {
+                                            if let Some(email) = &version.email {
+                                                cx.render(rsx!("{email}"))
+                                            } else {
+                                                cx.render(rsx!("Unknown"))
+                                            }
+                                        }
+                                        td {
+                                            RelativeTime {
+                                                format: RelativeTimeFormat::Relative,
+                                                datetime: &version.created_at
+                                            }
+                                        }
+                                        td {
+                                            class: "text-right",
+                                            if Some(version.version) == current_version {
+                                                cx.render(rsx!(
+                                                    Label {
+                                                        "Current"
+                                                    }
+                                                ))
+                                            } else {
+                                                cx.render(rsx!(
+                                                    button {
+                                                        class: "btn btn-sm",
+                                                        "type": "button",
+                                                        "data-version-id": "{version.id}",
+                                                        "data-cipher-name": "{version.name}",
+                                                        "data-cipher-value": "{version.secret}",
+                                                        "Rollback"
+                                                    }
+                                                ))
+                                            }
+                                        }
+                                    }
+                                ))
+                            }
+                        }
+                    }
+                    input {
+                        "type": "hidden",
+                        "name": "secret_id",
+                        "value": "{cx.props.secret.id}"
+                    }
+                    input {
+                        "type": "hidden",
+                        "name": "version_id"
+                    }
+                    input {
+                        "type": "hidden",
+                        "name": "service_account_secrets"
+                    }
+                    input {
+                        "type": "hidden",
+                        "data-vault-id": "{cx.props.secret.vault_id}",
+                        "data-environment-id": "{cx.props.secret.environment_id}",
+                        "data-name-blind-index": "{cx.props.secret.name_blind_index}",
+                        "data-encrypted-vault-key": "{cx.props.user_vault.encrypted_vault_key}",
+                        "data-ecdh-public-key": "{cx.props.user_vault.ecdh_public_key}",
+                        class: "secret-details"
+                    }
+                }
+            }
+            DrawerFooter {
+                span {
+                    class: "color-fg-muted",
+                    "Pick a version to roll back to"
+                }
+            }
+        }
+    })
+}
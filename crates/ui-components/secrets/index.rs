@@ -1,6 +1,6 @@
 use crate::cloak_layout::{CloakLayout, SideBar};
 use assets::files::button_plus_svg;
-use db::{Environment, Secret, UserVault};
+use db::{Environment, Secret, SecretVersion, UserVault};
 use dioxus::prelude::*;
 use primer_rsx::*;
 
@@ -10,6 +10,7 @@ struct SecretProps {
     user_vault: UserVault,
     env_secrets: Vec<(Environment, Vec<Secret>)>,
     secrets: Vec<Secret>,
+    versions: Vec<SecretVersion>,
     environments: Vec<Environment>,
 }
 
@@ -18,6 +19,7 @@ pub fn index(
     user_vault: UserVault,
     environments: Vec<Environment>,
     secrets: Vec<Secret>,
+    versions: Vec<SecretVersion>,
 ) -> String {
     fn app(cx: Scope<SecretProps>) -> Element {
         if cx.props.secrets.is_empty() {
@@ -72,6 +74,7 @@ pub fn index(
                             super::table::SecretsTable {
                                 user_vault: cx.props.user_vault.clone(),
                                 secrets: cx.props.secrets.clone(),
+                                versions: cx.props.versions.clone(),
                                 organisation_id: cx.props.organisation_id
                             }
                         }
@@ -82,6 +85,7 @@ pub fn index(
                                 super::table::SecretsTable {
                                     user_vault: cx.props.user_vault.clone(),
                                     secrets: env_secrets.1.clone(),
+                                    versions: cx.props.versions.clone(),
                                     organisation_id: cx.props.organisation_id,
                                     environment: env_secrets.0
                                 }
@@ -121,6 +125,7 @@ pub fn index(
             user_vault,
             env_secrets,
             secrets,
+            versions,
             environments,
         },
     );
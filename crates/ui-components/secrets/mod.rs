@@ -1,5 +1,7 @@
 pub mod delete;
+pub mod edit;
 pub mod empty;
+pub mod history;
 pub mod index;
 pub mod new_secret;
 pub mod table;
@@ -82,6 +82,13 @@ pub fn NewSecretForm(cx: Scope<NewSecretFormProps>) -> Element {
                             id: "vault-id",
                             value: "{cx.props.user_vault.vault_id}"
                         }
+                        // The version of the key above, so the server can tell if
+                        // it has been rotated since the page loaded.
+                        input {
+                            "type": "hidden",
+                            name: "key_version",
+                            value: "{cx.props.user_vault.key_version}"
+                        }
                         input {
                             "type": "hidden",
                             id: "name-blind-index",
@@ -1,5 +1,5 @@
 #![allow(non_snake_case)]
-use db::{Environment, Secret, UserVault};
+use db::{Environment, Secret, SecretVersion, UserVault};
 use dioxus::prelude::*;
 use primer_rsx::*;
 
@@ -8,6 +8,7 @@ pub struct TableProps {
     organisation_id: i32,
     environment: Option<Environment>,
     secrets: Vec<Secret>,
+    versions: Vec<SecretVersion>,
     user_vault: UserVault,
 }
 
@@ -58,6 +59,16 @@ pub fn SecretsTable(cx: Scope<TableProps>) -> Element {
                                 DropDown {
                                     direction: Direction::SouthWest,
                                     button_text: "...",
+                                    DropDownLink {
+                                        drawer_trigger: format!("edit-secret-trigger-{}", secret.id),
+                                        href: "#",
+                                        "Edit Secret"
+                                    }
+                                    DropDownLink {
+                                        drawer_trigger: format!("secret-history-trigger-{}", secret.id),
+                                        href: "#",
+                                        "View History"
+                                    }
                                     DropDownLink {
                                         drawer_trigger: format!("delete-secret-trigger-{}", secret.id),
                                         href: "#",
@@ -81,5 +92,28 @@ pub fn SecretsTable(cx: Scope<TableProps>) -> Element {
                 }
             ))
         })
+        // The edit drawers
+        cx.props.secrets.iter().map(|secret| {
+            cx.render(rsx!(
+                super::edit::EditSecretDrawer {
+                    organisation_id: cx.props.organisation_id,
+                    user_vault: &cx.props.user_vault,
+                    secret: secret,
+                    trigger_id: format!("edit-secret-trigger-{}", secret.id),
+                }
+            ))
+        })
+        // And the history drawers
+        cx.props.secrets.iter().map(|secret| {
+            cx.render(rsx!(
+                super::history::SecretHistoryDrawer {
+                    organisation_id: cx.props.organisation_id,
+                    user_vault: &cx.props.user_vault,
+                    secret: secret,
+                    versions: cx.props.versions.iter().filter(|v| v.secret_id == secret.id).collect(),
+                    trigger_id: format!("secret-history-trigger-{}", secret.id),
+                }
+            ))
+        })
     ))
 }
@@ -2,5 +2,6 @@ pub mod connect_account;
 pub mod delete;
 pub mod index;
 pub mod new_account;
+pub mod rotate_key;
 pub mod table;
 pub mod view_account;
@@ -0,0 +1,121 @@
+#![allow(non_snake_case)]
+#![allow(unused_braces)]
+use db::ServiceAccount;
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+#[derive(Props, PartialEq)]
+pub struct RotateKeyFormProps<'a> {
+    submit_action: String,
+    drawer_trigger: String,
+    service_account: &'a ServiceAccount,
+}
+
+pub fn RotateKeyForm<'a>(cx: Scope<'a, RotateKeyFormProps<'a>>) -> Element {
+    let service_account = cx.props.service_account;
+    // The browser needs these to fetch the secrets, blank if not connected.
+    let vault_id = service_account
+        .vault_id
+        .map(|id| id.to_string())
+        .unwrap_or_default();
+    let environment_id = service_account
+        .environment_id
+        .map(|id| id.to_string())
+        .unwrap_or_default();
+
+    cx.render(rsx! {
+        form {
+            id: "rotate-account-key-form-{service_account.id}",
+            method: "post",
+            action: "{cx.props.submit_action}",
+            Drawer {
+                label: "Rotate Key",
+                trigger_id: "{cx.props.drawer_trigger}",
+                component_name: "rotate-account-key",
+                DrawerBody {
+                    div {
+                        class: "d-flex flex-column",
+                        Alert {
+                            alert_color: AlertColor::Warn,
+                            class: "mb-3",
+                            "A new key will be created for '{service_account.account_name}'
+                            and its secrets encrypted for it. Download the new private key
+                            and give it to the service before the old one retires."
+                        }
+                        Select {
+                            name: "grace_hours",
+                            label: "How long should the old key keep working?",
+                            help_text: "When this is over the old key and its copy of the secrets are removed",
+                            option {
+                                value: "0",
+                                "Retire it now"
+                            }
+                            option {
+                                value: "1",
+                                "1 hour"
+                            }
+                            option {
+                                value: "24",
+                                selected: true,
+                                "1 day"
+                            }
+                            option {
+                                value: "168",
+                                "1 week"
+                            }
+                            option {
+                                value: "720",
+                                "30 days"
+                            }
+                        }
+                        input {
+                            "type": "hidden",
+                            class: "rotate-service-account-id",
+                            name: "service_account_id",
+                            value: "{service_account.id}"
+                        }
+                        input {
+                            "type": "hidden",
+                            class: "rotate-vault-id",
+                            value: "{vault_id}"
+                        }
+                        input {
+                            "type": "hidden",
+                            class: "rotate-environment-id",
+                            value: "{environment_id}"
+                        }
+                        //  These hidden fields are populated by the rotate-account-key.ts
+                        input {
+                            "type": "hidden",
+                            class: "rotate-public-key",
+                            name: "public_key"
+                        }
+                        input {
+                            "type": "hidden",
+                            class: "rotate-encrypted-private-key",
+                            name: "encrypted_private_key"
+                        }
+                        input {
+                            "type": "hidden",
+                            class: "rotate-public-ecdh-key",
+                            name: "public_ecdh_key"
+                        }
+                        input {
+                            "type": "hidden",
+                            class: "rotate-secrets",
+                            name: "secrets"
+                        }
+                    }
+                }
+                DrawerFooter {
+                    Button {
+                        id: "rotate-account-key-button-{service_account.id}",
+                        button_type: ButtonType::Submit,
+                        button_scheme: ButtonScheme::Danger,
+                        "Rotate Key"
+                    }
+                }
+            }
+        }
+    })
+}
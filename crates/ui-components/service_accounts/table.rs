@@ -86,6 +86,21 @@ pub fn ServiceAccountTable(cx: Scope<TableProps>) -> Element {
                                             format: RelativeTimeFormat::Datetime,
                                             datetime: &service_account.updated_at
                                         }
+                                        if let Some(expires_at) = &service_account.previous_key_expires_at {
+                                            cx.render(rsx!(
+                                                Label {
+                                                    class: "ml-2 mr-1",
+                                                    label_color: LabelColor::Attention,
+                                                    "Old key retires"
+                                                }
+                                                RelativeTime {
+                                                    format: RelativeTimeFormat::Relative,
+                                                    datetime: expires_at
+                                                }
+                                            ))
+                                        } else {
+                                            None
+                                        }
                                     }
                                     td {
                                         RelativeTime {
@@ -98,6 +113,19 @@ pub fn ServiceAccountTable(cx: Scope<TableProps>) -> Element {
                                         DropDown {
                                             direction: Direction::SouthWest,
                                             button_text: "...",
+                                            // Only one key can be retiring at a time.
+                                            if service_account.previous_key_expires_at.is_none() {
+                                                cx.render(rsx!(
+                                                    DropDownLink {
+                                                        drawer_trigger: format!("sa-rotate-trigger-{}",
+                                                            service_account.id),
+                                                        href: "#",
+                                                        "Rotate Key"
+                                                    }
+                                                ))
+                                            } else {
+                                                None
+                                            }
                                             DropDownLink {
                                                 drawer_trigger: format!("sa-delete-trigger-{}", 
                                                     service_account.id),
@@ -124,6 +152,11 @@ pub fn ServiceAccountTable(cx: Scope<TableProps>) -> Element {
                     drawer_trigger: "service-account-view-{sa.id}",
                     service_account: sa
                 }
+                super::rotate_key::RotateKeyForm {
+                    submit_action: crate::routes::service_accounts::rotate_key_route(cx.props.team_id),
+                    drawer_trigger: format!("sa-rotate-trigger-{}", sa.id),
+                    service_account: sa
+                }
             ))
         })
     ))
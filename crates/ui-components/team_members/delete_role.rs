@@ -0,0 +1,50 @@
+#![allow(non_snake_case)]
+use db::TeamRole;
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+#[derive(Props, PartialEq)]
+pub struct DrawerProps<'a> {
+    organisation_id: i32,
+    team_role: &'a TeamRole,
+    trigger_id: String,
+}
+
+pub fn DeleteRoleDrawer<'a>(cx: Scope<'a, DrawerProps<'a>>) -> Element {
+    cx.render(rsx! {
+        Drawer {
+            submit_action: crate::routes::team::delete_role_route(cx.props.organisation_id),
+            label: "Delete Role ?",
+            trigger_id: &cx.props.trigger_id,
+            DrawerBody {
+                div {
+                    class: "d-flex flex-column",
+                    Alert {
+                        alert_color: AlertColor::Warn,
+                        class: "mb-3",
+                        h4 {
+                            "Are you sure you want to delete "
+                            strong {
+                                "{cx.props.team_role.name}"
+                            }
+                            "?"
+                        }
+                        "Roles can only be deleted once no members or invitations have them."
+                    }
+                    input {
+                        "type": "hidden",
+                        "name": "team_role_id",
+                        "value": "{cx.props.team_role.id}"
+                    }
+                }
+            }
+            DrawerFooter {
+                Button {
+                    button_type: ButtonType::Submit,
+                    button_scheme: ButtonScheme::Danger,
+                    "Delete Role"
+                }
+            }
+        }
+    })
+}
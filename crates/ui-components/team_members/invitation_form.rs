@@ -1,13 +1,15 @@
 #![allow(non_snake_case)]
+use db::TeamRole;
 use dioxus::prelude::*;
 use primer_rsx::*;
 
-#[derive(Props, PartialEq, Eq)]
-pub struct InvitationFormProps {
+#[derive(Props, PartialEq)]
+pub struct InvitationFormProps<'a> {
     submit_action: String,
+    team_roles: &'a Vec<TeamRole>,
 }
 
-pub fn InvitationForm(cx: Scope<InvitationFormProps>) -> Element {
+pub fn InvitationForm<'a>(cx: Scope<'a, InvitationFormProps<'a>>) -> Element {
     cx.render(rsx! {
         // The form to create an invitation
         form {
@@ -40,39 +42,33 @@ pub fn InvitationForm(cx: Scope<InvitationFormProps>) -> Element {
                             label: "Last Name",
                             name: "last_name"
                         }
-                        Alert {
-                            alert_color: AlertColor::Success,
-                            class: "mb-3",
-                            label {
-                                input {
-                                    "type": "checkbox",
-                                    name: "admin"
-                                }
-                                strong {
-                                    class: "ml-2",
-                                    "Invite as Team Administrator"
-                                }
+                        Select {
+                            label: "Role",
+                            help_text: "What they will be able to do in the team",
+                            name: "role",
+                            value: "Collaborator",
+                            option {
+                                value: "Collaborator",
+                                "Collaborator"
                             }
-                            p {
-                                class: "note",
-                                "Team Administrators can invite new team members"
+                            option {
+                                value: "Administrator",
+                                "Administrator"
                             }
+                            cx.props.team_roles.iter().map(|team_role| rsx!(
+                                option {
+                                    value: "{team_role.id}",
+                                    "{team_role.name}"
+                                }
+                            ))
                         }
                         Alert {
                             alert_color: AlertColor::Success,
-                            label {
-                                input {
-                                    "type": "checkbox",
-                                    name: "negotiator"
-                                }
-                                strong {
-                                    class: "ml-2",
-                                    "Invite as Negotiator"
-                                }
-                            }
+                            class: "mb-3",
                             p {
                                 class: "note",
-                                "Negotiators can handle high interest events."
+                                "Administrators can manage the team and invite new team members.
+                                Collaborators can create vaults and service accounts and see the audit trail."
                             }
                         }
                     }
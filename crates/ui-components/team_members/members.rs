@@ -1,7 +1,7 @@
 #![allow(non_snake_case)]
 use crate::cloak_layout::{CloakLayout, SideBar};
 use assets::files::button_plus_svg;
-use db::{Invitation, Member, Organisation, User};
+use db::{Invitation, Member, Organisation, TeamRole, User};
 use dioxus::prelude::*;
 use primer_rsx::*;
 
@@ -10,7 +10,9 @@ struct MembersProps {
     invites: Vec<Invitation>,
     organisation: Organisation,
     user: User,
+    team_roles: Vec<TeamRole>,
     can_manage_team: bool,
+    can_invite_members: bool,
     submit_action: String,
     team_name: String,
     profile_link: String,
@@ -22,7 +24,9 @@ pub fn members(
     members: Vec<Member>,
     organisation: Organisation,
     user: User,
+    team_roles: Vec<TeamRole>,
     can_manage_team: bool,
+    can_invite_members: bool,
 ) -> String {
     fn app(cx: Scope<MembersProps>) -> Element {
         cx.render(rsx! {
@@ -32,11 +36,17 @@ pub fn members(
                 title: "Team Members"
                 header: cx.render(rsx!(
                     h3 { "Team Members" }
-                    Button {
-                        prefix_image_src: "{button_plus_svg.name}",
-                        drawer_trigger: "create-invite-form",
-                        button_scheme: ButtonScheme::Primary,
-                        "Invite New Team Member"
+                    if cx.props.can_invite_members {
+                        cx.render(rsx! {
+                            Button {
+                                prefix_image_src: "{button_plus_svg.name}",
+                                drawer_trigger: "create-invite-form",
+                                button_scheme: ButtonScheme::Primary,
+                                "Invite New Team Member"
+                            }
+                        })
+                    } else {
+                        None
                     }
                 ))
 
@@ -156,6 +166,15 @@ pub fn members(
                                                         }
                                                     ))
                                                 )
+                                                if let Some(team_role) = &member.team_role {
+                                                    cx.render(rsx!(
+                                                        super::team_role::TeamRole {
+                                                            name: team_role
+                                                        }
+                                                    ))
+                                                } else {
+                                                    None
+                                                }
                                             }
                                             if cx.props.can_manage_team {
                                                 cx.render(rsx!(
@@ -164,6 +183,13 @@ pub fn members(
                                                         DropDown {
                                                             direction: Direction::SouthWest,
                                                             button_text: "...",
+                                                            DropDownLink {
+                                                                drawer_trigger: format!("change-role-trigger-{}-{}",
+                                                                    member.id, member.organisation_id),
+                                                                href: "#",
+                                                                target: "_top",
+                                                                "Change Role"
+                                                            }
                                                             DropDownLink {
                                                                 drawer_trigger: format!("remove-member-trigger-{}-{}", 
                                                                     member.id, member.organisation_id),
@@ -206,6 +232,15 @@ pub fn members(
                                                         }
                                                     ))
                                                 )
+                                                if let Some(team_role) = &invite.team_role {
+                                                    cx.render(rsx!(
+                                                        super::team_role::TeamRole {
+                                                            name: team_role
+                                                        }
+                                                    ))
+                                                } else {
+                                                    None
+                                                }
                                             }
                                             if cx.props.can_manage_team {
                                                 cx.render(rsx!(
@@ -232,6 +267,23 @@ pub fn members(
                         }
                     }
                 }
+
+                super::team_ownership::TeamOwnership {
+                    organisation: &cx.props.organisation,
+                    members: &cx.props.members,
+                    user_id: cx.props.user.id
+                }
+
+                if cx.props.can_manage_team {
+                    cx.render(rsx! {
+                        super::roles::RolesTable {
+                            team_roles: &cx.props.team_roles,
+                            organisation_id: cx.props.organisation.id
+                        }
+                    })
+                } else {
+                    None
+                }
             }
 
             cx.props.members.iter().map(|member| rsx!(
@@ -246,9 +298,21 @@ pub fn members(
                 ))
             ))
 
+            cx.props.members.iter().map(|member| rsx!(
+                cx.render(rsx!(
+                    super::team_role::ChangeRoleDrawer {
+                        organisation_id: member.organisation_id,
+                        member: member,
+                        team_roles: &cx.props.team_roles,
+                        trigger_id: format!("change-role-trigger-{}-{}", member.id, member.organisation_id)
+                    }
+                ))
+            ))
+
             // The form to create an invitation
             super::invitation_form::InvitationForm {
-                submit_action: cx.props.submit_action.clone()
+                submit_action: cx.props.submit_action.clone(),
+                team_roles: &cx.props.team_roles
             }
 
             // Form to set he org name
@@ -275,7 +339,9 @@ pub fn members(
             invites,
             organisation,
             user,
+            team_roles,
             can_manage_team,
+            can_invite_members,
             submit_action,
             team_name,
             profile_link,
@@ -1,7 +1,11 @@
+pub mod delete_role;
 pub mod invitation_form;
 pub mod members;
 pub mod remove_member;
 pub mod remove_warning;
+pub mod role_form;
+pub mod roles;
 pub mod team_name_form;
+pub mod team_ownership;
 pub mod team_popup;
 pub mod team_role;
@@ -26,11 +26,6 @@ pub fn RemoveMemberDrawer(cx: Scope<DrawerProps>) -> Element {
                             "Are you sure you want to remove '{cx.props.email}' from the team?"
                         }
                     }
-                    input {
-                        "type": "hidden",
-                        "name": "organisation_id",
-                        "value": "{cx.props.organisation_id}"
-                    }
                     input {
                         "type": "hidden",
                         "name": "user_id",
@@ -0,0 +1,62 @@
+#![allow(non_snake_case)]
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+pub static DRAW_TRIGGER: &str = "new-role-drawer";
+
+#[derive(Props, PartialEq, Eq)]
+pub struct RoleFormProps {
+    submit_action: String,
+}
+
+pub fn RoleForm(cx: Scope<RoleFormProps>) -> Element {
+    cx.render(rsx! {
+        form {
+            method: "post",
+            action: "{cx.props.submit_action}",
+            Drawer {
+                label: "Create a role for your team",
+                trigger_id: DRAW_TRIGGER,
+                DrawerBody {
+                    div {
+                        class: "d-flex flex-column",
+                        Input {
+                            input_type: InputType::Text,
+                            help_text: "i.e. Auditor or Contractor"
+                            required: true,
+                            label: "Name",
+                            name: "name"
+                        }
+                        super::team_role::PERMISSIONS.iter().map(|(_, name, label, description)| rsx!(
+                            Alert {
+                                alert_color: AlertColor::Success,
+                                class: "mb-3",
+                                label {
+                                    input {
+                                        "type": "checkbox",
+                                        name: "{name}"
+                                    }
+                                    strong {
+                                        class: "ml-2",
+                                        "{label}"
+                                    }
+                                }
+                                p {
+                                    class: "note",
+                                    "{description}"
+                                }
+                            }
+                        ))
+                    }
+                }
+                DrawerFooter {
+                    Button {
+                        button_type: ButtonType::Submit,
+                        button_scheme: ButtonScheme::Primary,
+                        "Create Role"
+                    }
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,94 @@
+#![allow(non_snake_case)]
+use assets::files::button_plus_svg;
+use db::TeamRole;
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+#[derive(Props, PartialEq)]
+pub struct TableProps<'a> {
+    team_roles: &'a Vec<TeamRole>,
+    organisation_id: i32,
+}
+
+// The roles a team has made for itself, on top of Administrator and
+// Collaborator.
+pub fn RolesTable<'a>(cx: Scope<'a, TableProps<'a>>) -> Element {
+    cx.render(rsx!(
+        Box {
+            class: "mt-3",
+            BoxHeader {
+                title: "Team Roles",
+                Button {
+                    class: "ml-2",
+                    prefix_image_src: "{button_plus_svg.name}",
+                    drawer_trigger: super::role_form::DRAW_TRIGGER,
+                    button_size: ButtonSize::Small,
+                    "New Role"
+                }
+            }
+            BoxBody {
+                DataTable {
+                    table {
+                        thead {
+                            tr {
+                                th { "Name" }
+                                th { "Permissions" }
+                                th {
+                                    class: "text-right",
+                                    "Action"
+                                }
+                            }
+                        }
+                        tbody {
+                            cx.props.team_roles.iter().map(|team_role| rsx!(
+                                tr {
+                                    td {
+                                        strong {
+                                            "{team_role.name}"
+                                        }
+                                    }
+                                    td {
+                                        team_role.permissions.iter().map(|permission|
+                                            cx.render(rsx!(
+                                                super::team_role::Permission {
+                                                    permission: permission
+                                                }
+                                            ))
+                                        )
+                                    }
+                                    td {
+                                        class: "text-right",
+                                        DropDown {
+                                            direction: Direction::SouthWest,
+                                            button_text: "...",
+                                            DropDownLink {
+                                                drawer_trigger: format!("delete-role-trigger-{}", team_role.id),
+                                                href: "#",
+                                                target: "_top",
+                                                "Delete Role"
+                                            }
+                                        }
+                                    }
+                                }
+                            ))
+                        }
+                    }
+                }
+                // Create all the delete drawers
+                cx.props.team_roles.iter().map(|team_role| {
+                    cx.render(rsx!(
+                        super::delete_role::DeleteRoleDrawer {
+                            organisation_id: cx.props.organisation_id,
+                            team_role: team_role,
+                            trigger_id: format!("delete-role-trigger-{}", team_role.id),
+                        }
+                    ))
+                })
+            }
+        }
+
+        super::role_form::RoleForm {
+            submit_action: crate::routes::team::new_role_route(cx.props.organisation_id)
+        }
+    ))
+}
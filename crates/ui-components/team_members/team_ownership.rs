@@ -0,0 +1,192 @@
+#![allow(non_snake_case)]
+use db::types::public::Role;
+use db::{Member, Organisation};
+use dioxus::prelude::*;
+use primer_rsx::*;
+
+pub static TRANSFER_TRIGGER: &str = "transfer-team-drawer";
+pub static DELETE_TRIGGER: &str = "delete-team-drawer";
+pub static LEAVE_TRIGGER: &str = "leave-team-drawer";
+
+#[derive(Props, PartialEq)]
+pub struct OwnershipProps<'a> {
+    organisation: &'a Organisation,
+    members: &'a Vec<Member>,
+    user_id: i32,
+}
+
+// The owner can hand the team over or delete it, everyone else can leave.
+pub fn TeamOwnership<'a>(cx: Scope<'a, OwnershipProps<'a>>) -> Element {
+    let organisation = cx.props.organisation;
+
+    if organisation.created_by_user_id == cx.props.user_id {
+        cx.render(rsx! {
+            Box {
+                class: "mt-3",
+                BoxHeader {
+                    title: "Danger Zone"
+                }
+                BoxBody {
+                    p {
+                        "You own this team. Transfer it to another Administrator before you leave."
+                    }
+                    Button {
+                        drawer_trigger: TRANSFER_TRIGGER,
+                        "Transfer Ownership"
+                    }
+                    Button {
+                        class: "ml-2",
+                        drawer_trigger: DELETE_TRIGGER,
+                        button_scheme: ButtonScheme::Danger,
+                        "Delete Team"
+                    }
+                }
+            }
+
+            TransferDrawer {
+                organisation_id: organisation.id,
+                members: cx.props.members,
+                user_id: cx.props.user_id
+            }
+
+            DeleteTeamDrawer {
+                organisation_id: organisation.id
+            }
+        })
+    } else {
+        cx.render(rsx! {
+            Box {
+                class: "mt-3",
+                BoxHeader {
+                    title: "Danger Zone"
+                }
+                BoxBody {
+                    Button {
+                        drawer_trigger: LEAVE_TRIGGER,
+                        button_scheme: ButtonScheme::Danger,
+                        "Leave Team"
+                    }
+                }
+            }
+
+            LeaveDrawer {
+                organisation_id: organisation.id
+            }
+        })
+    }
+}
+
+#[derive(Props, PartialEq)]
+struct TransferProps<'a> {
+    organisation_id: i32,
+    members: &'a Vec<Member>,
+    user_id: i32,
+}
+
+fn TransferDrawer<'a>(cx: Scope<'a, TransferProps<'a>>) -> Element {
+    cx.render(rsx! {
+        Drawer {
+            submit_action: crate::routes::team::transfer_route(cx.props.organisation_id),
+            label: "Transfer Ownership",
+            trigger_id: TRANSFER_TRIGGER,
+            DrawerBody {
+                div {
+                    class: "d-flex flex-column",
+                    Select {
+                        label: "New Owner",
+                        help_text: "Only Administrators can own a team",
+                        name: "user_id",
+                        cx.props.members.iter()
+                            .filter(|member| member.id != cx.props.user_id)
+                            .filter(|member| member.roles.contains(&Role::Administrator))
+                            .map(|member| rsx!(
+                                option {
+                                    value: "{member.id}",
+                                    "{member.email}"
+                                }
+                            ))
+                    }
+                }
+            }
+            DrawerFooter {
+                Button {
+                    button_type: ButtonType::Submit,
+                    button_scheme: ButtonScheme::Danger,
+                    "Transfer Team"
+                }
+            }
+        }
+    })
+}
+
+#[derive(Props, PartialEq, Eq)]
+struct TeamProps {
+    organisation_id: i32,
+}
+
+fn DeleteTeamDrawer(cx: Scope<TeamProps>) -> Element {
+    cx.render(rsx! {
+        Drawer {
+            submit_action: crate::routes::team::delete_team_route(cx.props.organisation_id),
+            label: "Delete Team ?",
+            trigger_id: DELETE_TRIGGER,
+            DrawerBody {
+                div {
+                    class: "d-flex flex-column",
+                    Alert {
+                        alert_color: AlertColor::Warn,
+                        class: "mb-3",
+                        h4 {
+                            "This can't be undone"
+                        }
+                        "The team's vaults, secrets, service accounts and audit trail will all be deleted."
+                    }
+                    Input {
+                        input_type: InputType::Text,
+                        help_text: "Type the name of the team to confirm",
+                        required: true,
+                        label: "Team Name",
+                        name: "name"
+                    }
+                }
+            }
+            DrawerFooter {
+                Button {
+                    button_type: ButtonType::Submit,
+                    button_scheme: ButtonScheme::Danger,
+                    "Delete Team"
+                }
+            }
+        }
+    })
+}
+
+fn LeaveDrawer(cx: Scope<TeamProps>) -> Element {
+    cx.render(rsx! {
+        Drawer {
+            submit_action: crate::routes::team::leave_route(cx.props.organisation_id),
+            label: "Leave Team ?",
+            trigger_id: LEAVE_TRIGGER,
+            DrawerBody {
+                div {
+                    class: "d-flex flex-column",
+                    Alert {
+                        alert_color: AlertColor::Warn,
+                        class: "mb-3",
+                        h4 {
+                            "Are you sure you want to leave this team?"
+                        }
+                        "You'll lose access to all of the team's vaults."
+                    }
+                }
+            }
+            DrawerFooter {
+                Button {
+                    button_type: ButtonType::Submit,
+                    button_scheme: ButtonScheme::Danger,
+                    "Leave Team"
+                }
+            }
+        }
+    })
+}
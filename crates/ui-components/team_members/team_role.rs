@@ -1,5 +1,5 @@
 #![allow(non_snake_case)]
-use db::Role;
+use db::{Member, Permission, Role, TeamRole};
 use dioxus::prelude::*;
 use primer_rsx::*;
 
@@ -34,3 +34,178 @@ pub fn Role<'a>(cx: Scope<'a, RoleProps<'a>>) -> Element {
         )),
     }
 }
+
+#[derive(Props, PartialEq, Eq)]
+pub struct TeamRoleProps<'a> {
+    pub name: &'a String,
+}
+
+// One of the roles the team has made for itself.
+pub fn TeamRole<'a>(cx: Scope<'a, TeamRoleProps<'a>>) -> Element {
+    cx.render(rsx!(
+        Label {
+            class: "mr-2",
+            label_color: LabelColor::Accent,
+            "{cx.props.name}"
+        }
+    ))
+}
+
+// The form field, label and explanation for each permission a team role
+// can have.
+pub const PERMISSIONS: &[(Permission, &str, &str, &str)] = &[
+    (
+        Permission::ManageTeam,
+        "manage_team",
+        "Manage Team",
+        "Rename the team, remove members and manage roles",
+    ),
+    (
+        Permission::InviteMembers,
+        "invite_members",
+        "Invite Members",
+        "Invite new people into the team",
+    ),
+    (
+        Permission::CreateVault,
+        "create_vault",
+        "Create Vaults",
+        "Create new vaults",
+    ),
+    (
+        Permission::DeleteVault,
+        "delete_vault",
+        "Delete Vaults",
+        "Delete the vaults they are a member of",
+    ),
+    (
+        Permission::ManageServiceAccounts,
+        "manage_service_accounts",
+        "Manage Service Accounts",
+        "Create, connect and delete service accounts",
+    ),
+    (
+        Permission::ViewAudit,
+        "view_audit",
+        "View Audit Trail",
+        "See and export the audit trail",
+    ),
+];
+
+#[derive(Props, PartialEq, Eq)]
+pub struct PermissionProps<'a> {
+    pub permission: &'a Permission,
+}
+
+pub fn Permission<'a>(cx: Scope<'a, PermissionProps<'a>>) -> Element {
+    let label = PERMISSIONS
+        .iter()
+        .find(|(permission, ..)| permission == cx.props.permission)
+        .map(|(_, _, label, _)| *label)
+        .unwrap_or_default();
+    cx.render(rsx!(
+        Label {
+            class: "mr-2",
+            "{label}"
+        }
+    ))
+}
+
+#[derive(Props, PartialEq)]
+pub struct ChangeRoleProps<'a> {
+    organisation_id: i32,
+    member: &'a Member,
+    team_roles: &'a Vec<TeamRole>,
+    trigger_id: String,
+}
+
+pub fn ChangeRoleDrawer<'a>(cx: Scope<'a, ChangeRoleProps<'a>>) -> Element {
+    let member = cx.props.member;
+    // The same values the invitation form uses.
+    let current = if let Some(team_role_id) = member.team_role_id {
+        team_role_id.to_string()
+    } else if member.roles.contains(&Role::Administrator) {
+        "Administrator".to_string()
+    } else {
+        "Collaborator".to_string()
+    };
+
+    cx.render(rsx! {
+        Drawer {
+            submit_action: crate::routes::team::set_role_route(cx.props.organisation_id),
+            label: "Change Role",
+            trigger_id: &cx.props.trigger_id,
+            DrawerBody {
+                div {
+                    class: "d-flex flex-column",
+                    Select {
+                        label: "Role",
+                        help_text: "What they will be able to do in the team",
+                        name: "role",
+                        RoleOption {
+                            value: "Collaborator".to_string(),
+                            label: "Collaborator".to_string(),
+                            selected: current == "Collaborator"
+                        }
+                        RoleOption {
+                            value: "Administrator".to_string(),
+                            label: "Administrator".to_string(),
+                            selected: current == "Administrator"
+                        }
+                        cx.props.team_roles.iter().map(|team_role| rsx!(
+                            RoleOption {
+                                value: team_role.id.to_string(),
+                                label: team_role.name.clone(),
+                                selected: current == team_role.id.to_string()
+                            }
+                        ))
+                    }
+                    Alert {
+                        alert_color: AlertColor::Warn,
+                        class: "mb-3",
+                        "Every team needs at least one Administrator."
+                    }
+                    input {
+                        "type": "hidden",
+                        "name": "user_id",
+                        "value": "{member.id}"
+                    }
+                }
+            }
+            DrawerFooter {
+                Button {
+                    button_type: ButtonType::Submit,
+                    button_scheme: ButtonScheme::Primary,
+                    "Change Role"
+                }
+            }
+        }
+    })
+}
+
+#[derive(Props, PartialEq, Eq)]
+struct RoleOptionProps {
+    value: String,
+    label: String,
+    selected: bool,
+}
+
+// Shows the role the member has now when the drawer opens.
+fn RoleOption(cx: Scope<RoleOptionProps>) -> Element {
+    if cx.props.selected {
+        cx.render(rsx! {
+            option {
+                value: "{cx.props.value}",
+                selected: "selected",
+                "{cx.props.label}"
+            }
+        })
+    } else {
+        cx.render(rsx! {
+            option {
+                value: "{cx.props.value}",
+                "{cx.props.label}"
+            }
+        })
+    }
+}
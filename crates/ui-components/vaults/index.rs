@@ -13,6 +13,7 @@ pub struct VaultSummary {
     pub href: String,
     pub updated_at: String,
     pub created_at: String,
+    pub key_rotation_needed: bool,
 }
 
 #[derive(Props, PartialEq)]
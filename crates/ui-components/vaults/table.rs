@@ -40,6 +40,17 @@ pub fn VaultTable<'a>(cx: Scope<'a, TableProps<'a>>) -> Element {
                                                 "{vault.name}"
                                             }
                                         }
+                                        if vault.key_rotation_needed {
+                                            cx.render(rsx!(
+                                                Label {
+                                                    class: "ml-2",
+                                                    label_color: LabelColor::Danger,
+                                                    "Key Rotation Needed"
+                                                }
+                                            ))
+                                        } else {
+                                            None
+                                        }
                                     }
                                     td {
                                         RelativeTime {